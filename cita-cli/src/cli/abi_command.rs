@@ -1,6 +1,7 @@
 use clap::{App, Arg, ArgMatches, SubCommand};
 use serde_json::{self, Value};
 
+use crate::cli::CliError;
 use crate::interactive::GlobalConfig;
 use crate::printer::Printer;
 use cita_tool::{decode_input, decode_logs, decode_params, encode_input, encode_params, remove_0x};
@@ -131,7 +132,7 @@ pub fn abi_processor(
     sub_matches: &ArgMatches,
     printer: &Printer,
     config: &GlobalConfig,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let is_color = !sub_matches.is_present("no-color") && config.color();
     match sub_matches.subcommand() {
         ("encode", Some(em)) => match em.subcommand() {
@@ -144,8 +145,7 @@ pub fn abi_processor(
                     None => Vec::new(),
                     Some(param) => param.map(ToOwned::to_owned).collect::<Vec<String>>(),
                 };
-                let output = encode_input(file, abi, name, &values, lenient, false)
-                    .map_err(|err| format!("{}", err))?;
+                let output = encode_input(file, abi, name, &values, lenient, false)?;
                 printer.println(&Value::String(output), is_color);
             }
             ("params", Some(m)) => {
@@ -160,8 +160,7 @@ pub fn abi_processor(
                     types.push(param_iter.next().unwrap().to_owned());
                     values.push(param_iter.next().unwrap().to_owned());
                 }
-                let output =
-                    encode_params(&types, &values, lenient).map_err(|err| format!("{}", err))?;
+                let output = encode_params(&types, &values, lenient)?;
                 printer.println(&Value::String(output), is_color);
             }
             ("constructor", Some(m)) => {
@@ -173,12 +172,11 @@ pub fn abi_processor(
                     None => Vec::new(),
                     Some(param) => param.map(ToOwned::to_owned).collect::<Vec<String>>(),
                 };
-                let output = encode_input(file, abi, code, &values, lenient, true)
-                    .map_err(|err| format!("{}", err))?;
+                let output = encode_input(file, abi, code, &values, lenient, true)?;
                 printer.println(&Value::String(output), is_color);
             }
             _ => {
-                return Err(em.usage().to_owned());
+                return Err(em.usage().into());
             }
         },
         ("decode", Some(em)) => match em.subcommand() {
@@ -189,8 +187,7 @@ pub fn abi_processor(
                     .map(ToOwned::to_owned)
                     .collect();
                 let data = remove_0x(m.value_of("data").unwrap());
-                let output = decode_params(&types, data)
-                    .map_err(|err| err.to_string())?
+                let output = decode_params(&types, data)?
                     .iter()
                     .map(|value| serde_json::from_str(value).unwrap())
                     .collect();
@@ -201,8 +198,7 @@ pub fn abi_processor(
                 let abi = m.value_of("abi");
                 let name = m.value_of("name").unwrap();
                 let values = m.value_of("data").unwrap();
-                let output = decode_input(file, abi, name, values)
-                    .map_err(|err| format!("{}", err))?
+                let output = decode_input(file, abi, name, values)?
                     .iter()
                     .map(|value| serde_json::from_str(value).unwrap())
                     .collect();
@@ -217,19 +213,18 @@ pub fn abi_processor(
                     Some(param) => param.map(ToOwned::to_owned).collect::<Vec<String>>(),
                 };
                 let data = m.value_of("data").unwrap();
-                let output = decode_logs(file, abi, event, &topic, data)
-                    .map_err(|err| format!("{}", err))?
+                let output = decode_logs(file, abi, event, &topic, data)?
                     .iter()
                     .map(|value| serde_json::from_str(value).unwrap())
                     .collect();
                 printer.println(&Value::Array(output), is_color);
             }
             _ => {
-                return Err(em.usage().to_owned());
+                return Err(em.usage().into());
             }
         },
         _ => {
-            return Err(sub_matches.usage().to_owned());
+            return Err(sub_matches.usage().into());
         }
     }
     Ok(())
@@ -4,8 +4,8 @@ use cita_tool::client::basic::{AmendExt, Client};
 use cita_tool::remove_0x;
 
 use crate::cli::{
-    encryption, get_url, h256_validator, key_validator, parse_address, parse_privkey, parse_u256,
-    parse_u64,
+    check_response, encryption, get_url, h256_validator, key_validator, parse_address, parse_u256,
+    parse_u64, set_signing_key, wait_args, wait_receipt, CliError,
 };
 use crate::interactive::{set_output, GlobalConfig};
 use crate::printer::Printer;
@@ -27,9 +27,8 @@ pub fn amend_command() -> App<'static, 'static> {
         Arg::with_name("admin-private-key")
             .long("admin-private-key")
             .takes_value(true)
-            .required(true)
             .validator(|privkey| key_validator(privkey.as_ref()).map(|_| ()))
-            .help("The private key of super admin"),
+            .help("The private key of super admin, not needed with --signer"),
         Arg::with_name("quota")
             .long("quota")
             .takes_value(true)
@@ -56,7 +55,8 @@ pub fn amend_command() -> App<'static, 'static> {
                         .required(true)
                         .help("The contract code to amend"),
                 )
-                .args(&common_args),
+                .args(&common_args)
+                .args(&wait_args()),
         )
         .subcommand(
             SubCommand::with_name("abi")
@@ -83,7 +83,8 @@ pub fn amend_command() -> App<'static, 'static> {
                         .help("The path of ABI json file to amend (.json)"),
                 )
                 .group(ArgGroup::with_name("the-abi").args(&["content", "path"]))
-                .args(&common_args),
+                .args(&common_args)
+                .args(&wait_args()),
         )
         .subcommand(
             SubCommand::with_name("set-h256")
@@ -106,7 +107,8 @@ pub fn amend_command() -> App<'static, 'static> {
                         .validator(|kv| h256_validator(kv.as_str()))
                         .help("The key value pair"),
                 )
-                .args(&common_args),
+                .args(&common_args)
+                .args(&wait_args()),
         )
         .subcommand(
             SubCommand::with_name("balance")
@@ -127,7 +129,8 @@ pub fn amend_command() -> App<'static, 'static> {
                         .validator(|value| parse_u256(value.as_ref()).map(|_| ()))
                         .help("Account balance"),
                 )
-                .args(&common_args),
+                .args(&common_args)
+                .args(&wait_args()),
         )
 }
 
@@ -137,7 +140,7 @@ pub fn amend_processor(
     printer: &Printer,
     config: &mut GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let debug = sub_matches.is_present("debug") || config.debug();
     let mut client = client
         .set_debug(debug)
@@ -146,9 +149,7 @@ pub fn amend_processor(
     let result = match sub_matches.subcommand() {
         ("code", Some(m)) => {
             let encryption = encryption(m, config);
-            if let Some(private_key) = m.value_of("admin-private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("admin-private-key"), encryption)?;
             let address = m.value_of("address").unwrap();
             let content = m.value_of("content").unwrap();
             let quota = m.value_of("quota").map(|s| parse_u64(s).unwrap());
@@ -156,17 +157,14 @@ pub fn amend_processor(
         }
         ("abi", Some(m)) => {
             let encryption = encryption(m, config);
-            if let Some(private_key) = m.value_of("admin-private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("admin-private-key"), encryption)?;
             let content = match m.value_of("content") {
                 Some(content) => content.to_owned(),
                 None => {
                     let mut abi_content = String::new();
                     let path = m.value_of("path").unwrap();
-                    let mut file = fs::File::open(path).map_err(|err| format!("{}", err))?;
-                    file.read_to_string(&mut abi_content)
-                        .map_err(|err| format!("{}", err))?;
+                    let mut file = fs::File::open(path)?;
+                    file.read_to_string(&mut abi_content)?;
                     abi_content
                 }
             };
@@ -176,9 +174,7 @@ pub fn amend_processor(
         }
         ("set-h256", Some(m)) => {
             let encryption = encryption(m, config);
-            if let Some(private_key) = m.value_of("admin-private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("admin-private-key"), encryption)?;
             let address = m.value_of("address").unwrap();
             let h256_kv = m
                 .values_of("kv")
@@ -191,9 +187,7 @@ pub fn amend_processor(
         }
         ("balance", Some(m)) => {
             let encryption = encryption(m, config);
-            if let Some(private_key) = m.value_of("admin-private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("admin-private-key"), encryption)?;
             let address = m.value_of("address").unwrap();
             let balance = m
                 .value_of("value")
@@ -203,12 +197,13 @@ pub fn amend_processor(
             client.amend_balance(address, balance, quota)
         }
         _ => {
-            return Err(sub_matches.usage().to_owned());
+            return Err(sub_matches.usage().into());
         }
     };
-    let resp = result.map_err(|err| format!("{}", err))?;
+    let resp = result?;
+    let resp = wait_receipt(sub_matches, &client, resp, None)?;
     let is_color = !sub_matches.is_present("no-color") && config.color();
     printer.println(&resp, is_color);
     set_output(&resp, config);
-    Ok(())
+    check_response(&resp)
 }
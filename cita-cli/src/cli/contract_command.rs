@@ -14,8 +14,8 @@ use cita_tool::client::system_contract::{
 };
 
 use crate::cli::{
-    encryption, get_url, is_hex, key_validator, parse_address, parse_height, parse_privkey,
-    parse_u256, parse_u64,
+    check_response, encryption, get_url, is_hex, key_validator, parse_address, parse_height,
+    parse_u256, parse_u64, set_signing_key, wait_args, wait_receipt, CliError,
 };
 use crate::interactive::{set_output, GlobalConfig};
 use crate::printer::Printer;
@@ -94,15 +94,13 @@ pub fn contract_command() -> App<'static, 'static> {
     let private_key = Arg::with_name("private-key")
         .long("private-key")
         .takes_value(true)
-        .required(true)
         .validator(|private_key| key_validator(private_key.as_ref()).map(|_| ()))
-        .help("Private key");
+        .help("Private key, not needed with --signer");
     let admin_private = Arg::with_name("admin-private")
         .long("admin-private")
         .takes_value(true)
-        .required(true)
         .validator(|private_key| key_validator(private_key.as_ref()).map(|_| ()))
-        .help("Private key must be admin");
+        .help("Private key must be admin, not needed with --signer");
 
     let role_address_arg = address_arg.clone().help("Role address");
     let role_name_arg = name_arg.clone().help("Role name");
@@ -138,7 +136,8 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(
                             address_arg.clone().help("Degraded node address"),
                         )
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 )
                 .subcommand(
                     SubCommand::with_name("approveNode")
@@ -146,7 +145,8 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(
                             address_arg.clone().help("Approve node address"),
                         )
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 )
                 .subcommand(
                     SubCommand::with_name("setStake")
@@ -162,7 +162,8 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(
                             address_arg.clone().help("Set address"),
                         )
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 )
                 .subcommand(
                     SubCommand::with_name("stakePermillage").arg(
@@ -194,7 +195,8 @@ pub fn contract_command() -> App<'static, 'static> {
                                 ),
                         )
                         .arg(admin_private.clone())
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 )
                 .subcommand(
                     SubCommand::with_name("setDefaultAQL")
@@ -209,7 +211,8 @@ pub fn contract_command() -> App<'static, 'static> {
                                 ),
                         )
                         .arg(admin_private.clone())
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 )
                 .subcommand(
                     SubCommand::with_name("setAQL")
@@ -227,7 +230,8 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(
                             address_arg.clone().help("Account address"),
                         )
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 ),
         )
         .subcommand(
@@ -239,6 +243,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(group_name_arg.clone())
                         .arg(group_accounts_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -246,6 +251,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(group_origin_arg.clone())
                         .arg(group_target_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -254,6 +260,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(group_target_arg.clone())
                         .arg(group_name_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -262,6 +269,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(group_target_arg.clone())
                         .arg(group_accounts_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -270,6 +278,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(group_target_arg.clone())
                         .arg(group_accounts_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -371,6 +380,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(role_name_arg.clone())
                         .arg(permissions_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -378,6 +388,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .about("Delete the role")
                         .arg(role_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -386,6 +397,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(role_address_arg.clone())
                         .arg(role_name_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -394,6 +406,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(role_address_arg.clone())
                         .arg(permissions_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -402,6 +415,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(role_address_arg.clone())
                         .arg(permissions_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -410,6 +424,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(account_address_arg.clone())
                         .arg(role_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -418,6 +433,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(account_address_arg.clone())
                         .arg(role_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -425,6 +441,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .about("Clear the account's role")
                         .arg(account_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -512,6 +529,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(contracts_address_arg.clone())
                         .arg(function_hashes_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -519,6 +537,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .about("Delete the permission")
                         .arg(permission_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -527,6 +546,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(permission_address_arg.clone())
                         .arg(permission_name_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -536,6 +556,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(contracts_address_arg.clone())
                         .arg(function_hashes_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -545,6 +566,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(contracts_address_arg.clone())
                         .arg(function_hashes_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -553,6 +575,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(permission_address_arg.clone())
                         .arg(account_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -561,6 +584,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(permissions_address_arg.clone())
                         .arg(account_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -569,6 +593,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(permission_address_arg.clone())
                         .arg(account_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -577,6 +602,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .arg(permissions_address_arg.clone())
                         .arg(account_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 )
                 .subcommand(
@@ -584,6 +610,7 @@ pub fn contract_command() -> App<'static, 'static> {
                         .about("Clear the account's permissions")
                         .arg(account_address_arg.clone())
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(private_key.clone()),
                 ),
         )
@@ -601,7 +628,8 @@ pub fn contract_command() -> App<'static, 'static> {
                             address_arg.clone().help("Account address"),
                         )
                         .arg(admin_private.clone())
-                        .arg(quota_arg.clone()),
+                        .arg(quota_arg.clone())
+                        .args(&wait_args()),
                 ),
         )
         .subcommand(
@@ -617,6 +645,7 @@ pub fn contract_command() -> App<'static, 'static> {
                             .help("Binary content of one transaction[address + encode(function + params)]"),
                     )
                     .arg(quota_arg.clone())
+                    .args(&wait_args())
                     .arg(private_key.clone()),
             ),
         )
@@ -679,6 +708,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("Set chain name")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
                 .subcommand(
@@ -691,6 +721,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("Set operator")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
                 .subcommand(
@@ -703,6 +734,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("Set website")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
                 .subcommand(
@@ -715,6 +747,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("Set block interval")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
                 .subcommand(
@@ -746,6 +779,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("State value")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
         )
@@ -765,6 +799,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("Price value")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
         )
@@ -787,6 +822,7 @@ pub fn contract_command() -> App<'static, 'static> {
                                 .help("Version value")
                         )
                         .arg(quota_arg.clone())
+                        .args(&wait_args())
                         .arg(admin_private.clone())
                 )
         )
@@ -798,11 +834,13 @@ pub fn contract_processor(
     printer: &Printer,
     config: &mut GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let debug = sub_matches.is_present("debug") || config.debug();
     let mut client = client
         .set_debug(debug)
         .set_uri(get_url(sub_matches, config));
+    // The system contract clients take the client, keep one to wait for the receipt
+    let waiter = client.clone();
 
     let result = match sub_matches.subcommand() {
         ("NodeManager", Some(m)) => match m.subcommand() {
@@ -821,10 +859,7 @@ pub fn contract_processor(
             }
             ("deleteNode", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let address = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let mut client = NodeManageClient::create(client);
@@ -832,10 +867,7 @@ pub fn contract_processor(
             }
             ("approveNode", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let address = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let mut client = NodeManageClient::create(client);
@@ -843,10 +875,7 @@ pub fn contract_processor(
             }
             ("setStake", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let address = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let stake = m
@@ -861,7 +890,7 @@ pub fn contract_processor(
                 let client = NodeManageClient::create(client);
                 client.stake_permillage(address, m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("QuotaManager", Some(m)) => match m.subcommand() {
             ("getBQL", Some(m)) => QuotaManageClient::create(client).get_bql(m.value_of("height")),
@@ -880,36 +909,27 @@ pub fn contract_processor(
             }
             ("setBQL", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let quota_limit = parse_u256(m.value_of("quota-limit").unwrap())?;
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 QuotaManageClient::create(client).set_bql(quota_limit, quota)
             }
             ("setDefaultAQL", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let quota_limit = parse_u256(m.value_of("quota-limit").unwrap())?;
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 QuotaManageClient::create(client).set_default_aql(quota_limit, quota)
             }
             ("setAQL", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let quota_limit = parse_u256(m.value_of("quota-limit").unwrap())?;
                 let address = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 QuotaManageClient::create(client).set_aql(address, quota_limit, quota)
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("Group", Some(m)) => match m.subcommand() {
             ("queryInfo", Some(m)) => {
@@ -944,7 +964,7 @@ pub fn contract_processor(
                 let account_address = m.value_of("account").unwrap();
                 GroupClient::create(client).in_group(address, account_address, m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("GroupManagement", Some(m)) => match m.subcommand() {
             ("newGroup", Some(m)) => {
@@ -953,10 +973,7 @@ pub fn contract_processor(
                 let name = m.value_of("name").unwrap();
                 let accounts = m.value_of("accounts").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = GroupManageClient::create(client);
                 client.new_group(origin, name, accounts, quota)
             }
@@ -965,10 +982,7 @@ pub fn contract_processor(
                 let origin = m.value_of("origin").unwrap();
                 let target = m.value_of("target").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = GroupManageClient::create(client);
                 client.delete_group(origin, target, quota)
             }
@@ -978,10 +992,7 @@ pub fn contract_processor(
                 let target = m.value_of("target").unwrap();
                 let name = m.value_of("name").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = GroupManageClient::create(client);
                 client.update_group_name(origin, target, name, quota)
             }
@@ -991,10 +1002,7 @@ pub fn contract_processor(
                 let target = m.value_of("target").unwrap();
                 let accounts = m.value_of("accounts").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = GroupManageClient::create(client);
                 client.add_accounts(origin, target, accounts, quota)
             }
@@ -1004,10 +1012,7 @@ pub fn contract_processor(
                 let target = m.value_of("target").unwrap();
                 let accounts = m.value_of("accounts").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = GroupManageClient::create(client);
                 client.delete_accounts(origin, target, accounts, quota)
             }
@@ -1021,7 +1026,7 @@ pub fn contract_processor(
                 let client = GroupManageClient::create(client);
                 client.query_groups(m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("Role", Some(m)) => match m.subcommand() {
             ("queryRole", Some(m)) => {
@@ -1050,7 +1055,7 @@ pub fn contract_processor(
                 let client = RoleClient::create(client);
                 client.in_permissions(address, permission, m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("RoleManagement", Some(m)) => match m.subcommand() {
             ("newRole", Some(m)) => {
@@ -1058,10 +1063,7 @@ pub fn contract_processor(
                 let name = m.value_of("name").unwrap();
                 let permissions = m.value_of("permissions").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::new_role(&mut client, name, permissions, quota)
             }
@@ -1069,10 +1071,7 @@ pub fn contract_processor(
                 let encryption = encryption(m, config);
                 let role = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::delete_role(&mut client, role, quota)
             }
@@ -1081,10 +1080,7 @@ pub fn contract_processor(
                 let role = m.value_of("address").unwrap();
                 let name = m.value_of("name").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::update_role_name(&mut client, role, name, quota)
             }
@@ -1093,10 +1089,7 @@ pub fn contract_processor(
                 let role = m.value_of("address").unwrap();
                 let permissions = m.value_of("permissions").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::add_permissions(&mut client, role, permissions, quota)
             }
@@ -1105,10 +1098,7 @@ pub fn contract_processor(
                 let role = m.value_of("address").unwrap();
                 let permissions = m.value_of("permissions").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::delete_permissions(&mut client, role, permissions, quota)
             }
@@ -1117,10 +1107,7 @@ pub fn contract_processor(
                 let account = m.value_of("account").unwrap();
                 let role = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::set_role(&mut client, account, role, quota)
             }
@@ -1129,10 +1116,7 @@ pub fn contract_processor(
                 let account = m.value_of("account").unwrap();
                 let role = m.value_of("address").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::cancel_role(&mut client, account, role, quota)
             }
@@ -1140,10 +1124,7 @@ pub fn contract_processor(
                 let encryption = encryption(m, config);
                 let account = m.value_of("account").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = RoleManageClient::create(client);
                 RoleManagementExt::clear_role(&mut client, account, quota)
             }
@@ -1157,7 +1138,7 @@ pub fn contract_processor(
                 let client = RoleManageClient::create(client);
                 RoleManagementExt::query_accounts(&client, role, m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("Authorization", Some(m)) => match m.subcommand() {
             ("queryPermissions", Some(m)) => {
@@ -1198,7 +1179,7 @@ pub fn contract_processor(
                     m.value_of("height"),
                 )
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("Permission", Some(m)) => match m.subcommand() {
             ("inPermission", Some(m)) => {
@@ -1229,7 +1210,7 @@ pub fn contract_processor(
                 let client = PermissionClient::create(client);
                 PermissionExt::query_resource(&client, permission, m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("PermissionManagement", Some(m)) => match m.subcommand() {
             ("newPermission", Some(m)) => {
@@ -1238,10 +1219,7 @@ pub fn contract_processor(
                 let contracts = m.value_of("contracts").unwrap();
                 let function_hashes = m.value_of("function-hashes").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::new_permission(
                     &mut client,
@@ -1255,10 +1233,7 @@ pub fn contract_processor(
                 let encryption = encryption(m, config);
                 let permission = m.value_of("permission").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::delete_permission(&mut client, permission, quota)
             }
@@ -1267,10 +1242,7 @@ pub fn contract_processor(
                 let permission = m.value_of("permission").unwrap();
                 let name = m.value_of("name").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::update_permission_name(
                     &mut client,
@@ -1285,10 +1257,7 @@ pub fn contract_processor(
                 let contracts = m.value_of("contracts").unwrap();
                 let function_hashes = m.value_of("function-hashes").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::add_resources(
                     &mut client,
@@ -1304,10 +1273,7 @@ pub fn contract_processor(
                 let contracts = m.value_of("contracts").unwrap();
                 let function_hashes = m.value_of("function-hashes").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::delete_resources(
                     &mut client,
@@ -1322,10 +1288,7 @@ pub fn contract_processor(
                 let permission = m.value_of("permission").unwrap();
                 let account = m.value_of("account").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::set_authorization(&mut client, account, permission, quota)
             }
@@ -1334,10 +1297,7 @@ pub fn contract_processor(
                 let permissions = m.value_of("permissions").unwrap();
                 let account = m.value_of("account").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::set_authorizations(
                     &mut client,
@@ -1351,10 +1311,7 @@ pub fn contract_processor(
                 let permission = m.value_of("permission").unwrap();
                 let account = m.value_of("account").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::cancel_authorization(
                     &mut client,
@@ -1368,10 +1325,7 @@ pub fn contract_processor(
                 let permissions = m.value_of("permissions").unwrap();
                 let account = m.value_of("account").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::cancel_authorizations(
                     &mut client,
@@ -1384,14 +1338,11 @@ pub fn contract_processor(
                 let encryption = encryption(m, config);
                 let account = m.value_of("account").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let mut client = PermissionManageClient::create(client);
                 PermissionManagementExt::clear_authorization(&mut client, account, quota)
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("AdminManagement", Some(m)) => match m.subcommand() {
             ("admin", Some(m)) => AdminClient::create(client).admin(m.value_of("height")),
@@ -1401,28 +1352,22 @@ pub fn contract_processor(
             }
             ("update", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let address = m.value_of("address").unwrap();
                 AdminClient::create(client).add_admin(address, quota)
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("BatchTx", Some(m)) => match m.subcommand() {
             ("multiTxs", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("private-key").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let txs = m.values_of("tx-code").map(Iterator::collect).unwrap();
                 BatchTxClient::create(client).multi_transactions(txs, quota)
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("SysConfig", Some(m)) => match m.subcommand() {
             ("getChainOwner", Some(m)) => {
@@ -1459,10 +1404,7 @@ pub fn contract_processor(
             }
             ("setChainName", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: SysConfigClient<Client> = SysConfigExt::create(client);
                 let name = m.value_of("chain-name").unwrap();
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
@@ -1470,10 +1412,7 @@ pub fn contract_processor(
             }
             ("setOperator", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: SysConfigClient<Client> = SysConfigExt::create(client);
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let operator = m.value_of("operator").unwrap();
@@ -1481,10 +1420,7 @@ pub fn contract_processor(
             }
             ("setWebsite", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: SysConfigClient<Client> = SysConfigExt::create(client);
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let website = m.value_of("website").unwrap();
@@ -1492,10 +1428,7 @@ pub fn contract_processor(
             }
             ("setBlockInterval", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: SysConfigClient<Client> = SysConfigExt::create(client);
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let block_interval = m
@@ -1513,7 +1446,7 @@ pub fn contract_processor(
                 let client: SysConfigClient<Client> = SysConfigExt::create(client);
                 SysConfigExt::get_send_permission_check(&client, m.value_of("height"))
             }
-            _ => return Err(m.usage().to_owned()),
+            _ => return Err(m.usage().into()),
         },
         ("EmergencyBrake", Some(m)) => match m.subcommand() {
             ("state", Some(m)) => {
@@ -1522,10 +1455,7 @@ pub fn contract_processor(
             }
             ("setState", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: EmergencyBrakeClient<Client> = EmergencyBrakeExt::create(client);
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let state = m
@@ -1534,7 +1464,7 @@ pub fn contract_processor(
                     .unwrap();
                 EmergencyBrakeExt::set_state(&mut client, state, quota)
             }
-            _ => return Err(sub_matches.usage().to_owned()),
+            _ => return Err(sub_matches.usage().into()),
         },
         ("PriceManager", Some(m)) => match m.subcommand() {
             ("getQuotaPrice", Some(m)) => {
@@ -1543,10 +1473,7 @@ pub fn contract_processor(
             }
             ("setQuotaPrice", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: PriceManagerClient<Client> = PriceManagerExt::create(client);
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let price = m
@@ -1555,7 +1482,7 @@ pub fn contract_processor(
                     .unwrap();
                 PriceManagerExt::set_price(&mut client, price, quota)
             }
-            _ => return Err(sub_matches.usage().to_owned()),
+            _ => return Err(sub_matches.usage().into()),
         },
         ("VersionManager", Some(m)) => match m.subcommand() {
             ("getVersion", Some(m)) => {
@@ -1564,10 +1491,7 @@ pub fn contract_processor(
             }
             ("setVersion", Some(m)) => {
                 let encryption = encryption(m, config);
-                client.set_private_key(&parse_privkey(
-                    m.value_of("admin-private").unwrap(),
-                    encryption,
-                )?);
+                set_signing_key(&mut client, m.value_of("admin-private"), encryption)?;
                 let mut client: VersionManagerClient<Client> = VersionManagerExt::create(client);
                 let quota = m.value_of("quota").map(|quota| parse_u64(quota).unwrap());
                 let version = m
@@ -1576,13 +1500,14 @@ pub fn contract_processor(
                     .unwrap();
                 VersionManagerExt::set_version(&mut client, version, quota)
             }
-            _ => return Err(sub_matches.usage().to_owned()),
+            _ => return Err(sub_matches.usage().into()),
         },
-        _ => return Err(sub_matches.usage().to_owned()),
+        _ => return Err(sub_matches.usage().into()),
     };
     let is_color = !sub_matches.is_present("no-color") && config.color();
-    let response = result.map_err(|err| format!("{}", err))?;
+    let response = result?;
+    let response = wait_receipt(sub_matches, &waiter, response, None)?;
     printer.println(&response, is_color);
     set_output(&response, config);
-    Ok(())
+    check_response(&response)
 }
@@ -0,0 +1,94 @@
+use std::fmt;
+use std::io;
+
+use cita_tool::{ErrorKind, JsonRpcResponse, ToolError};
+
+/// Exit code of the errors of no other category
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code of an invalid argument or data
+pub const EXIT_INVALID_INPUT: i32 = 2;
+/// Exit code of a failed connection to the node
+pub const EXIT_TRANSPORT: i32 = 3;
+/// Exit code of a request timeout
+pub const EXIT_TIMEOUT: i32 = 4;
+/// Exit code of a jsonrpc error response
+pub const EXIT_RPC: i32 = 5;
+/// Exit code of an ABI error
+pub const EXIT_ABI: i32 = 6;
+/// Exit code of a key, signature or signer error
+pub const EXIT_CRYPTO: i32 = 7;
+/// Exit code of a failed or expired transaction
+pub const EXIT_TRANSACTION: i32 = 8;
+/// Exit code of a file error
+pub const EXIT_IO: i32 = 9;
+
+/// Error of a command, with the exit code of the process
+#[derive(Debug)]
+pub struct CliError {
+    code: i32,
+    message: String,
+}
+
+impl CliError {
+    /// Create an error with the exit code
+    pub fn new<T: Into<String>>(code: i32, message: T) -> Self {
+        CliError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Get exit code
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::new(EXIT_FAILURE, message)
+    }
+}
+
+impl<'a> From<&'a str> for CliError {
+    fn from(message: &'a str) -> Self {
+        CliError::new(EXIT_FAILURE, message)
+    }
+}
+
+impl From<ToolError> for CliError {
+    fn from(err: ToolError) -> Self {
+        let code = match err.kind() {
+            ErrorKind::Transport => EXIT_TRANSPORT,
+            ErrorKind::Timeout => EXIT_TIMEOUT,
+            ErrorKind::Rpc => EXIT_RPC,
+            ErrorKind::Abi => EXIT_ABI,
+            ErrorKind::Crypto => EXIT_CRYPTO,
+            ErrorKind::InvalidInput => EXIT_INVALID_INPUT,
+            ErrorKind::Transaction => EXIT_TRANSACTION,
+            ErrorKind::Io => EXIT_IO,
+            ErrorKind::Other => EXIT_FAILURE,
+        };
+        CliError::new(code, err.to_string())
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        ToolError::Stdio(err).into()
+    }
+}
+
+/// The error of a printed response, the process exits with `EXIT_RPC`
+pub fn check_response(response: &JsonRpcResponse) -> Result<(), CliError> {
+    match response.error() {
+        Some(error) => Err(ToolError::JsonRpc(error).into()),
+        None => Ok(()),
+    }
+}
@@ -1,13 +1,17 @@
 use ansi_term::Colour::Yellow;
 use clap::{App, Arg, ArgMatches, SubCommand};
 
+use cita_tool::signer::{serve_signer, Keystore, KeystoreSigner};
 use cita_tool::{
     decode, pubkey_to_address, remove_0x, Hashable, KeyPair, LowerHex, Message, PubKey, Signature,
+    ToolError,
 };
 
-use crate::cli::{encryption, h256_validator, is_hex, key_validator};
+use crate::cli::{encryption, h256_validator, is_hex, key_validator, parse_privkey, CliError};
 use crate::interactive::GlobalConfig;
 use crate::printer::Printer;
+use std::env;
+use std::io;
 use std::str::FromStr;
 
 /// Key related commands
@@ -25,6 +29,36 @@ pub fn key_command() -> App<'static, 'static> {
                     .help("The private key of transaction"),
             ),
         )
+        .subcommand(
+            SubCommand::with_name("keystore")
+                .about("Encrypt the private key to a keystore with KEYSTORE_PASSWORD")
+                .arg(
+                    Arg::with_name("private-key")
+                        .long("private-key")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(|privkey| key_validator(privkey.as_ref()).map(|_| ()))
+                        .help("The private key to encrypt"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The keystore file"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("serve-keystore")
+                .about("Sign with the keystore and KEYSTORE_PASSWORD for the requests on stdin, the signer process of --signer keystore:<path>")
+                .arg(
+                    Arg::with_name("keystore")
+                        .long("keystore")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The keystore file"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("pub-to-address").arg(
                 Arg::with_name("pubkey")
@@ -81,7 +115,7 @@ pub fn key_processor(
     sub_matches: &ArgMatches,
     printer: &Printer,
     config: &GlobalConfig,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     match sub_matches.subcommand() {
         ("create", Some(m)) => {
             let encryption = encryption(m, config);
@@ -96,6 +130,23 @@ pub fn key_processor(
             let is_color = !sub_matches.is_present("no-color") && config.color();
             printer.println(&key_pair, is_color);
         }
+        ("keystore", Some(m)) => {
+            let encryption = encryption(m, config);
+            let private_key = parse_privkey(m.value_of("private-key").unwrap(), encryption)?;
+            let password = env::var("KEYSTORE_PASSWORD")
+                .map_err(|_| "The keystore needs KEYSTORE_PASSWORD".to_string())?;
+            let keystore = Keystore::encrypt(&private_key, &password)?;
+            keystore.save(m.value_of("output").unwrap())?;
+            printer.println(&format!("0x{}", keystore.address), printer.color());
+        }
+        ("serve-keystore", Some(m)) => {
+            let password = env::var("KEYSTORE_PASSWORD")
+                .map_err(|_| "The keystore needs KEYSTORE_PASSWORD".to_string())?;
+            let keystore = Keystore::load(m.value_of("keystore").unwrap())?;
+            let signer = KeystoreSigner::new(&keystore, &password)?;
+            let stdin = io::stdin();
+            serve_signer(&signer, stdin.lock(), io::stdout())?;
+        }
         ("pub-to-address", Some(m)) => {
             let encryption = encryption(m, config);
             let pubkey = m.value_of("pubkey").unwrap();
@@ -112,7 +163,7 @@ pub fn key_processor(
         ("hash", Some(m)) => {
             let encryption = encryption(m, config);
             let content =
-                decode(remove_0x(m.value_of("content").unwrap())).map_err(|err| err.to_string())?;
+                decode(remove_0x(m.value_of("content").unwrap())).map_err(ToolError::Decode)?;
             printer.println(&content.crypt_hash(encryption).lower_hex(), printer.color());
         }
         ("verification", Some(m)) => {
@@ -121,12 +172,12 @@ pub fn key_processor(
             let message = Message::from_str(remove_0x(m.value_of("message").unwrap()))
                 .map_err(|err| err.to_string())?;
             let sig = Signature::from(
-                &decode(remove_0x(m.value_of("signature").unwrap())).map_err(|e| e.to_string())?,
+                &decode(remove_0x(m.value_of("signature").unwrap())).map_err(ToolError::Decode)?,
             );
             println!("{}", sig.verify_public(pubkey, &message)?);
         }
         _ => {
-            return Err(sub_matches.usage().to_owned());
+            return Err(sub_matches.usage().into());
         }
     }
     Ok(())
@@ -2,18 +2,21 @@ use clap::{App, Arg, ArgMatches, SubCommand};
 use serde_json::json;
 
 use cita_tool::client::basic::{Client, Transfer};
-use cita_tool::{JsonRpcParams, ParamsValue, TransactionOptions};
+use cita_tool::devnode::DevNode;
+use cita_tool::{remove_0x, Address, JsonRpcParams, ParamsValue, TransactionOptions, U256};
 
 use crate::cli::{
-    encryption, get_url, is_hex, key_validator, parse_address, parse_privkey, parse_u256,
-    parse_u32, parse_u64, search_app,
+    check_response, encryption, get_url, is_hex, key_validator, parse_address, parse_u256,
+    parse_u32, parse_u64, search_app, set_signing_key, wait_args, wait_receipt, CliError,
 };
 use crate::interactive::{set_output, GlobalConfig};
 use crate::printer::Printer;
 
 use std::collections::BTreeSet;
 use std::io;
-use std::time::SystemTime;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
 /// Search command tree
 pub fn search_command() -> App<'static, 'static> {
@@ -128,8 +131,7 @@ pub fn transfer_command() -> App<'static, 'static> {
                 .long("private-key")
                 .validator(|private| key_validator(private.as_str()).map(|_| ()))
                 .takes_value(true)
-                .required(true)
-                .help("Transfer Account Private Key"),
+                .help("Transfer Account Private Key, not needed with --signer"),
         )
         .arg(
             Arg::with_name("value")
@@ -147,6 +149,7 @@ pub fn transfer_command() -> App<'static, 'static> {
                 .takes_value(true)
                 .help("Transaction quota costs, default 30000"),
         )
+        .args(&wait_args())
 }
 
 /// Account transfer processor
@@ -155,29 +158,25 @@ pub fn transfer_processor(
     printer: &Printer,
     config: &mut GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let debug = sub_matches.is_present("debug") || config.debug();
     let mut client = client
         .set_debug(debug)
         .set_uri(get_url(sub_matches, config));
 
     let encryption = encryption(sub_matches, config);
-    client.set_private_key(&parse_privkey(
-        sub_matches.value_of("private-key").unwrap(),
-        encryption,
-    )?);
+    set_signing_key(&mut client, sub_matches.value_of("private-key"), encryption)?;
     let address = sub_matches.value_of("address").unwrap();
     let quota = sub_matches
         .value_of("quota")
         .map(|quota| parse_u64(quota).unwrap());
     let value = parse_u256(sub_matches.value_of("value").unwrap()).unwrap();
     let is_color = !sub_matches.is_present("no-color") && config.color();
-    let response = client
-        .transfer(value, address, quota)
-        .map_err(|err| format!("{}", err))?;
+    let response = client.transfer(value, address, quota)?;
+    let response = wait_receipt(sub_matches, &client, response, None)?;
     printer.println(&response, is_color);
     set_output(&response, config);
-    Ok(())
+    check_response(&response)
 }
 
 /// Simple benchmark
@@ -225,9 +224,8 @@ pub fn benchmark_command() -> App<'static, 'static> {
                     Arg::with_name("private-key")
                         .long("private-key")
                         .takes_value(true)
-                        .required(true)
                         .validator(|privkey| key_validator(privkey.as_ref()).map(|_| ()))
-                        .help("The private key of transaction"),
+                        .help("The private key of transaction, not needed with --signer"),
                 )
                 .arg(
                     Arg::with_name("quota")
@@ -267,7 +265,7 @@ pub fn benchmark_processor(
     printer: &Printer,
     config: &GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let mut client = client.set_uri(get_url(sub_matches, config));
 
     match sub_matches.subcommand() {
@@ -275,9 +273,7 @@ pub fn benchmark_processor(
             let params = JsonRpcParams::new()
                 .insert("method", ParamsValue::String(String::from("blockNumber")));
             let start = SystemTime::now();
-            let result = client
-                .send_request(vec![params; 1000].into_iter())
-                .map_err(|err| format!("{}", err))?;
+            let result = client.send_request(vec![params; 1000].into_iter())?;
             assert_eq!(result.len(), 1000);
             match start.elapsed() {
                 Ok(elapsed) => {
@@ -294,7 +290,7 @@ pub fn benchmark_processor(
                 }
                 Err(e) => {
                     // an error occurred!
-                    return Err(format!("Error: {:?}", e));
+                    return Err(format!("Error: {:?}", e).into());
                 }
             }
         }
@@ -303,9 +299,7 @@ pub fn benchmark_processor(
             if let Some(chain_id) = m.value_of("chain-id").map(|s| parse_u256(s).unwrap()) {
                 client.set_chain_id(chain_id);
             }
-            if let Some(private_key) = m.value_of("private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
             let code = m.value_of("code").unwrap();
             let address = m.value_of("address").unwrap();
             let current_height = m.value_of("height").map(|s| parse_u64(s).unwrap());
@@ -328,12 +322,8 @@ pub fn benchmark_processor(
 
             let mut txs = Vec::with_capacity(number as usize);
             for _ in 0..number {
-                let tx = client
-                    .generate_transaction(tx_options)
-                    .map_err(|err| format!("{}", err))?;
-                let byte_code = client
-                    .generate_sign_transaction(&tx)
-                    .map_err(|err| format!("{}", err))?;
+                let tx = client.generate_transaction(tx_options)?;
+                let byte_code = client.generate_sign_transaction(&tx)?;
                 let params = JsonRpcParams::new()
                     .insert(
                         "method",
@@ -345,17 +335,124 @@ pub fn benchmark_processor(
                     );
                 txs.push(params);
             }
+            // The failed transactions do not stop the others
             let result = client
-                .send_request(txs.into_iter())
-                .map_err(|err| format!("{}", err))?;
+                .send_request_each(txs.into_iter())?
+                .into_iter()
+                .map(|result| match result {
+                    Ok(response) => json!(response),
+                    Err(err) => json!({ "error": err.to_string() }),
+                })
+                .collect::<Vec<_>>();
             printer.println(&json!(result), true);
         }
-        _ => return Err(sub_matches.usage().to_owned()),
+        _ => return Err(sub_matches.usage().into()),
     }
 
     Ok(())
 }
 
+/// Mock node command
+pub fn devnode_command() -> App<'static, 'static> {
+    App::new("devnode")
+        .about("Run a mock CITA node with its chain in memory, for tests and demos")
+        .arg(
+            Arg::with_name("listen")
+                .long("listen")
+                .takes_value(true)
+                .default_value("127.0.0.1:1337")
+                .validator(|addr| {
+                    addr.parse::<SocketAddr>()
+                        .map(|_| ())
+                        .map_err(|err| err.to_string())
+                })
+                .help("Address to serve the JSONRPC requests on"),
+        )
+        .arg(
+            Arg::with_name("block-interval")
+                .long("block-interval")
+                .takes_value(true)
+                .default_value("3000")
+                .validator(|interval| parse_u64(interval.as_str()).map(|_| ()))
+                .help("Milliseconds between the blocks"),
+        )
+        .arg(
+            Arg::with_name("chain-id")
+                .long("chain-id")
+                .takes_value(true)
+                .default_value("1")
+                .validator(|chain_id| parse_u32(chain_id.as_str()).map(|_| ()))
+                .help("Chain id of the transactions"),
+        )
+        .arg(
+            Arg::with_name("chain-version")
+                .long("chain-version")
+                .takes_value(true)
+                .default_value("2")
+                .validator(|version| parse_u32(version.as_str()).map(|_| ()))
+                .help("Chain version of the transactions"),
+        )
+        .arg(
+            Arg::with_name("admin")
+                .long("admin")
+                .takes_value(true)
+                .validator(|address| parse_address(address.as_str()))
+                .help("Admin of the chain, also its only validator"),
+        )
+        .arg(
+            Arg::with_name("balance")
+                .long("balance")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .validator(|balance| parse_balance(balance.as_str()).map(|_| ()))
+                .help("Initial balance of an account: <address>=<value>"),
+        )
+}
+
+/// Initial balance `<address>=<value>` of the mock node
+fn parse_balance(balance: &str) -> Result<(Address, U256), String> {
+    let mut parts = balance.splitn(2, '=');
+    match (parts.next(), parts.next()) {
+        (Some(address), Some(value)) => {
+            let address = Address::from_str(remove_0x(address)).map_err(|err| err.to_string())?;
+            Ok((address, parse_u256(value)?))
+        }
+        _ => Err(format!("Expect <address>=<value>: {}", balance)),
+    }
+}
+
+/// Mock node processor, serve until the process is stopped
+pub fn devnode_processor(sub_matches: &ArgMatches, printer: &Printer) -> Result<(), CliError> {
+    let interval = parse_u64(sub_matches.value_of("block-interval").unwrap()).unwrap();
+    let chain_id = parse_u32(sub_matches.value_of("chain-id").unwrap()).unwrap();
+    let version = parse_u32(sub_matches.value_of("chain-version").unwrap()).unwrap();
+    let mut node = DevNode::new()
+        .set_block_interval(Duration::from_millis(interval))
+        .set_chain_id(chain_id)
+        .set_version(version);
+    if let Some(admin) = sub_matches.value_of("admin") {
+        node = node.set_admin(Address::from_str(remove_0x(admin)).unwrap_or_default());
+    }
+    for balance in sub_matches.values_of("balance").into_iter().flatten() {
+        let (address, value) = parse_balance(balance)?;
+        node = node.set_balance(address, value);
+    }
+
+    let addr = sub_matches.value_of("listen").unwrap().parse().unwrap();
+    let server = node.bind(&addr)?;
+    printer.println(
+        &json!({
+            "url": format!("http://{}", server.local_addr()),
+            "chainId": chain_id,
+            "version": version,
+            "blockInterval": interval,
+        }),
+        true,
+    );
+    Ok(server.run()?)
+}
+
 // Generate completion scripts
 pub fn completion_command() -> App<'static, 'static> {
     App::new("completions")
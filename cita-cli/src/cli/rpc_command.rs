@@ -4,12 +4,14 @@ use cita_tool::{
     client::basic::{Client, ClientExt},
     error::ToolError,
     rpctypes::JsonRpcResponse,
+    signer::Signer,
     ParamsValue, ResponseValue, TransactionOptions, UnverifiedTransaction,
 };
 
 use crate::cli::{
-    encryption, get_url, h256_validator, is_hex, key_validator, parse_address, parse_height,
-    parse_privkey, parse_u256, parse_u32, parse_u64,
+    auto_quota_args, check_response, encryption, get_url, h256_validator, is_hex, key_validator,
+    nonce_counters, parse_address, parse_height, parse_u256, parse_u32, parse_u64, set_auto_quota,
+    set_signing_key, set_validity_nonce, validity_nonce_args, wait_args, wait_receipt, CliError,
 };
 use crate::interactive::{set_output, GlobalConfig};
 use crate::printer::Printer;
@@ -62,9 +64,8 @@ pub fn rpc_command() -> App<'static, 'static> {
                     Arg::with_name("private-key")
                         .long("private-key")
                         .takes_value(true)
-                        .required(true)
                         .validator(|privkey| key_validator(privkey.as_ref()).map(|_| ()))
-                        .help("The private key of transaction"),
+                        .help("The private key of transaction, not needed with --signer"),
                 )
                 .arg(
                     Arg::with_name("quota")
@@ -86,7 +87,10 @@ pub fn rpc_command() -> App<'static, 'static> {
                         .takes_value(true)
                         .validator(|version| parse_u32(version.as_str()).map(|_| ()))
                         .help("The version of transaction, default is 0"),
-                ),
+                )
+                .args(&validity_nonce_args())
+                .args(&auto_quota_args())
+                .args(&wait_args()),
         )
         .subcommand(
             SubCommand::with_name("getBlockByHash")
@@ -499,13 +503,15 @@ pub fn rpc_processor(
     printer: &Printer,
     config: &mut GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let debug = sub_matches.is_present("debug") || config.debug();
     let is_color = !sub_matches.is_present("no-color") && config.color();
     let mut client = client
         .set_debug(debug)
         .set_uri(get_url(sub_matches, config));
 
+    let counters = nonce_counters();
+    let mut valid_until_block = None;
     let result = match sub_matches.subcommand() {
         ("peerCount", _) => client.get_peer_count(),
         ("peersInfo", _) => client.get_peers_info(),
@@ -516,9 +522,7 @@ pub fn rpc_processor(
             if let Some(chain_id) = m.value_of("chain-id").map(|s| parse_u256(s).unwrap()) {
                 client.set_chain_id(chain_id);
             }
-            if let Some(private_key) = m.value_of("private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
             let code = m.value_of("code").unwrap();
             let address = m.value_of("address").unwrap();
             let current_height = m.value_of("height").map(|s| parse_u64(s).unwrap());
@@ -527,6 +531,7 @@ pub fn rpc_processor(
             let version = m
                 .value_of("version")
                 .map(|version| parse_u32(version).unwrap());
+            let account = client.signer().map(Signer::address).transpose()?;
             let tx_options = TransactionOptions::new()
                 .set_code(code)
                 .set_address(address)
@@ -534,6 +539,9 @@ pub fn rpc_processor(
                 .set_quota(quota)
                 .set_value(value)
                 .set_version(version);
+            let tx_options = set_validity_nonce(m, tx_options, &counters, account)?;
+            let tx_options = set_auto_quota(m, tx_options, account);
+            valid_until_block = tx_options.valid_until_block();
             client.send_raw_transaction(tx_options)
         }
         ("getBlockByHash", Some(m)) => {
@@ -645,11 +653,12 @@ pub fn rpc_processor(
             m.value_of("height").unwrap(),
         ),
         _ => {
-            return Err(sub_matches.usage().to_owned());
+            return Err(sub_matches.usage().into());
         }
     };
-    let resp = result.map_err(|err| format!("{}", err))?;
+    let resp = result?;
+    let resp = wait_receipt(sub_matches, &client, resp, valid_until_block)?;
     printer.println(&resp, is_color);
     set_output(&resp, config);
-    Ok(())
+    check_response(&resp)
 }
@@ -4,7 +4,8 @@ use cita_tool::client::basic::{Client, StoreExt};
 use cita_tool::remove_0x;
 
 use crate::cli::{
-    encryption, get_url, is_hex, key_validator, parse_address, parse_privkey, parse_u64,
+    check_response, encryption, get_url, is_hex, key_validator, parse_address, parse_u64,
+    set_signing_key, wait_args, wait_receipt, CliError,
 };
 use crate::interactive::{set_output, GlobalConfig};
 use crate::printer::Printer;
@@ -26,9 +27,8 @@ pub fn store_command() -> App<'static, 'static> {
         Arg::with_name("private-key")
             .long("private-key")
             .takes_value(true)
-            .required(true)
             .validator(|privkey| key_validator(privkey.as_ref()).map(|_| ()))
-            .help("The private key of transaction"),
+            .help("The private key of transaction, not needed with --signer"),
         Arg::with_name("quota")
             .long("quota")
             .takes_value(true)
@@ -49,7 +49,8 @@ pub fn store_command() -> App<'static, 'static> {
                         .takes_value(true)
                         .help("The content of data to store"),
                 )
-                .args(&common_args),
+                .args(&common_args)
+                .args(&wait_args()),
         )
         .subcommand(
             SubCommand::with_name("abi")
@@ -77,7 +78,8 @@ pub fn store_command() -> App<'static, 'static> {
                         .help("The path of ABI json file to store (.json)"),
                 )
                 .group(ArgGroup::with_name("the-abi").args(&["content", "path"]))
-                .args(&common_args),
+                .args(&common_args)
+                .args(&wait_args()),
         )
 }
 
@@ -87,7 +89,7 @@ pub fn store_processor(
     printer: &Printer,
     config: &mut GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let debug = sub_matches.is_present("debug") || config.debug();
     let mut client = client
         .set_debug(debug)
@@ -98,9 +100,7 @@ pub fn store_processor(
             let encryption = encryption(m, config);
             let quota = m.value_of("quota").map(|s| parse_u64(s).unwrap());
             let content = remove_0x(m.value_of("content").unwrap());
-            if let Some(private_key) = m.value_of("private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
             client.store_data(content, quota)
         }
         ("abi", Some(m)) => {
@@ -111,25 +111,23 @@ pub fn store_processor(
                 None => {
                     let mut abi_content = String::new();
                     let path = m.value_of("path").unwrap();
-                    let mut file = fs::File::open(path).map_err(|err| format!("{}", err))?;
-                    file.read_to_string(&mut abi_content)
-                        .map_err(|err| format!("{}", err))?;
+                    let mut file = fs::File::open(path)?;
+                    file.read_to_string(&mut abi_content)?;
                     abi_content
                 }
             };
             let address = m.value_of("address").unwrap();
-            if let Some(private_key) = m.value_of("private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
             client.store_abi(address, content, quota)
         }
         _ => {
-            return Err(sub_matches.usage().to_owned());
+            return Err(sub_matches.usage().into());
         }
     };
-    let resp = result.map_err(|err| format!("{}", err))?;
+    let resp = result?;
+    let resp = wait_receipt(sub_matches, &client, resp, None)?;
     let is_color = !sub_matches.is_present("no-color") && config.color();
     printer.println(&resp, is_color);
     set_output(&resp, config);
-    Ok(())
+    check_response(&resp)
 }
@@ -1,15 +1,19 @@
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use cita_tool::client::basic::Client;
-use cita_tool::{encode, ProtoMessage, TransactionOptions, UnverifiedTransaction};
+use cita_tool::{
+    encode, remove_0x, Address, ProtoMessage, ToolError, Transaction, TransactionOptions,
+    UnverifiedTransaction,
+};
 
 use crate::cli::{
-    encryption, get_url, is_hex, key_validator, parse_address, parse_privkey, parse_u256,
-    parse_u32, parse_u64,
+    auto_quota_args, check_response, encryption, get_url, is_hex, key_validator, nonce_counters,
+    parse_address, parse_u256, parse_u32, parse_u64, set_auto_quota, set_signing_key,
+    set_validity_nonce, validity_nonce_args, wait_args, wait_receipt, CliError,
 };
 use crate::interactive::{set_output, GlobalConfig};
 use crate::printer::Printer;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
 use std::str::FromStr;
 
@@ -73,6 +77,48 @@ pub fn tx_command() -> App<'static, 'static> {
                         .takes_value(true)
                         .validator(|version| parse_u32(version.as_str()).map(|_| ()))
                         .help("The version of transaction, default is 0"),
+                )
+                .arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .takes_value(true)
+                        .validator(|address| parse_address(address.as_str()))
+                        .help("The sender of transaction, whose nonce counter is used"),
+                )
+                .arg(
+                    Arg::with_name("offline")
+                        .long("offline")
+                        .requires_all(&["chain-id", "version", "valid-until-block"])
+                        .conflicts_with_all(&["height", "auto-quota"])
+                        .help("Build without the network, from the chain id, version and valid until block"),
+                )
+                .args(&validity_nonce_args())
+                .args(&auto_quota_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("sign")
+                .about("Sign an unsigned transaction without the network")
+                .arg(
+                    Arg::with_name("byte-code")
+                        .long("byte-code")
+                        .takes_value(true)
+                        .validator(|code| is_hex(code.as_str()))
+                        .required(true)
+                        .help("Unsigned transaction binary data, made by `tx make`"),
+                )
+                .arg(
+                    Arg::with_name("private-key")
+                        .long("private-key")
+                        .takes_value(true)
+                        .validator(|private| key_validator(private.as_str()).map(|_| ()))
+                        .conflicts_with("key-file")
+                        .help("The private key of the sender, not needed with --signer"),
+                )
+                .arg(
+                    Arg::with_name("key-file")
+                        .long("key-file")
+                        .takes_value(true)
+                        .help("The file holding the private key of the sender"),
                 ),
         )
         .subcommand(
@@ -85,7 +131,8 @@ pub fn tx_command() -> App<'static, 'static> {
                         .validator(|code| is_hex(code.as_str()))
                         .required(true)
                         .help("Signed transaction binary data"),
-                ),
+                )
+                .args(&wait_args()),
         )
         .subcommand(
             SubCommand::with_name("sendTransaction")
@@ -103,8 +150,7 @@ pub fn tx_command() -> App<'static, 'static> {
                         .long("private-key")
                         .validator(|private| key_validator(private.as_str()).map(|_| ()))
                         .takes_value(true)
-                        .required(true)
-                        .help("Transfer Account Private Key"),
+                        .help("Transfer Account Private Key, not needed with --signer"),
                 ),
         )
         .subcommand(
@@ -133,13 +179,15 @@ pub fn tx_processor(
     printer: &Printer,
     config: &mut GlobalConfig,
     client: Client,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     let debug = sub_matches.is_present("debug") || config.debug();
     let is_color = !sub_matches.is_present("no-color") && config.color();
     let mut client = client
         .set_debug(debug)
         .set_uri(get_url(sub_matches, config));
 
+    let counters = nonce_counters();
+    let mut valid_until_block = None;
     let result = match sub_matches.subcommand() {
         ("make", Some(m)) => {
             if let Some(chain_id) = m.value_of("chain-id").map(|s| parse_u256(s).unwrap()) {
@@ -160,27 +208,47 @@ pub fn tx_processor(
                 .set_quota(quota)
                 .set_value(value)
                 .set_version(version);
-            let tx = client
-                .generate_transaction(tx_options)
-                .map_err(|err| format!("{}", err))?;
+            let account = m
+                .value_of("from")
+                .map(|from| Address::from_str(remove_0x(from)).unwrap());
+            let tx_options = set_validity_nonce(m, tx_options, &counters, account)?;
+            let tx_options = set_auto_quota(m, tx_options, account);
+            let tx = if m.is_present("offline") {
+                tx_options.build_offline(parse_u256(m.value_of("chain-id").unwrap())?)
+            } else {
+                client.generate_transaction(tx_options)
+            }?;
             printer.println(
                 &format!(
                     "0x{}",
-                    encode(tx.write_to_bytes().map_err(|err| format!("{}", err))?)
+                    encode(tx.write_to_bytes().map_err(ToolError::Proto)?)
                 ),
                 is_color,
             );
             return Ok(());
         }
+        ("sign", Some(m)) => {
+            let encryption = encryption(sub_matches, config);
+            let private_key = match m.value_of("key-file") {
+                Some(path) => Some(fs::read_to_string(path)?.trim().to_string()),
+                None => m.value_of("private-key").map(ToString::to_string),
+            };
+            set_signing_key(&mut client, private_key.as_deref(), encryption)?;
+            let tx = Transaction::from_str(m.value_of("byte-code").unwrap())?;
+            let signed = client.generate_sign_transaction(&tx)?;
+            printer.println(&signed, is_color);
+            return Ok(());
+        }
         ("sendSignedTransaction", Some(m)) => {
             let byte_code = m.value_of("byte-code").unwrap();
+            valid_until_block = UnverifiedTransaction::from_str(byte_code)
+                .ok()
+                .map(|tx| tx.get_transaction().get_valid_until_block());
             client.send_signed_transaction(byte_code)
         }
         ("sendTransaction", Some(m)) => {
             let encryption = encryption(sub_matches, config);
-            if let Some(private_key) = m.value_of("private-key") {
-                client.set_private_key(&parse_privkey(private_key, encryption)?);
-            }
+            set_signing_key(&mut client, m.value_of("private-key"), encryption)?;
             let byte_code = m.value_of("byte-code").unwrap();
             client.send_transaction(byte_code)
         }
@@ -190,23 +258,21 @@ pub fn tx_processor(
             let content_file = m.value_of("file");
             let mut content_reader = get_content(content_file, content)?;
             let mut content_data = String::new();
-            content_reader
-                .read_to_string(&mut content_data)
-                .map_err(|err| format!("{}", err))?;
+            content_reader.read_to_string(&mut content_data)?;
             let content_data = content_data.trim();
-            let tx =
-                UnverifiedTransaction::from_str(&content_data).map_err(|err| format!("{}", err))?;
+            let tx = UnverifiedTransaction::from_str(content_data)?;
             printer.println(&tx.to_json(encryption)?, is_color);
             return Ok(());
         }
         _ => {
-            return Err(sub_matches.usage().to_owned());
+            return Err(sub_matches.usage().into());
         }
     };
-    let resp = result.map_err(|err| format!("{}", err))?;
+    let resp = result?;
+    let resp = wait_receipt(sub_matches, &client, resp, valid_until_block)?;
     printer.println(&resp, is_color);
     set_output(&resp, config);
-    Ok(())
+    check_response(&resp)
 }
 
 fn get_content(path: Option<&str>, content: Option<&str>) -> Result<Box<dyn Read>, String> {
@@ -1,9 +1,25 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use clap::{App, ArgMatches};
+use clap::{App, Arg, ArgMatches};
 
-use cita_tool::{remove_0x, Address, Encryption, PrivateKey, H256, H512, U256};
+use cita_tool::client::basic::Client;
+use cita_tool::client::endpoint::{Endpoints, Strategy};
+use cita_tool::client::limit::RequestLimits;
+use cita_tool::client::tls::TlsConfig;
+use cita_tool::client::transport::HttpTransport;
+use cita_tool::client::wait::WaitOptions;
+use cita_tool::signer::{parse_signer_spec, signer_from_spec, ExternalSigner, Signer};
+use cita_tool::{
+    parse_url, remove_0x, Address, AutoQuota, Encryption, JsonRpcResponse, NonceSource, PrivateKey,
+    ResponseValue, ToolError, TransactionOptions, H256, H512, U256,
+};
 
+use crate::cli::CliError;
 use crate::interactive::GlobalConfig;
 
 /// Get url from arg match
@@ -20,6 +36,315 @@ pub fn get_url<'a>(m: &'a ArgMatches, config: &'a GlobalConfig) -> &'a str {
     }
 }
 
+/// Get endpoints from arg match, the requests go to them instead of the url
+pub fn get_endpoints(m: &ArgMatches) -> Result<Option<Endpoints>, String> {
+    match m.value_of("endpoints") {
+        Some(urls) => {
+            let urls = urls
+                .split(',')
+                .map(parse_url)
+                .collect::<Result<Vec<_>, _>>()?;
+            let strategy = m
+                .value_of("strategy")
+                .map(|strategy| Strategy::from_str(strategy).unwrap())
+                .unwrap_or(Strategy::Failover);
+            let endpoints = Endpoints::new(urls).map_err(|err| err.to_string())?;
+            Ok(Some(endpoints.set_strategy(strategy)))
+        }
+        None => match m.subcommand().1 {
+            Some(m) => get_endpoints(m),
+            None => Ok(None),
+        },
+    }
+}
+
+/// Get the limits of the requests in flight and of the request rate from arg match
+pub fn get_request_limits(m: &ArgMatches) -> Option<RequestLimits> {
+    let max_in_flight = m
+        .value_of("max-in-flight")
+        .map(|max| parse_u64(max).unwrap() as usize);
+    let rate = m
+        .value_of("rate-limit")
+        .map(|rate| parse_u32(rate).unwrap());
+    if max_in_flight.is_some() || rate.is_some() {
+        Some(
+            RequestLimits::new()
+                .set_max_in_flight(max_in_flight)
+                .set_rate_limit(rate),
+        )
+    } else {
+        m.subcommand().1.and_then(get_request_limits)
+    }
+}
+
+/// Validate the comma separated urls
+pub fn endpoints_validator(urls: String) -> Result<(), String> {
+    urls.split(',')
+        .try_for_each(|url| parse_url(url).map(|_| ()))
+}
+
+/// Get the http transport with the headers, bearer token, proxy and tls from arg match,
+/// default to the `;` separated headers and the token of the env
+pub fn get_http_transport(
+    m: &ArgMatches,
+    env_headers: Option<&str>,
+    env_auth: Option<&str>,
+) -> Result<HttpTransport, String> {
+    let headers = find_values(m, "header")
+        .or_else(|| env_headers.map(|headers| headers.split(';').collect()))
+        .unwrap_or_default();
+    let mut transport = HttpTransport::new();
+    for header in headers
+        .into_iter()
+        .filter(|header| !header.trim().is_empty())
+    {
+        header_validator(header.to_string())?;
+        let (name, value) = header.split_once(':').unwrap();
+        transport = transport.set_header(name.trim(), value.trim());
+    }
+    if let Some(token) = find_values(m, "auth")
+        .and_then(|tokens| tokens.first().cloned())
+        .or(env_auth)
+    {
+        auth_validator(token.to_string())?;
+        transport = transport.set_bearer_token(token);
+    }
+    if let Some(proxy) = find_values(m, "proxy").and_then(|proxies| proxies.first().cloned()) {
+        transport = transport.set_proxy(Some(proxy.parse()?));
+    }
+    transport = transport.set_tls(get_tls_config(m)?);
+    Ok(transport)
+}
+
+/// Get the CA bundle, client certificate and pins from arg match
+fn get_tls_config(m: &ArgMatches) -> Result<TlsConfig, String> {
+    let read = |path: &str| fs::read(path).map_err(|err| format!("Can't read {}: {}", path, err));
+    let first = |name: &str| find_values(m, name).and_then(|values| values.first().cloned());
+    let mut tls = TlsConfig::new();
+    if let Some(path) = first("ca-bundle") {
+        tls = tls
+            .set_ca_bundle(&read(path)?)
+            .map_err(|err| err.to_string())?;
+    }
+    if let (Some(cert), Some(key)) = (first("client-cert"), first("client-key")) {
+        tls = tls
+            .set_identity(&read(cert)?, &read(key)?)
+            .map_err(|err| err.to_string())?;
+    }
+    for pin in find_values(m, "pin").unwrap_or_default() {
+        tls = tls.add_pin(pin).map_err(|err| err.to_string())?;
+    }
+    Ok(tls)
+}
+
+/// Values of the global arg, wherever it is given
+fn find_values<'a>(m: &'a ArgMatches, name: &str) -> Option<Vec<&'a str>> {
+    match m.values_of(name) {
+        Some(values) => Some(values.collect()),
+        None => m.subcommand().1.and_then(|m| find_values(m, name)),
+    }
+}
+
+/// The args of the commands generating a transaction, for its validity and nonce
+pub fn validity_nonce_args() -> [Arg<'static, 'static>; 4] {
+    [
+        Arg::with_name("valid-until-block")
+            .long("valid-until-block")
+            .takes_value(true)
+            .conflicts_with("valid-blocks")
+            .validator(|height| parse_u64(height.as_ref()).map(|_| ()))
+            .help("The last block the transaction is valid for"),
+        Arg::with_name("valid-blocks")
+            .long("valid-blocks")
+            .takes_value(true)
+            .validator(|blocks| parse_u64(blocks.as_ref()).map(|_| ()))
+            .help("Blocks after the current height the transaction is valid for, default is 88"),
+        Arg::with_name("nonce")
+            .long("nonce")
+            .takes_value(true)
+            .conflicts_with("nonce-counter")
+            .help("The nonce of transaction, default is a random uuid"),
+        Arg::with_name("nonce-counter")
+            .long("nonce-counter")
+            .help("Use the next value of the account counter as nonce, kept in ~/.cita-cli/nonces"),
+    ]
+}
+
+/// Set the validity and nonce from arg match, the counter is the one of the account
+pub fn set_validity_nonce<'a>(
+    m: &'a ArgMatches,
+    options: TransactionOptions<'a>,
+    counters: &'a Path,
+    account: Option<Address>,
+) -> Result<TransactionOptions<'a>, String> {
+    let options = options
+        .set_valid_until_block(
+            m.value_of("valid-until-block")
+                .map(|s| parse_u64(s).unwrap()),
+        )
+        .set_valid_blocks(m.value_of("valid-blocks").map(|s| parse_u64(s).unwrap()));
+    if let Some(nonce) = m.value_of("nonce") {
+        Ok(options.set_nonce(NonceSource::Fixed(nonce)))
+    } else if m.is_present("nonce-counter") {
+        let account = account.ok_or("The nonce counter needs the sender account")?;
+        if let Some(dir) = counters.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{}", err))?;
+        }
+        Ok(options.set_nonce(NonceSource::Counter(counters, account)))
+    } else {
+        Ok(options)
+    }
+}
+
+/// The args of the automatic quota, an opt-in instead of the default quota
+pub fn auto_quota_args() -> [Arg<'static, 'static>; 4] {
+    [
+        Arg::with_name("auto-quota")
+            .long("auto-quota")
+            .conflicts_with("quota")
+            .help(
+                "Estimate the quota, fail if the multiplied estimation exceeds the cap \
+                 or the account quota limit",
+            ),
+        Arg::with_name("quota-multiplier")
+            .long("quota-multiplier")
+            .takes_value(true)
+            .requires("auto-quota")
+            .validator(|multiplier| parse_multiplier(multiplier.as_ref()).map(|_| ()))
+            .help("The safety multiplier of the estimated quota, default is 1.2"),
+        Arg::with_name("quota-cap")
+            .long("quota-cap")
+            .takes_value(true)
+            .requires("auto-quota")
+            .validator(|cap| parse_u64(cap.as_ref()).map(|_| ()))
+            .help("The upper limit of the estimated quota"),
+        Arg::with_name("clamp-quota")
+            .long("clamp-quota")
+            .requires("auto-quota")
+            .help(
+                "Lower the multiplied quota to the cap or the account quota limit \
+                 instead of failing, the estimation itself must still fit",
+            ),
+    ]
+}
+
+/// Set the automatic quota from arg match, the account quota limit is the one of the sender
+pub fn set_auto_quota<'a>(
+    m: &ArgMatches,
+    options: TransactionOptions<'a>,
+    sender: Option<Address>,
+) -> TransactionOptions<'a> {
+    if !m.is_present("auto-quota") {
+        return options;
+    }
+    let mut auto_quota = AutoQuota::new()
+        .set_cap(m.value_of("quota-cap").map(|s| parse_u64(s).unwrap()))
+        .set_sender(sender)
+        .set_clamp(m.is_present("clamp-quota"));
+    if let Some(multiplier) = m.value_of("quota-multiplier") {
+        auto_quota = auto_quota.set_multiplier(parse_multiplier(multiplier).unwrap());
+    }
+    options.set_auto_quota(Some(auto_quota))
+}
+
+fn parse_multiplier(multiplier: &str) -> Result<f64, String> {
+    match multiplier.parse::<f64>() {
+        Ok(multiplier) if multiplier >= 1.0 => Ok(multiplier),
+        Ok(_) => Err("The multiplier must not be less than 1".to_string()),
+        Err(err) => Err(format!("{}", err)),
+    }
+}
+
+/// The file of the nonce counters, `~/.cita-cli/nonces`
+pub fn nonce_counters() -> PathBuf {
+    let mut path = dirs::home_dir().unwrap_or_default();
+    path.push(".cita-cli");
+    path.push("nonces");
+    path
+}
+
+/// The args of the commands sending a transaction, to wait for its receipt
+pub fn wait_args() -> [Arg<'static, 'static>; 2] {
+    [
+        Arg::with_name("wait")
+            .long("wait")
+            .help("Wait for the receipt of the transaction, fail on its error message"),
+        Arg::with_name("confirmations")
+            .long("confirmations")
+            .takes_value(true)
+            .requires("wait")
+            .validator(|confirmations| parse_u64(confirmations.as_ref()).map(|_| ()))
+            .help("Blocks on top of the block of the receipt, default is 0"),
+    ]
+}
+
+/// With `--wait`, wait for the receipt of the sent transaction, which replaces the response
+pub fn wait_receipt(
+    m: &ArgMatches,
+    client: &Client,
+    response: JsonRpcResponse,
+    valid_until_block: Option<u64>,
+) -> Result<JsonRpcResponse, CliError> {
+    let m = match find_matches(m, "wait") {
+        Some(m) => m,
+        None => return Ok(response),
+    };
+    let confirmations = m
+        .value_of("confirmations")
+        .map(|confirmations| parse_u64(confirmations).unwrap())
+        .unwrap_or(0);
+    let options = WaitOptions::new()
+        .set_valid_until_block(valid_until_block)
+        .set_confirmations(confirmations);
+    let receipt = client.wait_for_transaction(&response, &options)?;
+    let receipt = serde_json::to_value(&receipt).map_err(ToolError::SerdeJson)?;
+    Ok(JsonRpcResponse::from_result(
+        response.id(),
+        ResponseValue::from(receipt),
+    ))
+}
+
+/// The matches of the subcommand where the arg is present
+fn find_matches<'a>(m: &'a ArgMatches<'a>, name: &str) -> Option<&'a ArgMatches<'a>> {
+    if m.is_present(name) {
+        Some(m)
+    } else {
+        m.subcommand().1.and_then(|m| find_matches(m, name))
+    }
+}
+
+/// Validate the header as `Name: value`
+pub fn header_validator(header: String) -> Result<(), String> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid header, expect `Name: value`: {}", header))?;
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+    {
+        return Err(format!("Invalid header name: {}", name));
+    }
+    if !is_header_value(value) {
+        return Err(format!("Invalid header value: {}", value));
+    }
+    Ok(())
+}
+
+/// Verify the bearer token, it is sent in the `Authorization` header
+pub fn auth_validator(token: String) -> Result<(), String> {
+    if token.is_empty() || !is_header_value(&token) {
+        return Err(format!("Invalid auth token: {:?}", token));
+    }
+    Ok(())
+}
+
+/// Only visible ASCII, space and tab are valid in a header value
+fn is_header_value(value: &str) -> bool {
+    value.chars().all(|c| c == '\t' || (' '..='~').contains(&c))
+}
+
 /// the hexadecimal or numeric type string resolves to u64
 pub fn parse_u64(height: &str) -> Result<u64, String> {
     match is_hex(height) {
@@ -48,6 +373,50 @@ pub fn parse_privkey(hash: &str, encryption: Encryption) -> Result<PrivateKey, S
     Ok(PrivateKey::from_str(remove_0x(hash), encryption)?)
 }
 
+/// Set the private key of the arg to the client, which may sign with the signer instead
+pub fn set_signing_key(
+    client: &mut Client,
+    private_key: Option<&str>,
+    encryption: Encryption,
+) -> Result<(), String> {
+    match private_key {
+        Some(private_key) => {
+            client.set_private_key(&parse_privkey(private_key, encryption)?);
+            Ok(())
+        }
+        None if client.signer().is_some() => Ok(()),
+        None => Err("The private key or --signer is required".to_string()),
+    }
+}
+
+/// Get the signer of the `--signer` arg
+///
+/// `exec:<command>` runs the signer process, `unix:<path>` connects to the signer socket,
+/// `keystore:<path>` runs `key serve-keystore` as the signer process, which decrypts
+/// the keystore with the password. The key never enters this process.
+pub fn get_signer(
+    m: &ArgMatches,
+    keystore_password: Option<&str>,
+) -> Result<Option<Arc<dyn Signer>>, String> {
+    let signer = match m.value_of("signer") {
+        Some(signer) => signer,
+        None => return Ok(None),
+    };
+    let signer: Arc<dyn Signer> = match parse_signer_spec(signer).map_err(|err| err.to_string())? {
+        ("keystore", path) => {
+            let password = keystore_password.ok_or("The keystore needs KEYSTORE_PASSWORD")?;
+            let program = env::current_exe().map_err(|err| err.to_string())?;
+            let mut command = Command::new(program);
+            command
+                .args(["key", "serve-keystore", "--keystore", path])
+                .env("KEYSTORE_PASSWORD", password);
+            Arc::new(ExternalSigner::spawn(&mut command).map_err(|err| format!("{}", err))?)
+        }
+        _ => signer_from_spec(signer, None).map_err(|err| format!("{}", err))?,
+    };
+    Ok(Some(signer))
+}
+
 pub fn key_validator(hash: &str) -> Result<(), String> {
     is_hex(hash)?;
     if hash.len() > 66 {
@@ -28,7 +28,7 @@ use shell_words;
 use crate::cli::{
     abi_processor, amend_processor, benchmark_processor, build_interactive, contract_processor,
     encryption, key_processor, key_validator, rpc_processor, search_processor, store_processor,
-    string_include, transfer_processor, tx_processor,
+    string_include, transfer_processor, tx_processor, CliError,
 };
 use crate::printer::{OutputFormat, Printable, Printer};
 use cita_tool::client::basic::Client;
@@ -206,10 +206,10 @@ fn handle_commands(
     env_regex: &Regex,
     config_file: &PathBuf,
     client: &Client,
-) -> Result<bool, String> {
+) -> Result<bool, CliError> {
     let args = match shell_words::split(replace_cmd(&env_regex, line, &config).as_str()) {
         Ok(args) => args,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(e.to_string().into()),
     };
 
     match parser.clone().get_matches_from_safe(args) {
@@ -298,7 +298,7 @@ fn handle_commands(
             }
             _ => Ok(()),
         },
-        Err(err) => Err(err.to_string()),
+        Err(err) => Err(err.to_string().into()),
     }
     .map(|_| false)
 }
@@ -774,10 +774,7 @@ fn replace_cmd(regex: &Regex, line: &str, config: &GlobalConfig) -> String {
 
 pub fn set_output(response: &JsonRpcResponse, config: &mut GlobalConfig) {
     if let Some(result) = response.result() {
-        config.set(
-            "result".to_string(),
-            serde_json::from_str(serde_json::to_string(&result).unwrap().as_str()).unwrap(),
-        );
+        config.set("result".to_string(), result.into());
     }
 }
 
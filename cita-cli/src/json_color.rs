@@ -151,17 +151,6 @@ impl Colorizer {
             .build()
     }
 
-    /// Colorize a JSON string. Currently, all strings will be pretty-printed (with indentation and
-    /// spacing).
-    ///
-    /// # Errors
-    ///
-    /// An error is returned if the string is invalid JSON or an I/O error occurs.
-    pub fn colorize_json_str(&self, s: &str) -> Result<String> {
-        let value: Value = ::serde_json::from_str(s)?;
-        self.colorize_json_value(&value)
-    }
-
     /// An error is returned if the string is invalid JSON or an I/O error occurs.
     pub fn colorize_json_value(&self, value: &Value) -> Result<String> {
         let vec = self.to_vec(value)?;
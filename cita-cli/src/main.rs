@@ -10,8 +10,12 @@ use std::env;
 use std::iter::FromIterator;
 use std::process;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use cita_tool::client::basic::Client;
+use cita_tool::client::cassette::{RecordTransport, ReplayTransport};
+use cita_tool::client::middleware::LogMiddleware;
+use cita_tool::client::transport::Transport;
 use clap::crate_version;
 use dotenv::dotenv;
 
@@ -19,7 +23,8 @@ include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
 
 use crate::cli::{
     abi_processor, amend_processor, benchmark_processor, build_cli, completion_processor,
-    contract_processor, key_processor, rpc_processor, search_processor, store_processor,
+    contract_processor, devnode_processor, get_endpoints, get_http_transport, get_request_limits,
+    get_signer, key_processor, rpc_processor, search_processor, store_processor,
     transfer_processor, tx_processor,
 };
 use crate::interactive::GlobalConfig;
@@ -27,6 +32,9 @@ use crate::printer::Printer;
 
 const ENV_JSONRPC_URL: &str = "JSONRPC_URL";
 const DEFAULT_JSONRPC_URL: &str = "http://127.0.0.1:1337";
+const ENV_JSONRPC_HEADERS: &str = "JSONRPC_HEADERS";
+const ENV_JSONRPC_AUTH: &str = "JSONRPC_AUTH";
+const ENV_KEYSTORE_PASSWORD: &str = "KEYSTORE_PASSWORD";
 
 fn main() {
     dotenv().ok();
@@ -41,14 +49,70 @@ fn main() {
     let default_jsonrpc_url = env_map
         .remove(ENV_JSONRPC_URL)
         .unwrap_or_else(|| DEFAULT_JSONRPC_URL.to_owned());
+    let env_headers = env_map.remove(ENV_JSONRPC_HEADERS);
+    let env_auth = env_map.remove(ENV_JSONRPC_AUTH);
+    let env_keystore_password = env_map.remove(ENV_KEYSTORE_PASSWORD);
 
     let printer = Printer::default();
     let mut config = GlobalConfig::new(default_jsonrpc_url.to_string());
     let mut parser = build_cli(version.as_str());
     let matches = parser.clone().get_matches();
-    let client = Client::new();
+    let http = match get_http_transport(&matches, env_headers.as_deref(), env_auth.as_deref()) {
+        Ok(http) => http,
+        Err(err) => {
+            printer.eprintln(&Rc::new(err), true);
+            process::exit(1);
+        }
+    };
+    let transport: Arc<dyn Transport> = match get_endpoints(&matches) {
+        Ok(Some(endpoints)) => Arc::new(endpoints.set_transport(http)),
+        Ok(None) => Arc::new(http),
+        Err(err) => {
+            printer.eprintln(&Rc::new(err), true);
+            process::exit(1);
+        }
+    };
+    let transport = match (matches.value_of("replay"), matches.value_of("record")) {
+        (Some(path), _) => {
+            ReplayTransport::load(path).map(|replay| Arc::new(replay) as Arc<dyn Transport>)
+        }
+        (None, Some(path)) => RecordTransport::new(transport, path)
+            .map(|record| Arc::new(record) as Arc<dyn Transport>),
+        (None, None) => Ok(transport),
+    };
+    let transport = match transport {
+        Ok(transport) => transport,
+        Err(err) => {
+            printer.eprintln(&Rc::new(format!("{}", err)), true);
+            process::exit(1);
+        }
+    };
+    let mut client = Client::new().set_transport(transport);
+    if let Some(limits) = get_request_limits(&matches) {
+        client = client.set_request_limits(limits);
+    }
+    if let Some(path) = matches.value_of("log-file") {
+        match LogMiddleware::file(path) {
+            Ok(log) => client = client.add_middleware(log),
+            Err(err) => {
+                printer.eprintln(&Rc::new(format!("{}", err)), true);
+                process::exit(1);
+            }
+        }
+    }
+    match get_signer(&matches, env_keystore_password.as_deref()) {
+        Ok(Some(signer)) => {
+            client.set_signer(signer);
+        }
+        Ok(None) => {}
+        Err(err) => {
+            printer.eprintln(&Rc::new(err), true);
+            process::exit(1);
+        }
+    }
 
-    if let Err(err) = match matches.subcommand() {
+    let runtime = client.runtime().clone();
+    let result = match matches.subcommand() {
         ("rpc", Some(m)) => rpc_processor(m, &printer, &mut config, client),
         ("ethabi", Some(m)) => abi_processor(m, &printer, &config),
         ("key", Some(m)) => key_processor(m, &printer, &config),
@@ -62,6 +126,7 @@ fn main() {
         }
         ("tx", Some(m)) => tx_processor(m, &printer, &mut config, client),
         ("benchmark", Some(m)) => benchmark_processor(m, &printer, &config, client),
+        ("devnode", Some(m)) => devnode_processor(m, &printer),
         ("completions", Some(m)) => {
             completion_processor(&mut parser, m);
             Ok(())
@@ -72,9 +137,12 @@ fn main() {
             }
             Ok(())
         }
-    } {
-        printer.eprintln(&Rc::new(err), true);
-        process::exit(1);
+    };
+    // Close the connections before the process exits
+    runtime.shutdown();
+    if let Err(err) = result {
+        printer.eprintln(&Rc::new(err.to_string()), true);
+        process::exit(err.code());
     }
 }
 
@@ -141,16 +141,8 @@ impl Printable for Rc<String> {
 }
 
 impl Printable for JsonRpcResponse {
-    fn rc_string(&self, _format: OutputFormat, color: bool) -> Rc<String> {
-        let content = format!("{:?}", self);
-        let content = if color {
-            Colorizer::arbitrary()
-                .colorize_json_str(content.as_str())
-                .unwrap()
-        } else {
-            content
-        };
-        Rc::new(content)
+    fn rc_string(&self, format: OutputFormat, color: bool) -> Rc<String> {
+        serde_json::to_value(self).unwrap().rc_string(format, color)
     }
 }
 
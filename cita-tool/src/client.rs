@@ -1,14 +1,46 @@
 #[cfg(all(feature = "rustls", feature = "openssl"))]
 compile_error!("features `rustls` and `openssl` are mutually exclusive");
 
+/// Asynchronous client api, runs on the caller's runtime
+pub mod async_client;
 /// Basic client api, for Low-level interface
 pub mod basic;
+/// Cache of the chain metadata, shared across clients
+pub mod cache;
+/// Record the jsonrpc exchanges to a file and replay them without a node
+pub mod cassette;
+/// Serializable client config and client builder
+pub mod config;
+/// Multiple nodes with failover and load balancing
+pub mod endpoint;
+/// Limits of the requests in flight and of the request rate
+pub mod limit;
+/// Hooks around the requests and responses, logging and metrics
+pub mod middleware;
+/// HTTP and SOCKS5 proxies
+pub mod proxy;
+/// Quorum reads across several nodes
+pub mod quorum;
+/// Request timeout and retry policy
+pub mod retry;
+/// The runtime shared by the blocking clients
+pub mod runtime;
 /// System contract client api, call system contract more easy
 pub mod system_contract;
+/// Trust roots, client certificate and pinning of https
+pub mod tls;
+/// Jsonrpc transport, http by default, in-memory for tests
+pub mod transport;
+/// Typed results of the jsonrpc calls
+pub mod typed;
+/// Wait for the receipts of sent transactions
+pub mod wait;
+/// Websocket transport with push subscriptions
+pub mod websocket;
 
 mod transaction_option;
 
-pub use self::transaction_option::TransactionOptions;
+pub use self::transaction_option::{AutoQuota, NonceSource, TransactionOptions};
 
 use hyper::Uri;
 use std::str;
@@ -44,3 +76,28 @@ pub fn remove_0x(hex: &str) -> &str {
 pub fn parse_url(url: &str) -> Result<Uri, String> {
     url.parse().map_err(|_| "Invalid address".to_string())
 }
+
+/// Decode the `%XX` escapes of the userinfo of a url, other bytes are kept
+pub(crate) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
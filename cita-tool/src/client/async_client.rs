@@ -0,0 +1,1509 @@
+use std::collections::HashMap;
+use std::str;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::LowerHex;
+use failure::Fail;
+use futures::{future, future::join_all, stream, Future, Stream};
+use hex::{decode, encode};
+use hyper::Uri;
+use protobuf::{parse_from_bytes, Message};
+use serde;
+use serde_json;
+use types::U256;
+
+use crate::client::basic::{
+    ABI_ADDRESS, AMEND_ABI, AMEND_ADDRESS, AMEND_BALANCE, AMEND_CODE, AMEND_KV_H256, STORE_ADDRESS,
+};
+use crate::client::cache::{ChainMetadata, MetadataCache};
+use crate::client::limit::RequestLimits;
+use crate::client::middleware::{self, LogMiddleware, Middleware};
+use crate::client::proxy::Proxy;
+use crate::client::quorum;
+use crate::client::retry::RetryPolicy;
+use crate::client::system_contract::QuotaManageClient;
+use crate::client::tls::TlsConfig;
+use crate::client::transport::{HttpTransport, Transport};
+use crate::client::typed::AsyncTypedClientExt;
+use crate::client::{remove_0x, AutoQuota, TransactionOptions};
+use crate::crypto::PrivateKey;
+use crate::error::{ErrorKind, ToolError};
+use crate::protos::{Transaction, UnverifiedTransaction};
+use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue};
+use crate::signer::Signer;
+
+const BLOCK_NUMBER: &str = "blockNumber";
+const GET_META_DATA: &str = "getMetaData";
+const SEND_RAW_TRANSACTION: &str = "sendRawTransaction";
+const PEER_COUNT: &str = "peerCount";
+const PEERS_INFO: &str = "peersInfo";
+pub(crate) const GET_BLOCK_BY_HASH: &str = "getBlockByHash";
+const GET_BLOCK_BY_NUMBER: &str = "getBlockByNumber";
+const GET_TRANSACTION: &str = "getTransaction";
+const GET_TRANSACTION_PROOF: &str = "getTransactionProof";
+
+const GET_TRANSACTION_RECEIPT: &str = "getTransactionReceipt";
+pub(crate) const GET_LOGS: &str = "getLogs";
+const CALL: &str = "call";
+const GET_TRANSACTION_COUNT: &str = "getTransactionCount";
+const GET_CODE: &str = "getCode";
+const GET_ABI: &str = "getAbi";
+const GET_BALANCE: &str = "getBalance";
+
+pub(crate) const NEW_FILTER: &str = "newFilter";
+pub(crate) const NEW_BLOCK_FILTER: &str = "newBlockFilter";
+pub(crate) const UNINSTALL_FILTER: &str = "uninstallFilter";
+pub(crate) const GET_FILTER_CHANGES: &str = "getFilterChanges";
+const GET_FILTER_LOGS: &str = "getFilterLogs";
+
+const GET_BLOCK_HEADER: &str = "getBlockHeader";
+const GET_STATE_PROOF: &str = "getStateProof";
+const GET_STORAGE_AT: &str = "getStorageAt";
+
+const GET_VERSION: &str = "getVersion";
+
+const ESTIMATE_QUOTA: &str = "estimateQuota";
+
+/// Blocks a generated transaction stays valid for by default, after the current height
+pub(crate) const VALID_BLOCKS: u64 = 88;
+
+/// Boxed future returned by the asynchronous client api
+pub type ResponseFuture<T, E> = Box<dyn Future<Item = T, Error = E> + Send + 'static>;
+
+/// Boxed stream of the results of several requests, in order, a failed request is an `Err` item
+pub type ResponseStream<T> =
+    Box<dyn Stream<Item = Result<T, ToolError>, Error = ()> + Send + 'static>;
+
+/// Asynchronous jsonrpc client, Only to one chain
+///
+/// Every method returns a future, which must be driven by the caller's tokio runtime.
+/// Clones share the request id counter and the metadata cache.
+#[derive(Clone)]
+pub struct AsyncClient {
+    id: Arc<AtomicUsize>,
+    url: Uri,
+    transport: Option<Arc<dyn Transport>>,
+    http: HttpTransport,
+    retry: RetryPolicy,
+    limits: RequestLimits,
+    chain_id: Option<U256>,
+    tx_version: Option<u32>,
+    default_quota: Option<u64>,
+    valid_blocks: Option<u64>,
+    metadata: MetadataCache,
+    signer: Option<Arc<dyn Signer>>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    batch_size: Option<usize>,
+    strict: bool,
+    debug: bool,
+}
+
+impl AsyncClient {
+    /// Create an asynchronous client for CITA
+    pub fn new() -> Self {
+        AsyncClient {
+            id: Arc::new(AtomicUsize::new(0)),
+            url: "http://127.0.0.1:1337".parse().unwrap(),
+            transport: None,
+            http: HttpTransport::new(),
+            retry: RetryPolicy::new(),
+            limits: RequestLimits::new(),
+            chain_id: None,
+            tx_version: None,
+            default_quota: None,
+            valid_blocks: None,
+            metadata: MetadataCache::new(),
+            signer: None,
+            middlewares: Vec::new(),
+            batch_size: None,
+            strict: false,
+            debug: false,
+        }
+    }
+
+    /// Set url
+    /// ---
+    /// When the url address is invalid, panic
+    pub fn set_uri(mut self, url: &str) -> Self {
+        self.url = url.parse().unwrap();
+        self
+    }
+
+    /// Get url
+    pub fn uri(&self) -> &Uri {
+        &self.url
+    }
+
+    /// Set the transport which delivers the requests, default is http
+    pub fn set_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Get the transport which delivers the requests
+    pub fn transport(&self) -> Arc<dyn Transport> {
+        match self.transport {
+            Some(ref transport) => Arc::clone(transport),
+            None => Arc::new(self.http.clone()),
+        }
+    }
+
+    /// Set a header sent with every request of the default http transport
+    /// ---
+    /// When the name or value is invalid, panic
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        self.http = self.http.set_header(name, value);
+        self
+    }
+
+    /// Set the bearer token of the default http transport
+    pub fn set_bearer_token(mut self, token: &str) -> Self {
+        self.http = self.http.set_bearer_token(token);
+        self
+    }
+
+    /// Set the proxy of the default http transport, `None` to connect directly
+    ///
+    /// Default is the proxy from `HTTP_PROXY` and `HTTPS_PROXY`
+    pub fn set_proxy(mut self, proxy: Option<Proxy>) -> Self {
+        self.http = self.http.set_proxy(proxy);
+        self
+    }
+
+    /// Set the trust roots, client certificate and pins of the default http transport
+    pub fn set_tls(mut self, tls: TlsConfig) -> Self {
+        self.http = self.http.set_tls(tls);
+        self
+    }
+
+    /// Set the timeout and retry policy of the requests, default is no timeout and no retry
+    pub fn set_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        if policy.connect_timeout() != self.retry.connect_timeout() {
+            self.http = self.http.set_connect_timeout(policy.connect_timeout());
+        }
+        self.retry = policy;
+        self
+    }
+
+    /// Get retry policy
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Set the limits of the requests in flight and of the request rate, default is no limit
+    pub fn set_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Get request limits
+    pub fn request_limits(&self) -> &RequestLimits {
+        &self.limits
+    }
+
+    /// Set chain id, it overrides the one of the metadata for this client only
+    pub fn set_chain_id(&mut self, chain_id: U256) -> &mut Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the version of the transactions without one, default is the version of the chain
+    pub fn set_tx_version(mut self, version: Option<u32>) -> Self {
+        self.tx_version = version;
+        self
+    }
+
+    /// Get tx version
+    pub fn tx_version(&self) -> Option<u32> {
+        self.tx_version
+    }
+
+    /// Set the quota of the transactions without one nor auto quota, default is 10_000_000
+    pub fn set_default_quota(mut self, quota: Option<u64>) -> Self {
+        self.default_quota = quota;
+        self
+    }
+
+    /// Get default quota
+    pub fn default_quota(&self) -> Option<u64> {
+        self.default_quota
+    }
+
+    /// Set the blocks the transactions stay valid for, after the current height, default is 88
+    pub fn set_valid_blocks(mut self, blocks: Option<u64>) -> Self {
+        self.valid_blocks = blocks;
+        self
+    }
+
+    /// Get valid blocks
+    pub fn valid_blocks(&self) -> Option<u64> {
+        self.valid_blocks
+    }
+
+    /// Set the cache of the chain metadata, shared with the clients it is set to
+    pub fn set_metadata_cache(mut self, cache: MetadataCache) -> Self {
+        self.metadata = cache;
+        self
+    }
+
+    /// Get metadata cache
+    pub fn metadata_cache(&self) -> &MetadataCache {
+        &self.metadata
+    }
+
+    /// Set private key
+    pub fn set_private_key(&mut self, private_key: &PrivateKey) -> &mut Self {
+        match private_key {
+            PrivateKey::Null => {}
+            _ => self.signer = Some(Arc::new(*private_key)),
+        }
+        self
+    }
+
+    /// Set the signer of the transactions, instead of a private key
+    pub fn set_signer(&mut self, signer: Arc<dyn Signer>) -> &mut Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Get signer
+    pub fn signer(&self) -> Option<&dyn Signer> {
+        self.signer.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Get debug
+    pub fn debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Set debug mode, the requests and responses are logged to stderr
+    pub fn set_debug(mut self, mode: bool) -> Self {
+        self.debug = mode;
+        self
+    }
+
+    /// Add a middleware at the end of the chain, it sees every request and response
+    pub fn add_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Remove all the middlewares
+    pub fn clear_middlewares(mut self) -> Self {
+        self.middlewares.clear();
+        self
+    }
+
+    /// Set the maximum number of params sent in one jsonrpc batch,
+    /// default is None, every param is sent by itself
+    pub fn set_batch_size(mut self, size: Option<usize>) -> Self {
+        self.batch_size = size.map(|size| size.max(1));
+        self
+    }
+
+    /// Get batch size
+    pub fn batch_size(&self) -> Option<usize> {
+        self.batch_size
+    }
+
+    /// Set strict mode, a jsonrpc error response resolves to `ToolError::JsonRpc`
+    /// instead of an `Ok` response, default is false
+    pub fn set_strict(mut self, mode: bool) -> Self {
+        self.strict = mode;
+        self
+    }
+
+    /// Get strict
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    /// A copy of this client that no longer shares the id counter
+    pub(crate) fn detach(&self) -> Self {
+        AsyncClient {
+            id: Arc::new(AtomicUsize::new(self.id.load(Ordering::Relaxed))),
+            url: self.url.clone(),
+            transport: self.transport.clone(),
+            http: self.http.clone(),
+            retry: self.retry.clone(),
+            limits: self.limits.clone(),
+            chain_id: self.chain_id,
+            tx_version: self.tx_version,
+            default_quota: self.default_quota,
+            valid_blocks: self.valid_blocks,
+            metadata: self.metadata.clone(),
+            signer: self.signer.clone(),
+            middlewares: self.middlewares.clone(),
+            batch_size: self.batch_size,
+            strict: self.strict,
+            debug: self.debug,
+        }
+    }
+
+    /// Send requests
+    ///
+    /// With a batch size, the params are sent as jsonrpc batches of at most that size,
+    /// otherwise one request per param. The responses keep the order of the params,
+    /// the first failed request fails them all.
+    pub fn send_request<T: Iterator<Item = JsonRpcParams>>(
+        &self,
+        params: T,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        Box::new(self.send_request_stream(params).collect().then(|results| {
+            results
+                .unwrap_or_default()
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+        }))
+    }
+
+    /// Send requests, the result of each param streams back in the order of the params
+    ///
+    /// At most the `max_in_flight` of the request limits are sent at once. Every param
+    /// of a failed batch gets the error of the batch.
+    pub fn send_request_stream<T: Iterator<Item = JsonRpcParams>>(
+        &self,
+        params: T,
+    ) -> ResponseStream<JsonRpcResponse> {
+        let params = params
+            .map(|param| self.attach_id(param))
+            .collect::<Vec<JsonRpcParams>>();
+        let batches = params
+            .chunks(self.batch_size.unwrap_or(1))
+            .map(<[JsonRpcParams]>::to_vec)
+            .collect::<Vec<_>>();
+        let width = self.limits.max_in_flight().unwrap_or(batches.len()).max(1);
+
+        let client = self.clone();
+        let strict = self.strict;
+        Box::new(
+            stream::iter_ok::<_, ()>(batches)
+                .map(move |batch| {
+                    let len = batch.len();
+                    client.dispatch(&client.url, batch).then(move |result| {
+                        Ok(match result {
+                            Ok(responses) => responses
+                                .into_iter()
+                                .map(|response| check_response(response, strict))
+                                .collect(),
+                            Err(err) => batch_errors(err, len),
+                        })
+                    })
+                })
+                .buffered(width)
+                .map(stream::iter_ok::<_, ()>)
+                .flatten(),
+        )
+    }
+
+    /// Send multiple params to one node
+    pub fn send_request_with_multiple_url<T: Iterator<Item = Uri>>(
+        &self,
+        urls: T,
+        params: JsonRpcParams,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        let params = self.attach_id(params);
+
+        let strict = self.strict;
+        let reqs = urls
+            .map(|url| {
+                self.dispatch(&url, vec![params.clone()])
+                    .and_then(single_response)
+                    .and_then(move |response| check_response(response, strict))
+            })
+            .collect::<Vec<_>>();
+
+        Box::new(join_all(reqs))
+    }
+
+    /// Send one param to several nodes, resolve to the answer of at least `quorum` of them
+    ///
+    /// Answers agree when their result and error are equal. Without a quorum, or when
+    /// several answers reach it, resolve to `ToolError::Quorum` with every answer and failure.
+    pub fn send_request_with_quorum<T: Iterator<Item = Uri>>(
+        &self,
+        urls: T,
+        params: JsonRpcParams,
+        quorum: usize,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = self.attach_id(params);
+
+        let strict = self.strict;
+        let reqs = urls
+            .map(|url| {
+                self.dispatch(&url, vec![params.clone()])
+                    .and_then(single_response)
+                    .then(move |response| Ok((url, response)))
+            })
+            .collect::<Vec<_>>();
+
+        Box::new(join_all(reqs).and_then(move |responses| {
+            quorum::decide(responses, quorum).and_then(|response| check_response(response, strict))
+        }))
+    }
+
+    /// Send one request, resolve to its response
+    pub(crate) fn send_single(
+        &self,
+        params: JsonRpcParams,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        Box::new(
+            self.send_request(vec![params].into_iter())
+                .and_then(single_response),
+        )
+    }
+
+    /// Send the params with the transport, under the request limits and the retry policy,
+    /// through the middlewares
+    fn dispatch(
+        &self,
+        url: &Uri,
+        params: Vec<JsonRpcParams>,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        let transport = self.transport();
+        let mut middlewares = self.middlewares.clone();
+        if self.debug {
+            middlewares.push(Arc::new(LogMiddleware::stderr()));
+        }
+        let retry = self.retry.clone();
+        let url = url.clone();
+        Box::new(self.limits.acquire().and_then(move |permit| {
+            let request = {
+                let url = url.clone();
+                let params = params.clone();
+                retry.run(move || transport.send(&url, params.clone()))
+            };
+            middleware::intercept(middlewares, url, params, request).then(move |result| {
+                drop(permit);
+                result
+            })
+        }))
+    }
+
+    #[inline]
+    fn attach_id(&self, params: JsonRpcParams) -> JsonRpcParams {
+        let id = self.id.fetch_add(1, Ordering::Relaxed) + 1;
+        params.insert("id", ParamsValue::Int(id as u64))
+    }
+
+    /// Constructing a Transaction
+    pub fn generate_transaction(
+        &self,
+        transaction_options: TransactionOptions,
+    ) -> ResponseFuture<Transaction, ToolError> {
+        let parts = match transaction_options.parts() {
+            Ok(parts) => parts,
+            Err(err) => return Box::new(future::err(err)),
+        };
+        let quota: ResponseFuture<u64, ToolError> = match (
+            transaction_options.quota(),
+            transaction_options.auto_quota(),
+        ) {
+            (Some(quota), _) => Box::new(future::ok(quota)),
+            (None, Some(auto_quota)) => {
+                self.estimate_transaction_quota(&transaction_options, auto_quota)
+            }
+            (None, None) => Box::new(future::ok(self.default_quota.unwrap_or(10_000_000))),
+        };
+
+        let valid_blocks = transaction_options
+            .valid_blocks()
+            .or(self.valid_blocks)
+            .unwrap_or(VALID_BLOCKS);
+        let valid_until_block: ResponseFuture<u64, ToolError> = match (
+            transaction_options.valid_until_block(),
+            transaction_options.current_height(),
+        ) {
+            (Some(height), _) => Box::new(future::ok(height)),
+            (None, Some(height)) => Box::new(future::ok(height + valid_blocks)),
+            (None, None) => Box::new(
+                self.get_current_height()
+                    .map(move |height| height + valid_blocks),
+            ),
+        };
+        let version: ResponseFuture<u32, ToolError> =
+            match transaction_options.version().or(self.tx_version) {
+                Some(version) => Box::new(future::ok(version)),
+                None => Box::new(self.get_version().or_else(|_| Ok(0))),
+            };
+
+        let client = self.clone();
+        Box::new(valid_until_block.join3(version, quota).and_then(
+            move |(valid_until_block, version, quota)| {
+                let chain_id: ResponseFuture<U256, ToolError> = if version == 0 {
+                    Box::new(client.get_chain_id().map(U256::from))
+                } else if version < 3 {
+                    client.get_chain_id_v1()
+                } else {
+                    Box::new(future::err(ToolError::InvalidInput(
+                        "Invalid version".to_string(),
+                    )))
+                };
+                chain_id.and_then(move |chain_id| {
+                    parts.build(quota, valid_until_block, version, chain_id)
+                })
+            },
+        ))
+    }
+
+    /// Estimate the quota of the transaction, limited by the auto quota options
+    ///
+    /// Fail with `ToolError::QuotaExceeded` when the multiplied estimation exceeds the cap
+    /// or the account quota limit of the sender, and clamping is not set.
+    pub fn estimate_transaction_quota(
+        &self,
+        transaction_options: &TransactionOptions,
+        auto_quota: AutoQuota,
+    ) -> ResponseFuture<u64, ToolError> {
+        let sender = match auto_quota.sender() {
+            Some(sender) => Some(sender),
+            None => match self.signer().map(Signer::address) {
+                Some(Ok(sender)) => Some(sender),
+                Some(Err(err)) => return Box::new(future::err(err)),
+                None => None,
+            },
+        };
+        let sender = match sender {
+            Some(sender) => format!("0x{}", sender.lower_hex()),
+            None => {
+                return Box::new(future::err(ToolError::InvalidInput(
+                    "The auto quota needs the sender, set it or the signer".to_string(),
+                )))
+            }
+        };
+
+        let estimation = self
+            .estimate_quota(
+                Some(&sender),
+                transaction_options.address(),
+                Some(transaction_options.code()),
+                "latest",
+            )
+            .and_then(|response| {
+                if let Some(error) = response.error() {
+                    return Err(ToolError::JsonRpc(error));
+                }
+                if let Some(ResponseValue::Singe(ParamsValue::String(quota))) = response.result() {
+                    Ok(u64::from_str_radix(remove_0x(&quota), 16).map_err(ToolError::Parse)?)
+                } else {
+                    Err(ToolError::Transport(
+                        "No quota estimation in the response".to_string(),
+                    ))
+                }
+            });
+        let account_limit = QuotaManageClient::create_async(self.clone())
+            .account_quota_limit(&sender, None)
+            .map(|limit| {
+                if limit > U256::from(u64::MAX) {
+                    u64::MAX
+                } else {
+                    limit.low_u64()
+                }
+            });
+        Box::new(
+            estimation
+                .join(account_limit)
+                .and_then(move |(estimation, limit)| auto_quota.quota(estimation, limit)),
+        )
+    }
+
+    /// Constructing a UnverifiedTransaction hex string
+    #[inline]
+    pub fn generate_sign_transaction(&self, tx: &Transaction) -> Result<String, ToolError> {
+        let signer = self.signer().ok_or_else(|| {
+            ToolError::Signer("No signer, set the private key or a signer".to_string())
+        })?;
+        Ok(format!(
+            "0x{}",
+            encode(
+                tx.build_unverified_by(signer)?
+                    .write_to_bytes()
+                    .map_err(ToolError::Proto)?
+            )
+        ))
+    }
+
+    /// Send a signed transaction
+    pub fn send_signed_transaction(
+        &self,
+        param: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let byte_code = decode(remove_0x(param))
+            .map_err(ToolError::Decode)
+            .and_then(|bytes| {
+                parse_from_bytes::<UnverifiedTransaction>(bytes.as_slice())
+                    .map_err(ToolError::Proto)
+            })
+            .and_then(|tx| tx.write_to_bytes().map_err(ToolError::Proto));
+        match byte_code {
+            Ok(byte_code) => self.send_raw_bytes(format!("0x{}", encode(byte_code))),
+            Err(err) => Box::new(future::err(err)),
+        }
+    }
+
+    /// Send unsigned transactions
+    pub fn send_transaction(&self, param: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let byte_code = decode(remove_0x(param))
+            .map_err(ToolError::Decode)
+            .and_then(|bytes| parse_from_bytes(bytes.as_slice()).map_err(ToolError::Proto))
+            .and_then(|tx: Transaction| self.generate_sign_transaction(&tx));
+        match byte_code {
+            Ok(byte_code) => self.send_raw_bytes(byte_code),
+            Err(err) => Box::new(future::err(err)),
+        }
+    }
+
+    fn send_raw_bytes(&self, byte_code: String) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(SEND_RAW_TRANSACTION)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(byte_code)]),
+            );
+        self.send_single(params)
+    }
+
+    /// Get the chain metadata, from the cache unless it expired
+    pub fn chain_metadata(&self) -> ResponseFuture<ChainMetadata, ToolError> {
+        match self.metadata.metadata(&self.url.to_string()) {
+            Some(metadata) => Box::new(future::ok(metadata)),
+            None => self.refresh_metadata(),
+        }
+    }
+
+    /// Query the chain metadata and replace the cached one
+    pub fn refresh_metadata(&self) -> ResponseFuture<ChainMetadata, ToolError> {
+        let cache = self.metadata.clone();
+        let url = self.url.to_string();
+        Box::new(self.metadata("latest").map(move |metadata| {
+            let metadata = ChainMetadata::from(metadata);
+            cache.set_metadata(&url, metadata.clone());
+            metadata
+        }))
+    }
+
+    /// Get chain id
+    pub fn get_chain_id(&self) -> ResponseFuture<u32, ToolError> {
+        if let Some(chain_id) = self.chain_id.filter(|id| *id > U256::from(u32::MAX)) {
+            return Box::new(future::ok(chain_id.low_u32()));
+        }
+        Box::new(self.chain_metadata().map(|metadata| metadata.chain_id))
+    }
+
+    /// Get chain id v1
+    pub fn get_chain_id_v1(&self) -> ResponseFuture<U256, ToolError> {
+        if let Some(chain_id) = self.chain_id {
+            return Box::new(future::ok(chain_id));
+        }
+        Box::new(self.chain_metadata().map(|metadata| metadata.chain_id_v1))
+    }
+
+    /// Get block height
+    pub fn get_current_height(&self) -> ResponseFuture<u64, ToolError> {
+        Box::new(self.get_block_number().and_then(|response| {
+            if let Some(error) = response.error() {
+                return Err(ToolError::JsonRpc(error));
+            }
+            if let Some(ResponseValue::Singe(ParamsValue::String(height))) = response.result() {
+                Ok(u64::from_str_radix(remove_0x(&height), 16).map_err(ToolError::Parse)?)
+            } else {
+                Err(ToolError::Transport(
+                    "No block number in the response".to_string(),
+                ))
+            }
+        }))
+    }
+
+    /// Get version, from the cache unless it expired
+    pub fn get_version(&self) -> ResponseFuture<u32, ToolError> {
+        let url = self.url.to_string();
+        if let Some(version) = self.metadata.version(&url) {
+            return Box::new(future::ok(version));
+        }
+
+        let cache = self.metadata.clone();
+        Box::new(
+            self.call(
+                None,
+                "0xffffffffffffffffffffffffffffffffff020011",
+                Some("0x0d8e6e2c"),
+                "latest",
+            )
+            .and_then(move |response| {
+                let version = if let Some(ResponseValue::Singe(ParamsValue::String(version))) =
+                    response.result()
+                {
+                    u32::from_str_radix(remove_0x(&version), 16).map_err(ToolError::Parse)?
+                } else {
+                    0
+                };
+                cache.set_version(&url, version);
+                Ok(version)
+            }),
+        )
+    }
+}
+
+/// The error of a failed batch for each of its params
+fn batch_errors(err: ToolError, len: usize) -> Vec<Result<JsonRpcResponse, ToolError>> {
+    let copy = |err: &ToolError| match err {
+        ToolError::Timeout => ToolError::Timeout,
+        ToolError::HttpStatus(status) => ToolError::HttpStatus(*status),
+        ToolError::JsonRpc(error) => ToolError::JsonRpc(error.clone()),
+        err if err.kind() == ErrorKind::Transport => ToolError::Transport(err.to_string()),
+        err => ToolError::Customize(err.to_string()),
+    };
+    let mut errors = (1..len).map(|_| Err(copy(&err))).collect::<Vec<_>>();
+    errors.insert(0, Err(err));
+    errors
+}
+
+/// The response of a single param, a transport may answer nothing
+fn single_response(mut responses: Vec<JsonRpcResponse>) -> Result<JsonRpcResponse, ToolError> {
+    responses
+        .pop()
+        .ok_or_else(|| ToolError::Transport("No response to the request".to_string()))
+}
+
+/// The filter of `newFilter`, the blocks default to `latest`
+pub(crate) fn filter_object(
+    topic: Option<Vec<&str>>,
+    address: Option<Vec<&str>>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> ParamsValue {
+    let mut object = HashMap::new();
+    object.insert(
+        String::from("fromBlock"),
+        ParamsValue::String(String::from(from.unwrap_or("latest"))),
+    );
+    object.insert(
+        String::from("toBlock"),
+        ParamsValue::String(String::from(to.unwrap_or("latest"))),
+    );
+    object.insert(
+        String::from("topics"),
+        serde_json::from_str::<ParamsValue>(&serde_json::to_string(&topic).unwrap()).unwrap(),
+    );
+    object.insert(
+        String::from("address"),
+        serde_json::from_str::<ParamsValue>(&serde_json::to_string(&address).unwrap()).unwrap(),
+    );
+    ParamsValue::Map(object)
+}
+
+/// The error of the response as an `Err` in strict mode
+fn check_response(response: JsonRpcResponse, strict: bool) -> Result<JsonRpcResponse, ToolError> {
+    match response.error() {
+        Some(error) if strict => Err(ToolError::JsonRpc(error)),
+        _ => Ok(response),
+    }
+}
+
+impl Default for AsyncClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Asynchronous high level jsonrpc call, the futures version of
+/// [`ClientExt`](../basic/trait.ClientExt.html)
+pub trait AsyncClientExt<T, E>
+where
+    T: serde::Serialize + serde::Deserialize<'static> + ::std::fmt::Display,
+    E: Fail,
+{
+    /// peerCount: Get network peer count
+    fn get_peer_count(&self) -> ResponseFuture<T, E>;
+    /// peersInfo: Get all peers information
+    fn get_peers_info(&self) -> ResponseFuture<T, E>;
+    /// blockNumber: Get current height
+    fn get_block_number(&self) -> ResponseFuture<T, E>;
+    /// sendTransaction: Send a transaction and return transaction hash
+    fn send_raw_transaction(&self, transaction_option: TransactionOptions) -> ResponseFuture<T, E>;
+    /// getBlockByHash: Get block by hash
+    fn get_block_by_hash(&self, hash: &str, transaction_info: bool) -> ResponseFuture<T, E>;
+    /// getBlockByNumber: Get block by number
+    fn get_block_by_number(&self, height: &str, transaction_info: bool) -> ResponseFuture<T, E>;
+    /// getTransactionReceipt: Get transaction receipt
+    fn get_transaction_receipt(&self, hash: &str) -> ResponseFuture<T, E>;
+    /// getLogs: Get logs
+    fn get_logs(
+        &self,
+        topic: Option<Vec<&str>>,
+        address: Option<Vec<&str>>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> ResponseFuture<T, E>;
+    /// call: (readonly, will not save state change)
+    fn call(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<T, E>;
+    /// getTransaction: Get transaction by hash
+    fn get_transaction(&self, hash: &str) -> ResponseFuture<T, E>;
+    /// getTransactionCount: Get transaction count of an account
+    fn get_transaction_count(&self, address: &str, height: &str) -> ResponseFuture<T, E>;
+    /// getCode: Get the code of a contract
+    fn get_code(&self, address: &str, height: &str) -> ResponseFuture<T, E>;
+    /// getAbi: Get the ABI of a contract
+    fn get_abi(&self, address: &str, height: &str) -> ResponseFuture<T, E>;
+    /// getBalance: Get the balance of a contract (TODO: return U256)
+    fn get_balance(&self, address: &str, height: &str) -> ResponseFuture<T, E>;
+    /// newFilter:
+    fn new_filter(
+        &self,
+        topic: Option<Vec<&str>>,
+        address: Option<Vec<&str>>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> ResponseFuture<T, E>;
+    /// newBlockFilter:
+    fn new_block_filter(&self) -> ResponseFuture<T, E>;
+    /// uninstallFilter: Uninstall a filter by its id
+    fn uninstall_filter(&self, filter_id: &str) -> ResponseFuture<T, E>;
+    /// getFilterChanges: Get filter changes
+    fn get_filter_changes(&self, filter_id: &str) -> ResponseFuture<T, E>;
+    /// getFilterLogs: Get filter logs
+    fn get_filter_logs(&self, filter_id: &str) -> ResponseFuture<T, E>;
+    /// getTransactionProof: Get proof of a transaction
+    fn get_transaction_proof(&self, hash: &str) -> ResponseFuture<T, E>;
+    /// getMetaData: Get metadata
+    fn get_metadata(&self, height: &str) -> ResponseFuture<T, E>;
+    /// getBlockHeader: Get block headers based on block height
+    fn get_block_header(&self, height: &str) -> ResponseFuture<T, E>;
+    /// getStateProof: Get the proof of the variable at the specified height
+    fn get_state_proof(&self, address: &str, key: &str, height: &str) -> ResponseFuture<T, E>;
+    /// getStorageAt: Get the value of the key at the specified height
+    fn get_storage_at(&self, address: &str, key: &str, height: &str) -> ResponseFuture<T, E>;
+    /// getVersion: Get release version info of all modules
+    fn get_version(&self) -> ResponseFuture<T, E>;
+    /// estimateQuota: Estimate a transaction's quota used
+    fn estimate_quota(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<T, E>;
+}
+
+impl AsyncClientExt<JsonRpcResponse, ToolError> for AsyncClient {
+    fn get_peer_count(&self) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params =
+            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(PEER_COUNT)));
+        self.send_single(params)
+    }
+
+    fn get_peers_info(&self) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params =
+            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(PEERS_INFO)));
+        self.send_single(params)
+    }
+
+    fn get_block_number(&self) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params =
+            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(BLOCK_NUMBER)));
+        self.send_single(params)
+    }
+
+    fn send_raw_transaction(
+        &self,
+        transaction_option: TransactionOptions,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let client = self.clone();
+        Box::new(
+            self.generate_transaction(transaction_option)
+                .and_then(move |tx| {
+                    let byte_code = client.generate_sign_transaction(&tx)?;
+                    Ok((client, byte_code))
+                })
+                .and_then(|(client, byte_code)| client.send_signed_transaction(&byte_code)),
+        )
+    }
+
+    fn get_block_by_hash(
+        &self,
+        hash: &str,
+        transaction_info: bool,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_BLOCK_BY_HASH)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(hash)),
+                    ParamsValue::Bool(transaction_info),
+                ]),
+            );
+        self.send_single(params)
+    }
+
+    fn get_block_by_number(
+        &self,
+        height: &str,
+        transaction_info: bool,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_BLOCK_BY_NUMBER)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(height)),
+                    ParamsValue::Bool(transaction_info),
+                ]),
+            );
+        self.send_single(params)
+    }
+
+    fn get_transaction_receipt(&self, hash: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_TRANSACTION_RECEIPT)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(hash))]),
+            );
+        self.send_single(params)
+    }
+
+    fn get_logs(
+        &self,
+        topic: Option<Vec<&str>>,
+        address: Option<Vec<&str>>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let mut object = HashMap::new();
+        object.insert(
+            String::from("fromBlock"),
+            ParamsValue::String(String::from(from.unwrap_or("latest"))),
+        );
+        object.insert(
+            String::from("toBlock"),
+            ParamsValue::String(String::from(to.unwrap_or("latest"))),
+        );
+
+        if topic.is_some() {
+            object.insert(
+                String::from("topics"),
+                serde_json::from_str::<ParamsValue>(&serde_json::to_string(&topic).unwrap())
+                    .unwrap(),
+            );
+        } else {
+            object.insert(String::from("topics"), ParamsValue::List(Vec::new()));
+        }
+
+        object.insert(
+            String::from("address"),
+            serde_json::from_str::<ParamsValue>(&serde_json::to_string(&address).unwrap()).unwrap(),
+        );
+
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(GET_LOGS)))
+            .insert("params", ParamsValue::List(vec![ParamsValue::Map(object)]));
+        self.send_single(params)
+    }
+
+    fn call(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let mut object = HashMap::new();
+
+        object.insert(String::from("to"), ParamsValue::String(String::from(to)));
+        if let Some(from) = from {
+            object.insert(
+                String::from("from"),
+                ParamsValue::String(String::from(from)),
+            );
+        }
+        if let Some(data) = data {
+            object.insert(
+                String::from("data"),
+                ParamsValue::String(String::from(data)),
+            );
+        }
+
+        let param = ParamsValue::List(vec![
+            ParamsValue::Map(object),
+            ParamsValue::String(String::from(height)),
+        ]);
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(CALL)))
+            .insert("params", param);
+
+        self.send_single(params)
+    }
+
+    fn get_transaction(&self, hash: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(GET_TRANSACTION)))
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(hash))]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn get_transaction_count(
+        &self,
+        address: &str,
+        height: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_TRANSACTION_COUNT)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(address)),
+                    ParamsValue::String(String::from(height)),
+                ]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn get_code(&self, address: &str, height: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(GET_CODE)))
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(address)),
+                    ParamsValue::String(String::from(height)),
+                ]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn get_abi(&self, address: &str, height: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(GET_ABI)))
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(address)),
+                    ParamsValue::String(String::from(height)),
+                ]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn get_balance(
+        &self,
+        address: &str,
+        height: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(GET_BALANCE)))
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(address)),
+                    ParamsValue::String(String::from(height)),
+                ]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn new_filter(
+        &self,
+        topic: Option<Vec<&str>>,
+        address: Option<Vec<&str>>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(NEW_FILTER)))
+            .insert(
+                "params",
+                ParamsValue::List(vec![filter_object(topic, address, from, to)]),
+            );
+        self.send_single(params)
+    }
+
+    fn new_block_filter(&self) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new().insert(
+            "method",
+            ParamsValue::String(String::from(NEW_BLOCK_FILTER)),
+        );
+        self.send_single(params)
+    }
+
+    fn uninstall_filter(&self, filter_id: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(UNINSTALL_FILTER)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(filter_id))]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn get_filter_changes(&self, filter_id: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_FILTER_CHANGES)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(filter_id))]),
+            );
+
+        self.send_single(params)
+    }
+
+    fn get_filter_logs(&self, filter_id: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(GET_FILTER_LOGS)))
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(filter_id))]),
+            );
+        self.send_single(params)
+    }
+
+    fn get_transaction_proof(&self, hash: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_TRANSACTION_PROOF)),
+            )
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(hash))]),
+            );
+        self.send_single(params)
+    }
+
+    fn get_metadata(&self, height: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(height))]),
+            )
+            .insert("method", ParamsValue::String(String::from(GET_META_DATA)));
+        self.send_single(params)
+    }
+
+    fn get_block_header(&self, height: &str) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "params",
+                ParamsValue::List(vec![ParamsValue::String(String::from(height))]),
+            )
+            .insert(
+                "method",
+                ParamsValue::String(String::from(GET_BLOCK_HEADER)),
+            );
+        self.send_single(params)
+    }
+
+    fn get_state_proof(
+        &self,
+        address: &str,
+        key: &str,
+        height: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(address)),
+                    ParamsValue::String(String::from(key)),
+                    ParamsValue::String(String::from(height)),
+                ]),
+            )
+            .insert("method", ParamsValue::String(String::from(GET_STATE_PROOF)));
+        self.send_single(params)
+    }
+
+    fn get_storage_at(
+        &self,
+        address: &str,
+        key: &str,
+        height: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params = JsonRpcParams::new()
+            .insert(
+                "params",
+                ParamsValue::List(vec![
+                    ParamsValue::String(String::from(address)),
+                    ParamsValue::String(String::from(key)),
+                    ParamsValue::String(String::from(height)),
+                ]),
+            )
+            .insert("method", ParamsValue::String(String::from(GET_STORAGE_AT)));
+        self.send_single(params)
+    }
+
+    fn get_version(&self) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let params =
+            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(GET_VERSION)));
+        self.send_single(params)
+    }
+
+    fn estimate_quota(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+        let mut object = HashMap::new();
+
+        object.insert(String::from("to"), ParamsValue::String(String::from(to)));
+        if let Some(from) = from {
+            object.insert(
+                String::from("from"),
+                ParamsValue::String(String::from(from)),
+            );
+        }
+        if let Some(data) = data {
+            object.insert(
+                String::from("data"),
+                ParamsValue::String(String::from(data)),
+            );
+        }
+
+        let param = ParamsValue::List(vec![
+            ParamsValue::Map(object),
+            ParamsValue::String(String::from(height)),
+        ]);
+        let params = JsonRpcParams::new()
+            .insert("method", ParamsValue::String(String::from(ESTIMATE_QUOTA)))
+            .insert("params", param);
+
+        self.send_single(params)
+    }
+}
+
+/// Store data or contract ABI to chain, the futures version of
+/// [`StoreExt`](../basic/trait.StoreExt.html)
+pub trait AsyncStoreExt<T, E>: AsyncClientExt<T, E>
+where
+    T: serde::Serialize + serde::Deserialize<'static> + ::std::fmt::Display,
+    E: Fail + From<ToolError>,
+{
+    /// Store data to chain, data can be get back by `getTransaction` rpc call
+    fn store_data(&self, content: &str, quota: Option<u64>) -> ResponseFuture<T, E> {
+        let tx_options = TransactionOptions::new()
+            .set_code(content)
+            .set_address(STORE_ADDRESS)
+            .set_quota(quota);
+        self.send_raw_transaction(tx_options)
+    }
+
+    /// Store contract ABI to chain, ABI can be get back by `getAbi` rpc call
+    fn store_abi(
+        &self,
+        address: &str,
+        content: String,
+        quota: Option<u64>,
+    ) -> ResponseFuture<T, E> {
+        let address = remove_0x(address);
+        let content_abi = encode(content);
+        let data = format!("0x{}{}", address, content_abi);
+        let tx_options = TransactionOptions::new()
+            .set_code(&data)
+            .set_address(ABI_ADDRESS)
+            .set_quota(quota);
+        self.send_raw_transaction(tx_options)
+    }
+}
+
+impl AsyncStoreExt<JsonRpcResponse, ToolError> for AsyncClient {}
+
+/// Amend(Update) ABI/contract code/H256KV, the futures version of
+/// [`AmendExt`](../basic/trait.AmendExt.html)
+pub trait AsyncAmendExt<T, E>: AsyncClientExt<T, E>
+where
+    T: serde::Serialize + serde::Deserialize<'static> + ::std::fmt::Display,
+    E: Fail + From<ToolError>,
+{
+    /// Amend contract code
+    fn amend_code(&self, address: &str, content: &str, quota: Option<u64>) -> ResponseFuture<T, E> {
+        let address = remove_0x(address);
+        let content = remove_0x(content);
+        let data = format!("0x{}{}", address, content);
+        let tx_options = TransactionOptions::new()
+            .set_code(&data)
+            .set_address(AMEND_ADDRESS)
+            .set_quota(quota)
+            .set_value(Some(U256::from_str(remove_0x(AMEND_CODE)).unwrap()));
+        self.send_raw_transaction(tx_options)
+    }
+
+    /// Amend contract ABI
+    fn amend_abi(
+        &self,
+        address: &str,
+        content: String,
+        quota: Option<u64>,
+    ) -> ResponseFuture<T, E> {
+        let address = remove_0x(address);
+        let content_abi = encode(content);
+        let data = format!("0x{}{}", address, content_abi);
+        let tx_options = TransactionOptions::new()
+            .set_code(&data)
+            .set_address(AMEND_ADDRESS)
+            .set_quota(quota)
+            .set_value(Some(U256::from_str(remove_0x(AMEND_ABI)).unwrap()));
+        self.send_raw_transaction(tx_options)
+    }
+
+    /// Amend H256KV
+    fn amend_h256kv(
+        &self,
+        address: &str,
+        h256_kv: &str,
+        quota: Option<u64>,
+    ) -> ResponseFuture<T, E> {
+        let address = remove_0x(address);
+        let data = format!("0x{}{}", address, h256_kv);
+        let tx_options = TransactionOptions::new()
+            .set_code(&data)
+            .set_address(AMEND_ADDRESS)
+            .set_quota(quota)
+            .set_value(Some(U256::from_str(remove_0x(AMEND_KV_H256)).unwrap()));
+        self.send_raw_transaction(tx_options)
+    }
+
+    /// Amend account balance
+    fn amend_balance(
+        &self,
+        address: &str,
+        balance: U256,
+        quota: Option<u64>,
+    ) -> ResponseFuture<T, E> {
+        let address = remove_0x(address);
+        let data = format!("0x{}{}", address, balance.completed_lower_hex());
+        let tx_options = TransactionOptions::new()
+            .set_code(&data)
+            .set_address(AMEND_ADDRESS)
+            .set_quota(quota)
+            .set_value(Some(U256::from_str(remove_0x(AMEND_BALANCE)).unwrap()));
+        self.send_raw_transaction(tx_options)
+    }
+}
+
+impl AsyncAmendExt<JsonRpcResponse, ToolError> for AsyncClient {}
+
+/// Account transfer, only applies to charge mode, the futures version of
+/// [`Transfer`](../basic/trait.Transfer.html)
+pub trait AsyncTransfer<T, E>: AsyncClientExt<T, E>
+where
+    T: serde::Serialize + serde::Deserialize<'static> + ::std::fmt::Display,
+    E: Fail,
+{
+    /// Account transfer, only applies to charge mode
+    fn transfer(&self, value: U256, address: &str, quota: Option<u64>) -> ResponseFuture<T, E> {
+        let tx_options = TransactionOptions::new()
+            .set_address(address)
+            .set_quota(quota)
+            .set_value(Some(value));
+        self.send_raw_transaction(tx_options)
+    }
+}
+
+impl AsyncTransfer<JsonRpcResponse, ToolError> for AsyncClient {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+    use hyper::service::service_fn_ok;
+    use hyper::{Body, Response, Server};
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_runs_on_caller_runtime() {
+        let mut runtime = Runtime::new().unwrap();
+        let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(|| {
+            service_fn_ok(|_| {
+                Response::new(Body::from(r#"{"jsonrpc":"2.0","id":1,"result":"0x1b"}"#))
+            })
+        });
+        let url = format!("http://{}", server.local_addr());
+        runtime.spawn(server.map_err(|_| ()));
+
+        let client = AsyncClient::new().set_uri(&url);
+        assert_eq!(runtime.block_on(client.get_current_height()).unwrap(), 27);
+    }
+
+    /// Answer no request at all
+    struct Silent;
+
+    impl Transport for Silent {
+        fn send(
+            &self,
+            _url: &Uri,
+            _params: Vec<JsonRpcParams>,
+        ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+            Box::new(future::ok(Vec::new()))
+        }
+    }
+
+    #[test]
+    fn test_missing_response() {
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(Silent);
+        let url = || vec![client.url.clone()].into_iter();
+        let params =
+            JsonRpcParams::new().insert("method", ParamsValue::String("blockNumber".to_string()));
+
+        match runtime.block_on(client.get_current_height()) {
+            Err(ToolError::Transport(_)) => {}
+            other => panic!("expect a transport error, got {:?}", other),
+        }
+        match runtime.block_on(client.send_request_with_multiple_url(url(), params.clone())) {
+            Err(ToolError::Transport(_)) => {}
+            other => panic!("expect a transport error, got {:?}", other),
+        }
+        assert!(runtime
+            .block_on(client.send_request_with_quorum(url(), params, 1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_current_height_rpc_error() {
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(MemoryTransport::new());
+        match runtime.block_on(client.get_current_height()) {
+            Err(ToolError::JsonRpc(error)) => assert_eq!(error.code(), -32601),
+            other => panic!("expect the jsonrpc error, got {:?}", other),
+        }
+    }
+}
@@ -1,57 +1,29 @@
-use std::collections::HashMap;
 use std::str::FromStr;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::{str, u64};
+use std::sync::Arc;
 
 use crate::LowerHex;
 use failure::Fail;
-use futures::{future::join_all, future::JoinAll, sync, Future, Stream};
-use hex::{decode, encode};
-use hyper::{client::HttpConnector, Body, Client as HyperClient, Request, Uri};
-use protobuf::{parse_from_bytes, Message};
+use futures::{Future, Stream};
+use hex::encode;
+use hyper::Uri;
 use serde;
-use serde_json;
-use tokio;
 use types::U256;
-use uuid::Uuid;
 
+use crate::client::async_client::{AsyncClient, AsyncClientExt, ResponseFuture};
+use crate::client::cache::{ChainMetadata, MetadataCache};
+use crate::client::limit::RequestLimits;
+use crate::client::middleware::Middleware;
+use crate::client::proxy::Proxy;
+use crate::client::retry::RetryPolicy;
+use crate::client::runtime::ClientRuntime;
+use crate::client::tls::TlsConfig;
+use crate::client::transport::Transport;
 use crate::client::{remove_0x, TransactionOptions};
 use crate::crypto::PrivateKey;
 use crate::error::ToolError;
-use crate::protos::{Transaction, UnverifiedTransaction};
-use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue};
-
-const BLOCK_NUMBER: &str = "blockNumber";
-const GET_META_DATA: &str = "getMetaData";
-const SEND_RAW_TRANSACTION: &str = "sendRawTransaction";
-const PEER_COUNT: &str = "peerCount";
-const PEERS_INFO: &str = "peersInfo";
-const GET_BLOCK_BY_HASH: &str = "getBlockByHash";
-const GET_BLOCK_BY_NUMBER: &str = "getBlockByNumber";
-const GET_TRANSACTION: &str = "getTransaction";
-const GET_TRANSACTION_PROOF: &str = "getTransactionProof";
-
-const GET_TRANSACTION_RECEIPT: &str = "getTransactionReceipt";
-const GET_LOGS: &str = "getLogs";
-const CALL: &str = "call";
-const GET_TRANSACTION_COUNT: &str = "getTransactionCount";
-const GET_CODE: &str = "getCode";
-const GET_ABI: &str = "getAbi";
-const GET_BALANCE: &str = "getBalance";
-
-const NEW_FILTER: &str = "newFilter";
-const NEW_BLOCK_FILTER: &str = "newBlockFilter";
-const UNINSTALL_FILTER: &str = "uninstallFilter";
-const GET_FILTER_CHANGES: &str = "getFilterChanges";
-const GET_FILTER_LOGS: &str = "getFilterLogs";
-
-const GET_BLOCK_HEADER: &str = "getBlockHeader";
-const GET_STATE_PROOF: &str = "getStateProof";
-const GET_STORAGE_AT: &str = "getStorageAt";
-
-const GET_VERSION: &str = "getVersion";
-
-const ESTIMATE_QUOTA: &str = "estimateQuota";
+use crate::protos::Transaction;
+use crate::rpctypes::{JsonRpcParams, JsonRpcResponse};
+use crate::signer::Signer;
 
 /// Store action target address
 pub const STORE_ADDRESS: &str = "0xffffffffffffffffffffffffffffffffff010000";
@@ -70,96 +42,228 @@ pub const AMEND_KV_H256: &str = "0x03";
 pub const AMEND_BALANCE: &str = "0x05";
 
 /// Jsonrpc client, Only to one chain
+///
+/// The blocking wrapper of [`AsyncClient`](../async_client/struct.AsyncClient.html),
+/// every call is driven to completion on a background runtime.
+/// Clones share the runtime and the connections of the http transport.
 pub struct Client {
-    id: AtomicUsize,
-    url: Uri,
-    sender: sync::mpsc::UnboundedSender<Box<dyn Future<Item = (), Error = ()> + Send + 'static>>,
-    chain_id: Option<U256>,
-    private_key: Option<PrivateKey>,
-    debug: bool,
+    inner: AsyncClient,
+    runtime: ClientRuntime,
 }
 
 impl Client {
-    /// Create a client for CITA
+    /// Create a client for CITA, on the runtime shared by the clients
     pub fn new() -> Self {
-        let (sender, receiver) = sync::mpsc::unbounded();
+        Client {
+            inner: AsyncClient::new(),
+            runtime: ClientRuntime::shared(),
+        }
+    }
 
-        ::std::thread::spawn(move || {
-            let task = receiver
-                .map_err(|_| ::std::io::Error::new(::std::io::ErrorKind::Other, ""))
-                .for_each(|item| {
-                    tokio::spawn(item);
-                    Ok(())
-                })
-                .map_err(|_| ());
+    /// Set the runtime which drives the requests
+    pub fn set_runtime(mut self, runtime: ClientRuntime) -> Self {
+        self.runtime = runtime;
+        self
+    }
 
-            tokio::run(task);
-        });
+    /// Get runtime
+    pub fn runtime(&self) -> &ClientRuntime {
+        &self.runtime
+    }
 
-        Client {
-            id: AtomicUsize::new(0),
-            url: "http://127.0.0.1:1337".parse().unwrap(),
-            sender,
-            chain_id: None,
-            private_key: None,
-            debug: false,
-        }
+    /// Shut down the runtime of the client, the clients sharing it fail afterwards
+    pub fn shutdown(&self) {
+        self.runtime.shutdown()
     }
 
     /// Set url
     /// ---
     /// When the url address is invalid, panic
     pub fn set_uri(mut self, url: &str) -> Self {
-        self.url = url.parse().unwrap();
+        self.inner = self.inner.set_uri(url);
         self
     }
 
     /// Get url
     pub fn uri(&self) -> &Uri {
-        &self.url
+        self.inner.uri()
+    }
+
+    /// Set the transport which delivers the requests, default is http
+    pub fn set_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.inner = self.inner.set_transport(transport);
+        self
+    }
+
+    /// Get the transport which delivers the requests
+    pub fn transport(&self) -> Arc<dyn Transport> {
+        self.inner.transport()
+    }
+
+    /// Set the limits of the requests in flight and of the request rate, default is no limit
+    pub fn set_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.inner = self.inner.set_request_limits(limits);
+        self
+    }
+
+    /// Get request limits
+    pub fn request_limits(&self) -> &RequestLimits {
+        self.inner.request_limits()
     }
 
     /// Set chain id
     pub fn set_chain_id(&mut self, chain_id: U256) -> &mut Self {
-        self.chain_id = Some(chain_id);
+        self.inner.set_chain_id(chain_id);
+        self
+    }
+
+    /// Set the version of the transactions without one, default is the version of the chain
+    pub fn set_tx_version(mut self, version: Option<u32>) -> Self {
+        self.inner = self.inner.set_tx_version(version);
         self
     }
 
+    /// Set the quota of the transactions without one nor auto quota, default is 10_000_000
+    pub fn set_default_quota(mut self, quota: Option<u64>) -> Self {
+        self.inner = self.inner.set_default_quota(quota);
+        self
+    }
+
+    /// Set the blocks the transactions stay valid for, after the current height, default is 88
+    pub fn set_valid_blocks(mut self, blocks: Option<u64>) -> Self {
+        self.inner = self.inner.set_valid_blocks(blocks);
+        self
+    }
+
+    /// Set the cache of the chain metadata, shared with the clients it is set to
+    pub fn set_metadata_cache(mut self, cache: MetadataCache) -> Self {
+        self.inner = self.inner.set_metadata_cache(cache);
+        self
+    }
+
+    /// Get metadata cache
+    pub fn metadata_cache(&self) -> &MetadataCache {
+        self.inner.metadata_cache()
+    }
+
     /// Set private key
     pub fn set_private_key(&mut self, private_key: &PrivateKey) -> &mut Self {
-        match private_key {
-            PrivateKey::Null => {}
-            _ => self.private_key = Some(*private_key),
-        }
+        self.inner.set_private_key(private_key);
         self
     }
 
-    /// Get private key
-    pub fn private_key(&self) -> Option<&PrivateKey> {
-        self.private_key.as_ref()
+    /// Set the signer of the transactions, instead of a private key
+    pub fn set_signer(&mut self, signer: Arc<dyn Signer>) -> &mut Self {
+        self.inner.set_signer(signer);
+        self
+    }
+
+    /// Get signer
+    pub fn signer(&self) -> Option<&dyn Signer> {
+        self.inner.signer()
     }
 
     /// Get debug
     pub fn debug(&self) -> bool {
-        self.debug
+        self.inner.debug()
     }
 
-    /// Set debug mode
+    /// Set debug mode, the requests and responses are logged to stderr
     pub fn set_debug(mut self, mode: bool) -> Self {
-        self.debug = mode;
+        self.inner = self.inner.set_debug(mode);
+        self
+    }
+
+    /// Set strict mode, a jsonrpc error response is a `ToolError::JsonRpc`
+    pub fn set_strict(mut self, mode: bool) -> Self {
+        self.inner = self.inner.set_strict(mode);
+        self
+    }
+
+    /// Get strict
+    pub fn strict(&self) -> bool {
+        self.inner.strict()
+    }
+
+    /// Add a middleware at the end of the chain, it sees every request and response
+    pub fn add_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.inner = self.inner.add_middleware(middleware);
+        self
+    }
+
+    /// Remove all the middlewares
+    pub fn clear_middlewares(mut self) -> Self {
+        self.inner = self.inner.clear_middlewares();
+        self
+    }
+
+    /// Set a header sent with every request of the default http transport
+    /// ---
+    /// When the name or value is invalid, panic
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        self.inner = self.inner.set_header(name, value);
         self
     }
 
+    /// Set the bearer token of the default http transport
+    pub fn set_bearer_token(mut self, token: &str) -> Self {
+        self.inner = self.inner.set_bearer_token(token);
+        self
+    }
+
+    /// Set the proxy of the default http transport, `None` to connect directly
+    ///
+    /// Default is the proxy from `HTTP_PROXY` and `HTTPS_PROXY`
+    pub fn set_proxy(mut self, proxy: Option<Proxy>) -> Self {
+        self.inner = self.inner.set_proxy(proxy);
+        self
+    }
+
+    /// Set the trust roots, client certificate and pins of the default http transport
+    pub fn set_tls(mut self, tls: TlsConfig) -> Self {
+        self.inner = self.inner.set_tls(tls);
+        self
+    }
+
+    /// Set the timeout and retry policy of the requests, default is no timeout and no retry
+    pub fn set_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.inner = self.inner.set_retry_policy(policy);
+        self
+    }
+
+    /// Set the maximum number of params sent in one jsonrpc batch,
+    /// default is None, every param is sent by itself
+    pub fn set_batch_size(mut self, size: Option<usize>) -> Self {
+        self.inner = self.inner.set_batch_size(size);
+        self
+    }
+
+    /// Get the asynchronous client behind this blocking client
+    pub fn async_client(&self) -> &AsyncClient {
+        &self.inner
+    }
+
     /// Send requests
     pub fn send_request<T: Iterator<Item = JsonRpcParams>>(
         &self,
         params: T,
     ) -> Result<Vec<JsonRpcResponse>, ToolError> {
-        let params = params.collect::<Vec<JsonRpcParams>>();
-
-        let reqs = self.make_requests_with_params_list(params.into_iter());
+        self.run(self.inner.send_request(params))
+    }
 
-        self.run(reqs)
+    /// Send requests, with the result of each param in the order of the params
+    ///
+    /// Fails as a whole only when the requests can't be run, e.g. after `shutdown`
+    pub fn send_request_each<T: Iterator<Item = JsonRpcParams>>(
+        &self,
+        params: T,
+    ) -> Result<Vec<Result<JsonRpcResponse, ToolError>>, ToolError> {
+        let results = self
+            .inner
+            .send_request_stream(params)
+            .collect()
+            .map_err(|_| ToolError::Transport("The request stream failed".to_string()));
+        self.run(Box::new(results))
     }
 
     /// Send multiple params to one node
@@ -168,97 +272,17 @@ impl Client {
         url: T,
         params: JsonRpcParams,
     ) -> Result<Vec<JsonRpcResponse>, ToolError> {
-        let reqs = self.make_requests_with_all_url(url, params);
-
-        self.run(reqs)
+        self.run(self.inner.send_request_with_multiple_url(url, params))
     }
 
-    #[inline]
-    fn make_requests_with_all_url<T: Iterator<Item = Uri>>(
+    /// Send one param to several nodes, return the answer of at least `quorum` of them
+    pub fn send_request_with_quorum<T: Iterator<Item = Uri>>(
         &self,
-        urls: T,
+        url: T,
         params: JsonRpcParams,
-    ) -> JoinAll<Vec<Box<dyn Future<Item = JsonRpcResponse, Error = ToolError> + 'static + Send>>>
-    {
-        self.id.fetch_add(1, Ordering::Relaxed);
-        let params = params.insert(
-            "id",
-            ParamsValue::Int(self.id.load(Ordering::Relaxed) as u64),
-        );
-
-        if self.debug {
-            Self::debug_request(vec![&params].into_iter())
-        }
-
-        let client = create_client();
-        let mut reqs = Vec::with_capacity(100);
-        urls.for_each(|url| {
-            let req: Request<Body> = Request::builder()
-                .uri(url)
-                .method("POST")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&params).unwrap()))
-                .unwrap();
-            let future: Box<
-                dyn Future<Item = JsonRpcResponse, Error = ToolError> + 'static + Send,
-            > = Box::new(
-                client
-                    .request(req)
-                    .and_then(|res| res.into_body().concat2())
-                    .map_err(ToolError::Hyper)
-                    .and_then(|response| {
-                        serde_json::from_slice::<JsonRpcResponse>(&response)
-                            .map_err(ToolError::SerdeJson)
-                    }),
-            );
-            reqs.push(future);
-        });
-        join_all(reqs)
-    }
-
-    #[inline]
-    fn make_requests_with_params_list<T: Iterator<Item = JsonRpcParams>>(
-        &self,
-        params: T,
-    ) -> JoinAll<Vec<Box<dyn Future<Item = JsonRpcResponse, Error = ToolError> + 'static + Send>>>
-    {
-        let client = create_client();
-        let mut reqs = Vec::with_capacity(100);
-        params
-            .map(|param| {
-                self.id.fetch_add(1, Ordering::Relaxed);
-                let param = param.insert(
-                    "id",
-                    ParamsValue::Int(self.id.load(Ordering::Relaxed) as u64),
-                );
-                if self.debug {
-                    Self::debug_request(vec![&param].into_iter())
-                }
-                param
-            })
-            .for_each(|param| {
-                let req: Request<Body> = Request::builder()
-                    .uri(self.url.clone())
-                    .method("POST")
-                    .header("Content-Type", "application/json")
-                    .body(Body::from(serde_json::to_string(&param).unwrap()))
-                    .unwrap();
-                let future: Box<
-                    dyn Future<Item = JsonRpcResponse, Error = ToolError> + 'static + Send,
-                > = Box::new(
-                    client
-                        .request(req)
-                        .and_then(|res| res.into_body().concat2())
-                        .map_err(ToolError::Hyper)
-                        .and_then(|response| {
-                            serde_json::from_slice::<JsonRpcResponse>(&response)
-                                .map_err(ToolError::SerdeJson)
-                        }),
-                );
-                reqs.push(future);
-            });
-
-        join_all(reqs)
+        quorum: usize,
+    ) -> Result<JsonRpcResponse, ToolError> {
+        self.run(self.inner.send_request_with_quorum(url, params, quorum))
     }
 
     /// Constructing a Transaction
@@ -266,221 +290,79 @@ impl Client {
         &mut self,
         transaction_options: TransactionOptions,
     ) -> Result<Transaction, ToolError> {
-        let data = decode(remove_0x(transaction_options.code())).map_err(ToolError::Decode)?;
-        let current_height = transaction_options
-            .current_height()
-            .ok_or_else(|| ToolError::Customize("No height input".to_string()))
-            .or_else(|_| self.get_current_height())?;
-
-        let mut tx = Transaction::new();
-        tx.set_data(data);
-
-        tx.set_nonce(encode(Uuid::new_v4().as_bytes()));
-        tx.set_valid_until_block(current_height + 88);
-        tx.set_quota(transaction_options.quota().unwrap_or_else(|| 10_000_000));
-        let value = transaction_options
-            .value()
-            .map(|value| value.completed_lower_hex())
-            .unwrap_or_else(|| U256::zero().completed_lower_hex());
-        tx.set_value(decode(value).map_err(ToolError::Decode)?);
-
-        let version = transaction_options
-            .version()
-            .unwrap_or_else(|| self.get_version().unwrap_or_else(|_| 0));
-
-        if version == 0 {
-            // Create a contract if the target address is empty
-            tx.set_to(remove_0x(transaction_options.address()).to_string());
-            tx.set_chain_id(self.get_chain_id()?);
-        } else if version < 3 {
-            // Create a contract if the target address is empty
-            tx.set_to_v1(
-                decode(remove_0x(transaction_options.address())).map_err(ToolError::Decode)?,
-            );
-            tx.set_chain_id_v1(
-                decode(self.get_chain_id_v1()?.completed_lower_hex()).map_err(ToolError::Decode)?,
-            );
-        } else {
-            return Err(ToolError::Customize("Invalid version".to_string()));
-        }
-
-        tx.set_version(version);
-
-        Ok(tx)
+        self.run(self.inner.generate_transaction(transaction_options))
     }
 
     /// Constructing a UnverifiedTransaction hex string
     #[inline]
     pub fn generate_sign_transaction(&self, tx: &Transaction) -> Result<String, ToolError> {
-        Ok(format!(
-            "0x{}",
-            encode(
-                tx.build_unverified(*self.private_key().ok_or_else(|| ToolError::Customize(
-                    "The provided private key do not match the algorithm".to_string(),
-                ))?)
-                .write_to_bytes()
-                .map_err(ToolError::Proto)?
-            )
-        ))
+        self.inner.generate_sign_transaction(tx)
     }
 
     /// Send a signed transaction
     pub fn send_signed_transaction(&mut self, param: &str) -> Result<JsonRpcResponse, ToolError> {
-        let byte_code = format!(
-            "0x{}",
-            encode(
-                parse_from_bytes::<UnverifiedTransaction>(
-                    decode(remove_0x(param))
-                        .map_err(ToolError::Decode)?
-                        .as_slice()
-                )
-                .map_err(ToolError::Proto)?
-                .write_to_bytes()
-                .map_err(ToolError::Proto)?
-            )
-        );
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(SEND_RAW_TRANSACTION)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(byte_code)]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(self.inner.send_signed_transaction(param))
     }
 
     /// Send unsigned transactions
     pub fn send_transaction(&mut self, param: &str) -> Result<JsonRpcResponse, ToolError> {
-        let tx: Transaction = parse_from_bytes(
-            decode(remove_0x(param))
-                .map_err(ToolError::Decode)?
-                .as_slice(),
-        )
-        .map_err(ToolError::Proto)?;
-        let byte_code = self.generate_sign_transaction(&tx)?;
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(SEND_RAW_TRANSACTION)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(byte_code)]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(self.inner.send_transaction(param))
     }
 
-    /// Get chain id
-    pub fn get_chain_id(&mut self) -> Result<u32, ToolError> {
-        if self.chain_id.is_some() && self.check_chain_id() {
-            Ok(self.chain_id.unwrap().low_u32())
-        } else if let Some(ResponseValue::Map(mut value)) = self.get_metadata("latest")?.result() {
-            match value.remove("chainId").unwrap() {
-                ParamsValue::Int(chain_id) => {
-                    self.chain_id = Some(U256::from(chain_id));
-                    Ok(chain_id as u32)
-                }
-                _ => Ok(0),
-            }
-        } else {
-            Ok(0)
-        }
+    /// Get the chain metadata, from the cache unless it expired
+    pub fn chain_metadata(&self) -> Result<ChainMetadata, ToolError> {
+        self.run(self.inner.chain_metadata())
     }
 
-    #[inline]
-    fn check_chain_id(&self) -> bool {
-        self.chain_id
-            .map(|id| id > U256::from(u32::max_value()))
-            .unwrap_or(false)
+    /// Query the chain metadata and replace the cached one
+    pub fn refresh_metadata(&self) -> Result<ChainMetadata, ToolError> {
+        self.run(self.inner.refresh_metadata())
+    }
+
+    /// Get chain id
+    pub fn get_chain_id(&mut self) -> Result<u32, ToolError> {
+        self.run(self.inner.get_chain_id())
     }
 
     /// Get chain id v1
     pub fn get_chain_id_v1(&mut self) -> Result<U256, ToolError> {
-        if self.chain_id.is_some() {
-            Ok(self.chain_id.unwrap())
-        } else if let Some(ResponseValue::Map(mut value)) = self.get_metadata("latest")?.result() {
-            match value.remove("chainIdV1") {
-                Some(ParamsValue::String(chain_id)) => {
-                    let chain_id = U256::from_str(remove_0x(&chain_id))
-                        .map_err(|e| ToolError::Customize(e.to_string()))?;
-                    self.chain_id = Some(chain_id);
-                    Ok(chain_id)
-                }
-                _ => Ok(U256::zero()),
-            }
-        } else {
-            Ok(U256::zero())
-        }
+        self.run(self.inner.get_chain_id_v1())
     }
 
     /// Get block height
     pub fn get_current_height(&self) -> Result<u64, ToolError> {
-        let params =
-            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(BLOCK_NUMBER)));
-        let response = self.send_request(vec![params].into_iter())?.pop().unwrap();
-
-        if let Some(ResponseValue::Singe(ParamsValue::String(height))) = response.result() {
-            Ok(u64::from_str_radix(remove_0x(&height), 16).map_err(ToolError::Parse)?)
-        } else {
-            Err(ToolError::Customize(
-                "Corresponding address does not respond".to_string(),
-            ))
-        }
+        self.run(self.inner.get_current_height())
     }
 
     /// Get version
     pub fn get_version(&self) -> Result<u32, ToolError> {
-        if let Some(ResponseValue::Singe(ParamsValue::String(version))) = self
-            .call(
-                None,
-                "0xffffffffffffffffffffffffffffffffff020011",
-                Some("0x0d8e6e2c"),
-                "latest",
-            )?
-            .result()
-        {
-            Ok(u32::from_str_radix(remove_0x(&version), 16).map_err(ToolError::Parse)?)
-        } else {
-            Ok(0)
-        }
+        self.run(self.inner.get_version())
     }
 
     /// Start run
-    fn run(
+    pub(crate) fn run<T: Send + 'static>(
         &self,
-        reqs: JoinAll<
-            Vec<Box<dyn Future<Item = JsonRpcResponse, Error = ToolError> + 'static + Send>>,
-        >,
-    ) -> Result<Vec<JsonRpcResponse>, ToolError> {
-        let (tx, rx) = sync::oneshot::channel::<Result<Vec<JsonRpcResponse>, ToolError>>();
-        let req = reqs
-            .then(move |res| tx.send(res))
-            .map(|_| ())
-            .map_err(|_| ());
-        self.sender
-            .unbounded_send(Box::new(req))
-            .map_err(|e| ToolError::Customize(e.to_string()))?;
-        rx.wait().map_err(|e| ToolError::Customize(e.to_string()))?
-    }
-
-    fn debug_request<'a, T: Iterator<Item = &'a JsonRpcParams>>(params: T) {
-        params.for_each(|param| {
-            println!("<--{}", param);
-        });
+        req: ResponseFuture<T, ToolError>,
+    ) -> Result<T, ToolError> {
+        self.runtime.block_on(req)
     }
 }
 
 impl Clone for Client {
     fn clone(&self) -> Self {
         Client {
-            id: AtomicUsize::new(self.id.load(Ordering::Relaxed)),
-            url: self.url.clone(),
-            sender: self.sender.clone(),
-            chain_id: None,
-            private_key: self.private_key,
-            debug: self.debug,
+            inner: self.inner.detach(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl From<AsyncClient> for Client {
+    /// Wrap the asynchronous client, on the runtime shared by the clients
+    fn from(inner: AsyncClient) -> Self {
+        Client {
+            inner,
+            runtime: ClientRuntime::shared(),
         }
     }
 }
@@ -601,30 +483,25 @@ where
 
 impl ClientExt<JsonRpcResponse, ToolError> for Client {
     fn get_peer_count(&self) -> Result<JsonRpcResponse, ToolError> {
-        let params =
-            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(PEER_COUNT)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_peer_count(&self.inner))
     }
 
     fn get_peers_info(&self) -> Result<JsonRpcResponse, ToolError> {
-        let params =
-            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(PEERS_INFO)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_peers_info(&self.inner))
     }
 
     fn get_block_number(&self) -> Result<JsonRpcResponse, ToolError> {
-        let params =
-            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(BLOCK_NUMBER)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_block_number(&self.inner))
     }
 
     fn send_raw_transaction(
         &mut self,
         transaction_option: TransactionOptions,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let tx = self.generate_transaction(transaction_option)?;
-        let byte_code = self.generate_sign_transaction(&tx)?;
-        Ok(self.send_signed_transaction(&byte_code)?)
+        self.run(AsyncClientExt::send_raw_transaction(
+            &self.inner,
+            transaction_option,
+        ))
     }
 
     fn get_block_by_hash(
@@ -632,19 +509,11 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         hash: &str,
         transaction_info: bool,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_BLOCK_BY_HASH)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(hash)),
-                    ParamsValue::Bool(transaction_info),
-                ]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_block_by_hash(
+            &self.inner,
+            hash,
+            transaction_info,
+        ))
     }
 
     fn get_block_by_number(
@@ -652,32 +521,15 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         height: &str,
         transaction_info: bool,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_BLOCK_BY_NUMBER)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(height)),
-                    ParamsValue::Bool(transaction_info),
-                ]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_block_by_number(
+            &self.inner,
+            height,
+            transaction_info,
+        ))
     }
 
     fn get_transaction_receipt(&self, hash: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_TRANSACTION_RECEIPT)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(hash))]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_transaction_receipt(&self.inner, hash))
     }
 
     fn get_logs(
@@ -687,35 +539,13 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         from: Option<&str>,
         to: Option<&str>,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let mut object = HashMap::new();
-        object.insert(
-            String::from("fromBlock"),
-            ParamsValue::String(String::from(from.unwrap_or("latest"))),
-        );
-        object.insert(
-            String::from("toBlock"),
-            ParamsValue::String(String::from(to.unwrap_or("latest"))),
-        );
-
-        if topic.is_some() {
-            object.insert(
-                String::from("topics"),
-                serde_json::from_str::<ParamsValue>(&serde_json::to_string(&topic).unwrap())
-                    .unwrap(),
-            );
-        } else {
-            object.insert(String::from("topics"), ParamsValue::List(Vec::new()));
-        }
-
-        object.insert(
-            String::from("address"),
-            serde_json::from_str::<ParamsValue>(&serde_json::to_string(&address).unwrap()).unwrap(),
-        );
-
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(GET_LOGS)))
-            .insert("params", ParamsValue::List(vec![ParamsValue::Map(object)]));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_logs(
+            &self.inner,
+            topic,
+            address,
+            from,
+            to,
+        ))
     }
 
     fn call(
@@ -725,42 +555,11 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         data: Option<&str>,
         height: &str,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let mut object = HashMap::new();
-
-        object.insert(String::from("to"), ParamsValue::String(String::from(to)));
-        if let Some(from) = from {
-            object.insert(
-                String::from("from"),
-                ParamsValue::String(String::from(from)),
-            );
-        }
-        if let Some(data) = data {
-            object.insert(
-                String::from("data"),
-                ParamsValue::String(String::from(data)),
-            );
-        }
-
-        let param = ParamsValue::List(vec![
-            ParamsValue::Map(object),
-            ParamsValue::String(String::from(height)),
-        ]);
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(CALL)))
-            .insert("params", param);
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::call(&self.inner, from, to, data, height))
     }
 
     fn get_transaction(&self, hash: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(GET_TRANSACTION)))
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(hash))]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_transaction(&self.inner, hash))
     }
 
     fn get_transaction_count(
@@ -768,62 +567,23 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         address: &str,
         height: &str,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_TRANSACTION_COUNT)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(address)),
-                    ParamsValue::String(String::from(height)),
-                ]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_transaction_count(
+            &self.inner,
+            address,
+            height,
+        ))
     }
 
     fn get_code(&self, address: &str, height: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(GET_CODE)))
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(address)),
-                    ParamsValue::String(String::from(height)),
-                ]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_code(&self.inner, address, height))
     }
 
     fn get_abi(&self, address: &str, height: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(GET_ABI)))
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(address)),
-                    ParamsValue::String(String::from(height)),
-                ]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_abi(&self.inner, address, height))
     }
 
     fn get_balance(&self, address: &str, height: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(GET_BALANCE)))
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(address)),
-                    ParamsValue::String(String::from(height)),
-                ]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_balance(&self.inner, address, height))
     }
 
     fn new_filter(
@@ -833,110 +593,41 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         from: Option<&str>,
         to: Option<&str>,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let mut object = HashMap::new();
-        object.insert(
-            String::from("fromBlock"),
-            ParamsValue::String(String::from(from.unwrap_or("latest"))),
-        );
-        object.insert(
-            String::from("toBlock"),
-            ParamsValue::String(String::from(to.unwrap_or("latest"))),
-        );
-        object.insert(
-            String::from("topics"),
-            serde_json::from_str::<ParamsValue>(&serde_json::to_string(&topic).unwrap()).unwrap(),
-        );
-        object.insert(
-            String::from("address"),
-            serde_json::from_str::<ParamsValue>(&serde_json::to_string(&address).unwrap()).unwrap(),
-        );
-
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(NEW_FILTER)))
-            .insert("params", ParamsValue::List(vec![ParamsValue::Map(object)]));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::new_filter(
+            &self.inner,
+            topic,
+            address,
+            from,
+            to,
+        ))
     }
 
     fn new_block_filter(&self) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new().insert(
-            "method",
-            ParamsValue::String(String::from(NEW_BLOCK_FILTER)),
-        );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::new_block_filter(&self.inner))
     }
 
     fn uninstall_filter(&self, filter_id: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(UNINSTALL_FILTER)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(filter_id))]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::uninstall_filter(&self.inner, filter_id))
     }
 
     fn get_filter_changes(&self, filter_id: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_FILTER_CHANGES)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(filter_id))]),
-            );
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_filter_changes(&self.inner, filter_id))
     }
 
     fn get_filter_logs(&self, filter_id: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(GET_FILTER_LOGS)))
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(filter_id))]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_filter_logs(&self.inner, filter_id))
     }
 
     fn get_transaction_proof(&self, hash: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_TRANSACTION_PROOF)),
-            )
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(hash))]),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_transaction_proof(&self.inner, hash))
     }
 
     fn get_metadata(&self, height: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(height))]),
-            )
-            .insert("method", ParamsValue::String(String::from(GET_META_DATA)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_metadata(&self.inner, height))
     }
 
     fn get_block_header(&self, height: &str) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "params",
-                ParamsValue::List(vec![ParamsValue::String(String::from(height))]),
-            )
-            .insert(
-                "method",
-                ParamsValue::String(String::from(GET_BLOCK_HEADER)),
-            );
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_block_header(&self.inner, height))
     }
 
     fn get_state_proof(
@@ -945,17 +636,12 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         key: &str,
         height: &str,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(address)),
-                    ParamsValue::String(String::from(key)),
-                    ParamsValue::String(String::from(height)),
-                ]),
-            )
-            .insert("method", ParamsValue::String(String::from(GET_STATE_PROOF)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_state_proof(
+            &self.inner,
+            address,
+            key,
+            height,
+        ))
     }
 
     fn get_storage_at(
@@ -964,23 +650,16 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         key: &str,
         height: &str,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let params = JsonRpcParams::new()
-            .insert(
-                "params",
-                ParamsValue::List(vec![
-                    ParamsValue::String(String::from(address)),
-                    ParamsValue::String(String::from(key)),
-                    ParamsValue::String(String::from(height)),
-                ]),
-            )
-            .insert("method", ParamsValue::String(String::from(GET_STORAGE_AT)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_storage_at(
+            &self.inner,
+            address,
+            key,
+            height,
+        ))
     }
 
     fn get_version(&self) -> Result<JsonRpcResponse, ToolError> {
-        let params =
-            JsonRpcParams::new().insert("method", ParamsValue::String(String::from(GET_VERSION)));
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::get_version(&self.inner))
     }
 
     fn estimate_quota(
@@ -990,31 +669,13 @@ impl ClientExt<JsonRpcResponse, ToolError> for Client {
         data: Option<&str>,
         height: &str,
     ) -> Result<JsonRpcResponse, ToolError> {
-        let mut object = HashMap::new();
-
-        object.insert(String::from("to"), ParamsValue::String(String::from(to)));
-        if let Some(from) = from {
-            object.insert(
-                String::from("from"),
-                ParamsValue::String(String::from(from)),
-            );
-        }
-        if let Some(data) = data {
-            object.insert(
-                String::from("data"),
-                ParamsValue::String(String::from(data)),
-            );
-        }
-
-        let param = ParamsValue::List(vec![
-            ParamsValue::Map(object),
-            ParamsValue::String(String::from(height)),
-        ]);
-        let params = JsonRpcParams::new()
-            .insert("method", ParamsValue::String(String::from(ESTIMATE_QUOTA)))
-            .insert("params", param);
-
-        Ok(self.send_request(vec![params].into_iter())?.pop().unwrap())
+        self.run(AsyncClientExt::estimate_quota(
+            &self.inner,
+            from,
+            to,
+            data,
+            height,
+        ))
     }
 }
 
@@ -1124,15 +785,3 @@ where
 }
 
 impl Transfer<JsonRpcResponse, ToolError> for Client {}
-
-#[cfg(feature = "openssl")]
-pub(crate) fn create_client() -> HyperClient<hyper_tls::HttpsConnector<HttpConnector>> {
-    let https = hyper_tls::HttpsConnector::new(4).unwrap();
-    HyperClient::builder().build::<_, Body>(https)
-}
-
-#[cfg(feature = "rustls")]
-pub(crate) fn create_client() -> HyperClient<hyper_rustls::HttpsConnector<HttpConnector>> {
-    let https = hyper_rustls::HttpsConnector::new(4);
-    HyperClient::builder().build::<_, Body>(https)
-}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use types::U256;
+
+use crate::models::MetaData;
+
+/// The chain metadata a transaction is built with
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainMetadata {
+    /// Chain id of version 0 transactions
+    pub chain_id: u32,
+    /// Chain id of version 1 transactions
+    pub chain_id_v1: U256,
+    /// Block interval in milliseconds
+    pub block_interval: u64,
+    /// Economical model, 0 is quota and 1 is charge
+    pub economical_model: u8,
+}
+
+impl From<MetaData> for ChainMetadata {
+    fn from(metadata: MetaData) -> Self {
+        ChainMetadata {
+            chain_id: metadata.chain_id,
+            chain_id_v1: metadata.chain_id_v1,
+            block_interval: metadata.block_interval,
+            economical_model: metadata.economical_model,
+        }
+    }
+}
+
+/// Hits and misses of the cache, since its creation
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CacheStats {
+    /// Lookups answered by the cache
+    pub hits: usize,
+    /// Lookups which had to query the chain
+    pub misses: usize,
+}
+
+#[derive(Default)]
+struct Entry {
+    metadata: Option<(ChainMetadata, Instant)>,
+    version: Option<(u32, Instant)>,
+}
+
+/// Cache of the chain metadata and transaction version of each url
+///
+/// Clones share the entries, an entry older than the ttl is queried again.
+#[derive(Clone)]
+pub struct MetadataCache {
+    ttl: Option<Duration>,
+    entries: Arc<RwLock<HashMap<String, Entry>>>,
+    hits: Arc<AtomicUsize>,
+    misses: Arc<AtomicUsize>,
+}
+
+impl MetadataCache {
+    /// Create an empty cache, the entries live for 5 minutes
+    pub fn new() -> Self {
+        MetadataCache {
+            ttl: Some(Duration::from_secs(300)),
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            hits: Arc::new(AtomicUsize::new(0)),
+            misses: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Set the time to live of the entries, None never expires
+    pub fn set_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Get ttl
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// The unexpired metadata of the url
+    pub fn metadata(&self, url: &str) -> Option<ChainMetadata> {
+        let metadata = self.entries.read().unwrap().get(url).and_then(|entry| {
+            entry
+                .metadata
+                .as_ref()
+                .filter(|(_, time)| self.is_fresh(*time))
+                .map(|(metadata, _)| metadata.clone())
+        });
+        self.count(metadata)
+    }
+
+    /// The unexpired transaction version of the url
+    pub fn version(&self, url: &str) -> Option<u32> {
+        let version = self.entries.read().unwrap().get(url).and_then(|entry| {
+            entry
+                .version
+                .filter(|(_, time)| self.is_fresh(*time))
+                .map(|(version, _)| version)
+        });
+        self.count(version)
+    }
+
+    /// Store the metadata of the url
+    pub fn set_metadata(&self, url: &str, metadata: ChainMetadata) {
+        let mut entries = self.entries.write().unwrap();
+        entries.entry(url.to_string()).or_default().metadata = Some((metadata, Instant::now()));
+    }
+
+    /// Store the transaction version of the url
+    pub fn set_version(&self, url: &str, version: u32) {
+        let mut entries = self.entries.write().unwrap();
+        entries.entry(url.to_string()).or_default().version = Some((version, Instant::now()));
+    }
+
+    /// Drop the entry of the url, the next lookup queries the chain
+    pub fn invalidate(&self, url: &str) {
+        self.entries.write().unwrap().remove(url);
+    }
+
+    /// Drop all the entries
+    pub fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+
+    /// Get the hits and misses
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn is_fresh(&self, time: Instant) -> bool {
+        self.ttl.map(|ttl| time.elapsed() < ttl).unwrap_or(true)
+    }
+
+    fn count<T>(&self, value: Option<T>) -> Option<T> {
+        match value {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        value
+    }
+}
+
+impl Default for MetadataCache {
+    fn default() -> Self {
+        MetadataCache::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::Client;
+    use crate::client::transport::MemoryTransport;
+    use crate::rpctypes::ResponseValue;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_metadata_cache() {
+        let metadata: ResponseValue = serde_json::from_str(
+            r#"{"chainId": 1, "chainIdV1": "0x2", "blockInterval": 3000, "economicalModel": 1}"#,
+        )
+        .unwrap();
+        let transport = Arc::new(
+            MemoryTransport::new()
+                .on_result("getMetaData", metadata)
+                .on_result("call", serde_json::from_str(r#""0x1""#).unwrap()),
+        );
+        let cache = MetadataCache::new();
+        let mut client = Client::new()
+            .set_transport(transport.clone())
+            .set_metadata_cache(cache.clone());
+
+        assert_eq!(client.get_chain_id().unwrap(), 1);
+        assert_eq!(client.get_chain_id_v1().unwrap(), U256::from(2));
+        assert_eq!(client.get_version().unwrap(), 1);
+        let mut shell = client.clone();
+        assert_eq!(shell.chain_metadata().unwrap().block_interval, 3000);
+        assert_eq!(shell.get_version().unwrap(), 1);
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 2 });
+
+        // The override of one client is not shared
+        shell.set_chain_id(U256::from(5));
+        assert_eq!(shell.get_chain_id_v1().unwrap(), U256::from(5));
+        assert_eq!(client.get_chain_id_v1().unwrap(), U256::from(2));
+
+        let metadata = client.refresh_metadata().unwrap();
+        assert_eq!(metadata.economical_model, 1);
+        assert_eq!(transport.requests().len(), 3);
+        cache.invalidate(&client.uri().to_string());
+        assert_eq!(shell.get_chain_id().unwrap(), 1);
+        assert_eq!(transport.requests().len(), 4);
+
+        let client = client.set_metadata_cache(cache.set_ttl(Some(Duration::from_secs(0))));
+        client.get_version().unwrap();
+        client.get_version().unwrap();
+        assert_eq!(transport.requests().len(), 6);
+    }
+}
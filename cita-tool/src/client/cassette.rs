@@ -0,0 +1,268 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use futures::{future, Future};
+use hyper::Uri;
+use serde_json::json;
+
+use crate::client::async_client::ResponseFuture;
+use crate::client::transport::Transport;
+use crate::error::{ErrorContext, ToolError};
+use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue};
+
+/// One recorded exchange, a json line of the cassette file
+#[derive(Clone, Serialize, Deserialize)]
+struct Entry {
+    request: JsonRpcParams,
+    response: JsonRpcResponse,
+}
+
+/// The request of the params, their method and params without the id
+fn request_key(param: &JsonRpcParams) -> String {
+    json!([param.get("method"), param.get("params")]).to_string()
+}
+
+/// Record the exchanges of the inner transport to a cassette file, one json line each
+///
+/// The failed requests are not recorded. The cassette is replayed by a `ReplayTransport`.
+///
+/// ```rust,no_run
+/// extern crate cita_tool;
+///
+/// use cita_tool::client::basic::Client;
+/// use cita_tool::client::cassette::RecordTransport;
+///
+/// let client = Client::new();
+/// let recorder = RecordTransport::new(client.transport(), "session.jsonl").unwrap();
+/// let client = client.set_transport(recorder);
+/// ```
+pub struct RecordTransport {
+    inner: Arc<dyn Transport>,
+    file: Arc<Mutex<File>>,
+}
+
+impl RecordTransport {
+    /// Record the exchanges of the transport, the cassette file is truncated
+    pub fn new<P: AsRef<Path>>(inner: Arc<dyn Transport>, path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("Can't open {}", path.display()))?;
+        Ok(RecordTransport {
+            inner,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+impl Transport for RecordTransport {
+    fn send(
+        &self,
+        url: &Uri,
+        params: Vec<JsonRpcParams>,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        let file = Arc::clone(&self.file);
+        Box::new(
+            self.inner
+                .send(url, params.clone())
+                .and_then(move |responses| {
+                    let mut lines = String::new();
+                    for (param, response) in params.into_iter().zip(responses.iter()) {
+                        let mut request = param;
+                        request.remove("id");
+                        let entry = Entry {
+                            request,
+                            response: response.clone(),
+                        };
+                        lines.push_str(&serde_json::to_string(&entry).unwrap());
+                        lines.push('\n');
+                    }
+                    let mut file = file.lock().unwrap();
+                    file.write_all(lines.as_bytes())
+                        .and_then(|_| file.flush())
+                        .context("Can't record the responses")?;
+                    Ok(responses)
+                }),
+        )
+    }
+}
+
+/// Answer the requests with the responses of a cassette file, without a node
+///
+/// A request matches a recorded one of the same method and params, whatever its id.
+/// The responses of the same request are replayed in the recorded order, the last one
+/// is repeated. A request never recorded is a `ToolError::Transport`.
+pub struct ReplayTransport {
+    responses: Mutex<HashMap<String, VecDeque<JsonRpcResponse>>>,
+}
+
+impl ReplayTransport {
+    /// Load the cassette file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let cassette =
+            fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+        let mut responses: HashMap<String, VecDeque<JsonRpcResponse>> = HashMap::new();
+        for (number, line) in cassette.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str::<Entry>(line)
+                .map_err(ToolError::SerdeJson)
+                .with_context(|| format!("Invalid cassette line {}", number + 1))?;
+            responses
+                .entry(request_key(&entry.request))
+                .or_default()
+                .push_back(entry.response);
+        }
+        Ok(ReplayTransport {
+            responses: Mutex::new(responses),
+        })
+    }
+
+    fn respond(&self, param: &JsonRpcParams) -> Result<JsonRpcResponse, ToolError> {
+        let key = request_key(param);
+        let mut responses = self.responses.lock().unwrap();
+        let recorded = responses
+            .get_mut(&key)
+            .filter(|recorded| !recorded.is_empty())
+            .ok_or_else(|| ToolError::Transport(format!("No recorded response of {}", key)))?;
+        let response = if recorded.len() > 1 {
+            recorded.pop_front().unwrap()
+        } else {
+            recorded[0].clone()
+        };
+        let id = match param.get("id") {
+            Some(ParamsValue::Int(id)) => *id,
+            _ => response.id(),
+        };
+        Ok(response.with_id(id))
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(
+        &self,
+        _url: &Uri,
+        params: Vec<JsonRpcParams>,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        Box::new(future::result(
+            params
+                .iter()
+                .map(|param| self.respond(param))
+                .collect::<Result<Vec<_>, _>>(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::{Client, ClientExt};
+    use crate::client::transport::MemoryTransport;
+    use crate::error::ErrorKind;
+    use crate::rpctypes::{ErrorResponse, ResponseValue};
+    use std::env;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const ADDRESS: &str = "0xffffffffffffffffffffffffffffffffff010000";
+
+    /// A cassette file in the temp dir, removed on drop even when the test fails
+    struct Cassette(PathBuf);
+
+    impl Cassette {
+        fn new(name: &str) -> Self {
+            Cassette(env::temp_dir().join(format!(
+                "cita-cassette-{}-{}.jsonl",
+                name,
+                std::process::id()
+            )))
+        }
+    }
+
+    impl Drop for Cassette {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn node() -> MemoryTransport {
+        let height = AtomicUsize::new(0x10);
+        MemoryTransport::new()
+            .on("blockNumber", move |_| {
+                let height = height.fetch_add(1, Ordering::SeqCst);
+                Ok(ResponseValue::Singe(ParamsValue::String(format!(
+                    "{:#x}",
+                    height
+                ))))
+            })
+            .on("getBalance", |param| match param.get("params") {
+                Some(ParamsValue::List(list)) if list.len() == 2 => Ok(ResponseValue::Singe(
+                    ParamsValue::String("0x64".to_string()),
+                )),
+                _ => Err(ErrorResponse::new(-32602, "Invalid params")),
+            })
+    }
+
+    /// Record two heights and a balance, return the balance response
+    fn record(cassette: &Cassette) -> JsonRpcResponse {
+        let recorder = RecordTransport::new(Arc::new(node()), &cassette.0).unwrap();
+        let client = Client::new().set_transport(recorder);
+        assert_eq!(client.get_current_height().unwrap(), 0x10);
+        assert_eq!(client.get_current_height().unwrap(), 0x11);
+        client.get_balance(ADDRESS, "latest").unwrap()
+    }
+
+    fn replay(cassette: &Cassette) -> Client {
+        Client::new().set_transport(ReplayTransport::load(&cassette.0).unwrap())
+    }
+
+    #[test]
+    fn test_record() {
+        let cassette = Cassette::new("record");
+        record(&cassette);
+        assert_eq!(fs::read_to_string(&cassette.0).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_replay_with_other_ids() {
+        let cassette = Cassette::new("ids");
+        let balance = record(&cassette);
+        let client = replay(&cassette);
+        client.get_peer_count().unwrap_err();
+        let replayed = client.get_balance(ADDRESS, "latest").unwrap();
+        assert_eq!(replayed.id(), 2);
+        assert_eq!(
+            replayed.to_string(),
+            balance.to_string().replace("\"id\":3", "\"id\":2")
+        );
+    }
+
+    #[test]
+    fn test_replay_in_order() {
+        let cassette = Cassette::new("order");
+        record(&cassette);
+        let client = replay(&cassette);
+        assert_eq!(client.get_current_height().unwrap(), 0x10);
+        assert_eq!(client.get_current_height().unwrap(), 0x11);
+        // The last response of the param is repeated
+        assert_eq!(client.get_current_height().unwrap(), 0x11);
+    }
+
+    #[test]
+    fn test_replay_unrecorded() {
+        let cassette = Cassette::new("unrecorded");
+        record(&cassette);
+        match replay(&cassette).get_balance(ADDRESS, "0x1") {
+            Err(err) => assert_eq!(err.kind(), ErrorKind::Transport),
+            Ok(_) => panic!("expect no recorded response"),
+        }
+    }
+}
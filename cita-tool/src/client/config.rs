@@ -0,0 +1,325 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use types::U256;
+
+use crate::client::async_client::AsyncClient;
+use crate::client::basic::Client;
+use crate::client::endpoint::{Endpoints, Strategy};
+use crate::client::parse_url;
+use crate::client::retry::RetryPolicy;
+use crate::client::transport::HttpTransport;
+use crate::error::{ErrorContext, ToolError};
+use crate::signer::{signer_from_spec, Signer};
+
+/// Settings of a client, serializable to load them from json, toml or any serde format
+///
+/// ```rust
+/// extern crate cita_tool;
+///
+/// use cita_tool::client::config::{ClientBuilder, ClientConfig};
+///
+/// let config = ClientConfig::from_json(r#"{"url": "http://127.0.0.1:1337", "timeout_ms": 3000}"#).unwrap();
+/// let client = ClientBuilder::from_config(config).build().unwrap();
+/// assert_eq!(client.uri().to_string(), "http://127.0.0.1:1337/");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    /// Jsonrpc url of the node, default is `http://127.0.0.1:1337`
+    pub url: Option<String>,
+    /// Urls of several nodes of the chain, used instead of the url
+    pub endpoints: Vec<String>,
+    /// How to choose between the endpoints, default is failover
+    pub strategy: Option<Strategy>,
+    /// Timeout of a request in milliseconds, default is no timeout
+    pub timeout_ms: Option<u64>,
+    /// Timeout to establish a connection in milliseconds, default is no timeout
+    pub connect_timeout_ms: Option<u64>,
+    /// Retries of a failed request, default is 0
+    pub max_retries: usize,
+    /// Chain id, default is the one of the chain metadata
+    pub chain_id: Option<U256>,
+    /// Version of the transactions, default is the version of the chain
+    pub tx_version: Option<u32>,
+    /// Quota of the transactions without one, default is 10_000_000
+    pub default_quota: Option<u64>,
+    /// Blocks the transactions stay valid for, default is 88
+    pub valid_blocks: Option<u64>,
+    /// Signer of the transactions: `exec:<command>`, `unix:<path>` or `keystore:<path>`
+    pub signer: Option<String>,
+    /// Log the requests and responses to stderr
+    pub debug: bool,
+}
+
+impl ClientConfig {
+    /// Parse the json config
+    pub fn from_json(json: &str) -> Result<Self, ToolError> {
+        serde_json::from_str(json)
+            .map_err(ToolError::SerdeJson)
+            .context("Invalid client config")
+    }
+
+    /// Load the json config file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let json =
+            fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Serialize the config to json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Build a client from a config, the invalid settings are errors instead of panics
+#[derive(Clone, Default)]
+pub struct ClientBuilder {
+    config: ClientConfig,
+    signer: Option<Arc<dyn Signer>>,
+    keystore_password: Option<String>,
+}
+
+impl ClientBuilder {
+    /// Create a builder of the default config
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a builder of the config
+    pub fn from_config(config: ClientConfig) -> Self {
+        ClientBuilder {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Get config
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Set url
+    pub fn set_url(mut self, url: &str) -> Self {
+        self.config.url = Some(url.to_string());
+        self
+    }
+
+    /// Set the urls of several nodes of the chain, used instead of the url
+    pub fn set_endpoints(mut self, urls: Vec<String>, strategy: Option<Strategy>) -> Self {
+        self.config.endpoints = urls;
+        self.config.strategy = strategy;
+        self
+    }
+
+    /// Set the timeout of a request
+    pub fn set_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.timeout_ms = timeout.map(|timeout| timeout.as_millis() as u64);
+        self
+    }
+
+    /// Set the timeout to establish a connection
+    pub fn set_connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.connect_timeout_ms = timeout.map(|timeout| timeout.as_millis() as u64);
+        self
+    }
+
+    /// Set the retries of a failed request
+    pub fn set_max_retries(mut self, retries: usize) -> Self {
+        self.config.max_retries = retries;
+        self
+    }
+
+    /// Set chain id
+    pub fn set_chain_id(mut self, chain_id: Option<U256>) -> Self {
+        self.config.chain_id = chain_id;
+        self
+    }
+
+    /// Set the version of the transactions
+    pub fn set_tx_version(mut self, version: Option<u32>) -> Self {
+        self.config.tx_version = version;
+        self
+    }
+
+    /// Set the quota of the transactions without one
+    pub fn set_default_quota(mut self, quota: Option<u64>) -> Self {
+        self.config.default_quota = quota;
+        self
+    }
+
+    /// Set the blocks the transactions stay valid for
+    pub fn set_valid_blocks(mut self, blocks: Option<u64>) -> Self {
+        self.config.valid_blocks = blocks;
+        self
+    }
+
+    /// Set the signer reference: `exec:<command>`, `unix:<path>` or `keystore:<path>`
+    pub fn set_signer_spec(mut self, spec: Option<&str>) -> Self {
+        self.config.signer = spec.map(ToString::to_string);
+        self
+    }
+
+    /// Set the signer, used instead of the signer reference
+    pub fn set_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Set the password of a keystore signer, it is not part of the config
+    pub fn set_keystore_password(mut self, password: &str) -> Self {
+        self.keystore_password = Some(password.to_string());
+        self
+    }
+
+    /// Set debug
+    pub fn set_debug(mut self, debug: bool) -> Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Build the asynchronous client
+    pub fn build_async(&self) -> Result<AsyncClient, ToolError> {
+        let config = &self.config;
+        let millis = |millis: Option<u64>| millis.map(Duration::from_millis);
+        let mut client = AsyncClient::new()
+            .set_retry_policy(
+                RetryPolicy::new()
+                    .set_timeout(millis(config.timeout_ms))
+                    .set_connect_timeout(millis(config.connect_timeout_ms))
+                    .set_max_retries(config.max_retries),
+            )
+            .set_tx_version(config.tx_version)
+            .set_default_quota(config.default_quota)
+            .set_valid_blocks(config.valid_blocks)
+            .set_debug(config.debug);
+        if let Some(ref url) = config.url {
+            parse_url(url).map_err(|err| ToolError::InvalidInput(format!("{}: {}", err, url)))?;
+            client = client.set_uri(url);
+        }
+        if !config.endpoints.is_empty() {
+            let urls = config
+                .endpoints
+                .iter()
+                .map(|url| {
+                    parse_url(url)
+                        .map_err(|err| ToolError::InvalidInput(format!("{}: {}", err, url)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let http = HttpTransport::new().set_connect_timeout(millis(config.connect_timeout_ms));
+            client = client.set_transport(
+                Endpoints::new(urls)?
+                    .set_strategy(config.strategy.unwrap_or(Strategy::Failover))
+                    .set_transport(http),
+            );
+        }
+        if let Some(chain_id) = config.chain_id {
+            client.set_chain_id(chain_id);
+        }
+        match (&self.signer, &config.signer) {
+            (Some(signer), _) => {
+                client.set_signer(Arc::clone(signer));
+            }
+            (None, Some(spec)) => {
+                client.set_signer(signer_from_spec(spec, self.keystore_password.as_deref())?);
+            }
+            (None, None) => {}
+        }
+        Ok(client)
+    }
+
+    /// Build the blocking client
+    pub fn build(&self) -> Result<Client, ToolError> {
+        self.build_async().map(Client::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::{Encryption, PrivateKey};
+    use crate::error::ErrorKind;
+
+    fn config() -> ClientConfig {
+        ClientConfig::from_json(
+            r#"{
+                "endpoints": ["http://127.0.0.1:1337", "http://127.0.0.1:1338"],
+                "strategy": "round-robin",
+                "timeout_ms": 3000,
+                "chain_id": "0x1",
+                "tx_version": 2,
+                "default_quota": 1000000,
+                "debug": true
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn invalid(builder: ClientBuilder) -> ErrorKind {
+        builder.build().err().unwrap().kind()
+    }
+
+    #[test]
+    fn test_config_json() {
+        let config = config();
+        assert_eq!(config.strategy, Some(Strategy::RoundRobin));
+        assert_eq!(config.chain_id, Some(U256::from(1)));
+        assert_eq!(ClientConfig::from_json(&config.to_json()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_config_unknown_field() {
+        assert!(ClientConfig::from_json(r#"{"uri": "http://127.0.0.1:1337"}"#).is_err());
+    }
+
+    #[test]
+    fn test_builder_from_config() {
+        let client = ClientBuilder::from_config(config()).build_async().unwrap();
+        assert_eq!(
+            client.retry_policy().timeout(),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(client.tx_version(), Some(2));
+        assert_eq!(client.default_quota(), Some(1_000_000));
+        assert!(client.debug());
+    }
+
+    #[test]
+    fn test_builder_signer() {
+        let key = PrivateKey::from_str(
+            "993ef0853d7bf1f4c2977457b50ea6b5f8bc2fd829e3ca3e19f6081ddabb07e9",
+            Encryption::Secp256k1,
+        )
+        .unwrap();
+        let client = ClientBuilder::new()
+            .set_url("http://127.0.0.1:1337")
+            .set_signer(Arc::new(key))
+            .build()
+            .unwrap();
+        assert!(client.signer().is_some());
+    }
+
+    #[test]
+    fn test_builder_invalid_url() {
+        assert_eq!(
+            invalid(ClientBuilder::new().set_url("http://[::1")),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_builder_invalid_signer_spec() {
+        assert_eq!(
+            invalid(ClientBuilder::new().set_signer_spec(Some("ledger:0"))),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            invalid(ClientBuilder::new().set_signer_spec(Some("keystore:/nonexistent"))),
+            ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future::{self, join_all, Loop};
+use futures::Future;
+use hyper::Uri;
+use tokio;
+
+use crate::client::async_client::ResponseFuture;
+use crate::client::transport::{HttpTransport, Transport};
+use crate::error::ToolError;
+use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue};
+
+/// How to choose the node of a request
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Take turns between the nodes
+    RoundRobin,
+    /// Always the first healthy node in the list
+    Failover,
+    /// The healthy node with the lowest average latency
+    LeastLatency,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(Strategy::RoundRobin),
+            "failover" => Ok(Strategy::Failover),
+            "least-latency" => Ok(Strategy::LeastLatency),
+            _ => Err(format!("Unknown strategy: {}", s)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Health {
+    failures: usize,
+    unhealthy: bool,
+    probed_at: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+struct Node {
+    url: Uri,
+    health: Mutex<Health>,
+}
+
+impl Node {
+    fn succeed(&self, latency: Duration) {
+        let mut health = self.health.lock().unwrap();
+        health.failures = 0;
+        health.unhealthy = false;
+        health.latency = Some(match health.latency {
+            Some(average) => average * 7 / 8 + latency / 8,
+            None => latency,
+        });
+    }
+
+    fn fail(&self, max_failures: usize) {
+        let mut health = self.health.lock().unwrap();
+        health.failures += 1;
+        if health.failures >= max_failures && !health.unhealthy {
+            health.unhealthy = true;
+            health.probed_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Node indexes in the order to try, healthy nodes first
+fn order(nodes: &[Node], strategy: Strategy, start: usize) -> Vec<usize> {
+    let mut order = (0..nodes.len()).collect::<Vec<_>>();
+    match strategy {
+        Strategy::RoundRobin => order.rotate_left(start % nodes.len()),
+        Strategy::Failover => {}
+        Strategy::LeastLatency => order.sort_by_key(|&index| {
+            nodes[index]
+                .health
+                .lock()
+                .unwrap()
+                .latency
+                .unwrap_or_default()
+        }),
+    }
+    order.sort_by_key(|&index| nodes[index].health.lock().unwrap().unhealthy);
+    order
+}
+
+/// Spread the requests over several nodes of one chain
+///
+/// A node is marked unhealthy after `max_failures` consecutive failures, and is skipped
+/// until a `blockNumber` probe succeeds. The probe is sent at most once per `probe_interval`,
+/// in the background: a request never waits for it.
+/// Jsonrpc errors are answers, they don't count as failures.
+/// The url of the client is ignored, the requests go to the endpoints.
+///
+/// ```rust
+/// extern crate cita_tool;
+///
+/// use cita_tool::client::basic::Client;
+/// use cita_tool::client::endpoint::{Endpoints, Strategy};
+///
+/// let endpoints = Endpoints::new(vec![
+///     "http://127.0.0.1:1337".parse().unwrap(),
+///     "http://127.0.0.1:1338".parse().unwrap(),
+/// ])
+/// .unwrap()
+/// .set_strategy(Strategy::Failover);
+/// let client = Client::new().set_transport(endpoints);
+/// ```
+pub struct Endpoints {
+    nodes: Arc<Vec<Node>>,
+    transport: Arc<dyn Transport>,
+    strategy: Strategy,
+    max_failures: usize,
+    probe_interval: Duration,
+    next: AtomicUsize,
+}
+
+impl Endpoints {
+    /// Create endpoints with round robin, over http
+    ///
+    /// Fail with `ToolError::InvalidInput` when the urls are empty
+    pub fn new(urls: Vec<Uri>) -> Result<Self, ToolError> {
+        if urls.is_empty() {
+            return Err(ToolError::InvalidInput(
+                "Endpoints need at least one url".to_string(),
+            ));
+        }
+        let nodes = urls
+            .into_iter()
+            .map(|url| Node {
+                url,
+                health: Mutex::new(Health::default()),
+            })
+            .collect();
+        Ok(Endpoints {
+            nodes: Arc::new(nodes),
+            transport: Arc::new(HttpTransport::new()),
+            strategy: Strategy::RoundRobin,
+            max_failures: 3,
+            probe_interval: Duration::from_secs(10),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Set strategy
+    pub fn set_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set the transport to each node, default is http
+    pub fn set_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Set the number of consecutive failures to mark a node unhealthy, default is 3
+    pub fn set_max_failures(mut self, max_failures: usize) -> Self {
+        self.max_failures = max_failures.max(1);
+        self
+    }
+
+    /// Set the interval between probes of an unhealthy node, default is 10 seconds
+    pub fn set_probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    /// Urls of the nodes currently marked unhealthy
+    pub fn unhealthy(&self) -> Vec<Uri> {
+        self.nodes
+            .iter()
+            .filter(|node| node.health.lock().unwrap().unhealthy)
+            .map(|node| node.url.clone())
+            .collect()
+    }
+
+    /// Probe the unhealthy nodes whose interval is up, None when no probe is due
+    fn probe(&self) -> Option<ResponseFuture<(), ()>> {
+        let probes = (0..self.nodes.len())
+            .filter(|&index| {
+                let mut health = self.nodes[index].health.lock().unwrap();
+                let due = health.unhealthy
+                    && health
+                        .probed_at
+                        .map(|at| at.elapsed() >= self.probe_interval)
+                        .unwrap_or(true);
+                if due {
+                    health.probed_at = Some(Instant::now());
+                }
+                due
+            })
+            .map(|index| {
+                let nodes = Arc::clone(&self.nodes);
+                let param = JsonRpcParams::new()
+                    .insert("jsonrpc", ParamsValue::String("2.0".to_string()))
+                    .insert("method", ParamsValue::String("blockNumber".to_string()))
+                    .insert("params", ParamsValue::List(Vec::new()))
+                    .insert("id", ParamsValue::Int(0));
+                let start = Instant::now();
+                self.transport
+                    .send(&nodes[index].url, vec![param])
+                    .then(move |result| {
+                        match result {
+                            Ok(ref responses) if responses.iter().all(JsonRpcResponse::is_ok) => {
+                                nodes[index].succeed(start.elapsed())
+                            }
+                            _ => {}
+                        }
+                        Ok(())
+                    })
+            })
+            .collect::<Vec<_>>();
+        if probes.is_empty() {
+            None
+        } else {
+            Some(Box::new(join_all(probes).map(|_| ())))
+        }
+    }
+}
+
+impl Transport for Endpoints {
+    fn send(
+        &self,
+        _url: &Uri,
+        params: Vec<JsonRpcParams>,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        let nodes = Arc::clone(&self.nodes);
+        let transport = Arc::clone(&self.transport);
+        let max_failures = self.max_failures;
+        let strategy = self.strategy;
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let probe = self.probe();
+
+        Box::new(future::lazy(move || {
+            if let Some(probe) = probe {
+                tokio::spawn(probe);
+            }
+            let order = order(&nodes, strategy, start);
+            future::loop_fn((0, None), move |(step, last_err)| {
+                let index = match order.get(step) {
+                    Some(&index) => index,
+                    None => {
+                        return Box::new(future::err(last_err.unwrap()))
+                            as ResponseFuture<Loop<_, _>, ToolError>;
+                    }
+                };
+                let nodes = Arc::clone(&nodes);
+                let start = Instant::now();
+                Box::new(
+                    transport
+                        .send(&nodes[index].url, params.clone())
+                        .then(move |result| match result {
+                            Ok(responses) => {
+                                nodes[index].succeed(start.elapsed());
+                                Ok(Loop::Break(responses))
+                            }
+                            Err(err) => {
+                                nodes[index].fail(max_failures);
+                                Ok(Loop::Continue((step + 1, Some(err))))
+                            }
+                        }),
+                )
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::async_client::AsyncClient;
+    use crate::rpctypes::ResponseValue;
+    use tokio::runtime::Runtime;
+
+    /// Node 0 is down or never answers, the others answer with their index
+    #[derive(Default)]
+    struct Cluster {
+        down: Mutex<bool>,
+        blackholed: Mutex<bool>,
+        hits: Mutex<Vec<String>>,
+    }
+
+    impl Transport for Cluster {
+        fn send(
+            &self,
+            url: &Uri,
+            params: Vec<JsonRpcParams>,
+        ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+            let host = url.port_part().unwrap().to_string();
+            self.hits.lock().unwrap().push(host.clone());
+            if host == "1000" && *self.down.lock().unwrap() {
+                return Box::new(future::err(ToolError::HttpStatus(502)));
+            }
+            if host == "1000" && *self.blackholed.lock().unwrap() {
+                return Box::new(future::empty());
+            }
+            let height = format!("{:#x}", host.parse::<u64>().unwrap());
+            Box::new(future::ok(
+                params
+                    .iter()
+                    .map(|_| {
+                        JsonRpcResponse::from_result(
+                            0,
+                            ResponseValue::Singe(ParamsValue::String(height.clone())),
+                        )
+                    })
+                    .collect(),
+            ))
+        }
+    }
+
+    fn endpoints(cluster: &Arc<Cluster>, strategy: Strategy) -> Endpoints {
+        Endpoints::new(vec![
+            "http://127.0.0.1:1000".parse().unwrap(),
+            "http://127.0.0.1:1001".parse().unwrap(),
+            "http://127.0.0.1:1002".parse().unwrap(),
+        ])
+        .unwrap()
+        .set_transport(Arc::clone(cluster))
+        .set_strategy(strategy)
+        .set_max_failures(1)
+    }
+
+    fn hits(cluster: &Arc<Cluster>) -> Vec<String> {
+        cluster.hits.lock().unwrap().drain(..).collect()
+    }
+
+    #[test]
+    fn test_endpoints() {
+        match Endpoints::new(Vec::new()) {
+            Err(ToolError::InvalidInput(_)) => {}
+            _ => panic!("expect the empty urls to be invalid"),
+        }
+        let mut runtime = Runtime::new().unwrap();
+        let cluster = Arc::new(Cluster::default());
+        *cluster.down.lock().unwrap() = true;
+
+        let client = AsyncClient::new().set_transport(endpoints(&cluster, Strategy::Failover));
+        assert_eq!(runtime.block_on(client.get_current_height()).unwrap(), 1001);
+        assert_eq!(hits(&cluster), vec!["1000", "1001"]);
+        assert_eq!(runtime.block_on(client.get_current_height()).unwrap(), 1001);
+        assert_eq!(hits(&cluster), vec!["1001"]);
+
+        let client = AsyncClient::new().set_transport(endpoints(&cluster, Strategy::RoundRobin));
+        for _ in 0..4 {
+            runtime.block_on(client.get_current_height()).unwrap();
+        }
+        assert_eq!(hits(&cluster), vec!["1000", "1001", "1001", "1002", "1001"]);
+
+        let endpoints = Arc::new(
+            endpoints(&cluster, Strategy::Failover).set_probe_interval(Duration::from_millis(0)),
+        );
+        let client = AsyncClient::new().set_transport(Arc::clone(&endpoints));
+        runtime.block_on(client.get_current_height()).unwrap();
+        assert_eq!(endpoints.unhealthy().len(), 1);
+        *cluster.down.lock().unwrap() = false;
+        hits(&cluster);
+        // The probe runs in the background, the request doesn't wait for it
+        assert_eq!(runtime.block_on(client.get_current_height()).unwrap(), 1001);
+        for _ in 0..100 {
+            if endpoints.unhealthy().is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(endpoints.unhealthy().is_empty());
+        assert_eq!(runtime.block_on(client.get_current_height()).unwrap(), 1000);
+    }
+
+    #[test]
+    fn test_blackholed_probe() {
+        let mut runtime = Runtime::new().unwrap();
+        let cluster = Arc::new(Cluster::default());
+        *cluster.down.lock().unwrap() = true;
+        let endpoints = Arc::new(
+            endpoints(&cluster, Strategy::Failover).set_probe_interval(Duration::from_millis(0)),
+        );
+        let client = AsyncClient::new().set_transport(Arc::clone(&endpoints));
+        runtime.block_on(client.get_current_height()).unwrap();
+        assert_eq!(endpoints.unhealthy().len(), 1);
+
+        *cluster.down.lock().unwrap() = false;
+        *cluster.blackholed.lock().unwrap() = true;
+        for _ in 0..3 {
+            assert_eq!(runtime.block_on(client.get_current_height()).unwrap(), 1001);
+        }
+        assert_eq!(endpoints.unhealthy().len(), 1);
+    }
+}
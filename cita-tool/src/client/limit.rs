@@ -0,0 +1,261 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::task::{self, Task};
+use futures::{future, Async, Future, Poll};
+use tokio::timer::Delay;
+
+use crate::client::async_client::ResponseFuture;
+use crate::error::ToolError;
+
+/// Limits of the requests a client sends: how many are in flight and how many start per second
+///
+/// Clones share the limits, so a client and its clones stay under them together.
+/// Default is no limit.
+#[derive(Clone, Default)]
+pub struct RequestLimits {
+    max_in_flight: Option<usize>,
+    rate: Option<u32>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    in_flight: usize,
+    waiters: Vec<Task>,
+    next_start: Option<Instant>,
+}
+
+impl RequestLimits {
+    /// Create the limits, no limit at all
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the maximum number of requests in flight, a batch is one request
+    pub fn set_max_in_flight(mut self, max: Option<usize>) -> Self {
+        self.max_in_flight = max.map(|max| max.max(1));
+        self
+    }
+
+    /// Get max in flight
+    pub fn max_in_flight(&self) -> Option<usize> {
+        self.max_in_flight
+    }
+
+    /// Set the maximum number of requests started per second
+    pub fn set_rate_limit(mut self, rate: Option<u32>) -> Self {
+        self.rate = rate.map(|rate| rate.max(1));
+        self
+    }
+
+    /// Get rate limit
+    pub fn rate_limit(&self) -> Option<u32> {
+        self.rate
+    }
+
+    /// The number of requests in flight now
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().in_flight
+    }
+
+    /// Resolve to a permit once a request may start, the request ends with the permit
+    pub(crate) fn acquire(&self) -> ResponseFuture<Permit, ToolError> {
+        let rate = self.rate;
+        let state = Arc::clone(&self.state);
+        Box::new(
+            Acquire {
+                max_in_flight: self.max_in_flight,
+                state: Arc::clone(&self.state),
+            }
+            .and_then(move |permit| -> ResponseFuture<Permit, ToolError> {
+                let rate = match rate {
+                    Some(rate) => rate,
+                    None => return Box::new(future::ok(permit)),
+                };
+                // Reserve the next start, one interval after the previous one
+                let now = Instant::now();
+                let start = {
+                    let mut state = state.lock().unwrap();
+                    let start = state.next_start.map_or(now, |next| next.max(now));
+                    state.next_start = Some(start + Duration::from_secs(1) / rate);
+                    start
+                };
+                if start <= now {
+                    Box::new(future::ok(permit))
+                } else {
+                    Box::new(
+                        Delay::new(start)
+                            .map_err(|err| {
+                                ToolError::Transport(format!("Rate limit timer error: {}", err))
+                            })
+                            .map(move |_| permit),
+                    )
+                }
+            }),
+        )
+    }
+}
+
+/// A request in flight, it is released when dropped
+pub(crate) struct Permit {
+    state: Option<Arc<Mutex<State>>>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(ref state) = self.state {
+            let mut state = state.lock().unwrap();
+            state.in_flight -= 1;
+            state.waiters.drain(..).for_each(|task| task.notify());
+        }
+    }
+}
+
+struct Acquire {
+    max_in_flight: Option<usize>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Future for Acquire {
+    type Item = Permit;
+    type Error = ToolError;
+
+    fn poll(&mut self) -> Poll<Permit, ToolError> {
+        let max = match self.max_in_flight {
+            Some(max) => max,
+            None => return Ok(Async::Ready(Permit { state: None })),
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.in_flight < max {
+            state.in_flight += 1;
+            Ok(Async::Ready(Permit {
+                state: Some(Arc::clone(&self.state)),
+            }))
+        } else {
+            state.waiters.push(task::current());
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::Client;
+    use crate::client::transport::Transport;
+    use crate::rpctypes::{
+        ErrorResponse, JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue,
+    };
+    use hyper::Uri;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answer after 20ms, record the most requests in flight at once
+    #[derive(Default)]
+    struct SlowTransport {
+        in_flight: Arc<AtomicUsize>,
+        max_seen: Arc<AtomicUsize>,
+    }
+
+    impl Transport for SlowTransport {
+        fn send(
+            &self,
+            _url: &Uri,
+            params: Vec<JsonRpcParams>,
+        ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+            let in_flight = Arc::clone(&self.in_flight);
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_seen.fetch_max(now, Ordering::SeqCst);
+            let responses = params
+                .iter()
+                .map(|param| match param.get("params") {
+                    Some(ParamsValue::List(list)) if !list.is_empty() => {
+                        JsonRpcResponse::from_result(1, ResponseValue::Singe(list[0].clone()))
+                    }
+                    _ => {
+                        JsonRpcResponse::from_error(1, ErrorResponse::new(-32602, "Invalid params"))
+                    }
+                })
+                .collect::<Vec<_>>();
+            Box::new(
+                Delay::new(Instant::now() + Duration::from_millis(20))
+                    .map_err(|err| ToolError::Transport(format!("Timer error: {}", err)))
+                    .map(move |_| {
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        responses
+                    }),
+            )
+        }
+    }
+
+    fn params(count: usize) -> Vec<JsonRpcParams> {
+        (0..count)
+            .map(|index| {
+                let param =
+                    JsonRpcParams::new().insert("method", ParamsValue::String("call".to_string()));
+                if index % 4 == 3 {
+                    param
+                } else {
+                    param.insert(
+                        "params",
+                        ParamsValue::List(vec![ParamsValue::Int(index as u64)]),
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn client(transport: &Arc<SlowTransport>, limits: RequestLimits) -> Client {
+        Client::new()
+            .set_transport(Arc::clone(transport))
+            .set_strict(true)
+            .set_request_limits(limits)
+    }
+
+    #[test]
+    fn test_max_in_flight() {
+        let transport = Arc::new(SlowTransport::default());
+        let limits = RequestLimits::new().set_max_in_flight(Some(3));
+        let client = client(&transport, limits.clone());
+        client.send_request_each(params(12).into_iter()).unwrap();
+        assert_eq!(transport.max_seen.load(Ordering::SeqCst), 3);
+        assert_eq!(limits.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_send_request_each() {
+        let transport = Arc::new(SlowTransport::default());
+        let client = client(&transport, RequestLimits::new().set_max_in_flight(Some(3)));
+        let results = client.send_request_each(params(12).into_iter()).unwrap();
+        // In order, the failed items do not fail the others
+        for (index, result) in results.iter().enumerate() {
+            match result {
+                Ok(response) => {
+                    assert_eq!(response.result().unwrap().to_string(), index.to_string())
+                }
+                Err(ToolError::JsonRpc(error)) => {
+                    assert_eq!(index % 4, 3);
+                    assert_eq!(error.code(), -32602);
+                }
+                Err(err) => panic!("unexpected error {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_send_request_fails_with_an_item() {
+        let transport = Arc::new(SlowTransport::default());
+        let client = client(&transport, RequestLimits::new().set_max_in_flight(Some(3)));
+        assert!(client.send_request(params(4).into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let transport = Arc::new(SlowTransport::default());
+        let client = client(&transport, RequestLimits::new().set_rate_limit(Some(100)));
+        let start = Instant::now();
+        client.send_request(params(3).into_iter()).unwrap();
+        // The third request starts 20ms after the first one
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,527 @@
+use std::env;
+use std::io;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use futures::future::{self, Loop};
+use futures::Future;
+use hyper::client::connect::{Connect, Connected, Destination, HttpConnector};
+use hyper::Uri;
+use tokio::io::{read_exact, write_all};
+use tokio::net::TcpStream;
+
+use crate::client::percent_decode;
+
+type Connecting<T> = Box<dyn Future<Item = T, Error = io::Error> + Send>;
+
+/// Protocol spoken to the proxy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProxyKind {
+    /// Absolute-form requests for http, `CONNECT` tunnel for https
+    Http,
+    /// SOCKS5 tunnel, the host name is resolved by the proxy
+    Socks5,
+}
+
+/// A proxy server, `http://[user:password@]host[:port]` or `socks5://[user:password@]host[:port]`
+///
+/// The port defaults to 80 for http and 1080 for socks5.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proxy {
+    kind: ProxyKind,
+    addr: String,
+    auth: Option<(String, String)>,
+}
+
+impl Proxy {
+    /// Protocol of the proxy
+    pub fn kind(&self) -> ProxyKind {
+        self.kind
+    }
+
+    /// `host:port` of the proxy
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// `Proxy-Authorization` of a http proxy with credentials
+    pub(crate) fn basic_auth(&self) -> Option<String> {
+        self.auth.as_ref().map(|(user, password)| {
+            format!(
+                "Basic {}",
+                base64::encode(&format!("{}:{}", user, password))
+            )
+        })
+    }
+}
+
+impl FromStr for Proxy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scheme, rest) = s.split_once("://").unwrap_or(("http", s));
+        let kind = match scheme.to_ascii_lowercase().as_str() {
+            "http" => ProxyKind::Http,
+            "socks5" | "socks5h" => ProxyKind::Socks5,
+            _ => return Err(format!("Unsupported proxy scheme: {}", scheme)),
+        };
+        let authority = rest.split('/').next().unwrap_or_default();
+        let (auth, host) = match authority.rsplit_once('@') {
+            Some((userinfo, host)) => {
+                let (user, password) = userinfo.split_once(':').unwrap_or((userinfo, ""));
+                (Some((percent_decode(user), percent_decode(password))), host)
+            }
+            None => (None, authority),
+        };
+        let uri = format!("http://{}", host)
+            .parse::<Uri>()
+            .map_err(|err| format!("Invalid proxy {}: {}", s, err))?;
+        let port = uri.port_u16().unwrap_or(match kind {
+            ProxyKind::Http => 80,
+            ProxyKind::Socks5 => 1080,
+        });
+        match uri.host() {
+            Some(host) if !host.is_empty() => Ok(Proxy {
+                kind,
+                addr: format!("{}:{}", host, port),
+                auth,
+            }),
+            _ => Err(format!("Invalid proxy {}: missing host", s)),
+        }
+    }
+}
+
+/// The proxy of each scheme and the hosts going around them
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Proxies {
+    http: Option<Proxy>,
+    https: Option<Proxy>,
+    no_proxy: Vec<String>,
+}
+
+impl Proxies {
+    /// Read `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY`, the lowercase names as well
+    ///
+    /// An invalid proxy in the env is ignored.
+    pub(crate) fn from_env() -> Self {
+        let var = |name: &str| {
+            env::var(name)
+                .or_else(|_| env::var(name.to_ascii_lowercase()))
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        };
+        Proxies {
+            http: var("HTTP_PROXY").and_then(|proxy| proxy.trim().parse().ok()),
+            https: var("HTTPS_PROXY").and_then(|proxy| proxy.trim().parse().ok()),
+            no_proxy: var("NO_PROXY")
+                .map(|hosts| {
+                    hosts
+                        .split(',')
+                        .map(|host| host.trim().trim_start_matches('.').to_ascii_lowercase())
+                        .filter(|host| !host.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Use the proxy for both schemes, `None` to connect directly
+    pub(crate) fn set(&mut self, proxy: Option<Proxy>) {
+        self.http = proxy.clone();
+        self.https = proxy;
+    }
+
+    /// The proxy to reach the host with the scheme, if any
+    pub(crate) fn find(&self, scheme: &str, host: &str) -> Option<&Proxy> {
+        let proxy = match scheme {
+            "https" => self.https.as_ref(),
+            _ => self.http.as_ref(),
+        }?;
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        if self.no_proxy.iter().any(|pattern| {
+            pattern == "*" || host == *pattern || host.ends_with(&format!(".{}", pattern))
+        }) {
+            None
+        } else {
+            Some(proxy)
+        }
+    }
+}
+
+/// Connect to the target through the proxy, if any
+///
+/// A http target behind a http proxy gets a plain connection to the proxy, then hyper sends
+/// the requests in absolute-form. The other targets get a tunnel.
+#[derive(Clone)]
+pub(crate) struct ProxyConnector {
+    http: HttpConnector,
+    proxies: Proxies,
+}
+
+impl ProxyConnector {
+    pub(crate) fn new(proxies: Proxies) -> Self {
+        let mut http = HttpConnector::new(4);
+        http.enforce_http(false);
+        ProxyConnector { http, proxies }
+    }
+}
+
+impl Connect for ProxyConnector {
+    type Transport = TcpStream;
+    type Error = io::Error;
+    type Future = Connecting<(TcpStream, Connected)>;
+
+    fn connect(&self, dst: Destination) -> Self::Future {
+        let proxy = match self.proxies.find(dst.scheme(), dst.host()) {
+            Some(proxy) => proxy.clone(),
+            None => return Box::new(self.http.connect(dst)),
+        };
+        let proxy_dst = match format!("http://{}", proxy.addr)
+            .parse::<Uri>()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+            .and_then(|uri| {
+                Destination::try_from_uri(uri)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+            }) {
+            Ok(proxy_dst) => proxy_dst,
+            Err(err) => return Box::new(future::err(err)),
+        };
+        let host = dst.host().to_string();
+        let port = dst
+            .port()
+            .unwrap_or(if dst.scheme() == "https" { 443 } else { 80 });
+        let connecting = self.http.connect(proxy_dst);
+
+        match proxy.kind {
+            ProxyKind::Http if dst.scheme() == "http" => {
+                Box::new(connecting.map(|(stream, connected)| (stream, connected.proxy(true))))
+            }
+            ProxyKind::Http => Box::new(connecting.and_then(move |(stream, connected)| {
+                http_tunnel(stream, format!("{}:{}", host, port), proxy.basic_auth())
+                    .map(|stream| (stream, connected))
+            })),
+            ProxyKind::Socks5 => Box::new(connecting.and_then(move |(stream, connected)| {
+                socks5_tunnel(stream, host, port, proxy.auth).map(|stream| (stream, connected))
+            })),
+        }
+    }
+}
+
+fn refused(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionRefused, message)
+}
+
+/// Open a tunnel with `CONNECT host:port`
+fn http_tunnel(stream: TcpStream, target: String, auth: Option<String>) -> Connecting<TcpStream> {
+    let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+    if let Some(auth) = auth {
+        request.push_str(&format!("Proxy-Authorization: {}\r\n", auth));
+    }
+    request.push_str("\r\n");
+    Box::new(
+        write_all(stream, request.into_bytes())
+            .and_then(|(stream, _)| read_head(stream))
+            .and_then(|(stream, head)| {
+                let status_line = head.lines().next().unwrap_or_default();
+                if status_line.split_whitespace().nth(1) == Some("200") {
+                    Ok(stream)
+                } else {
+                    Err(refused(format!(
+                        "Proxy refused the tunnel: {}",
+                        status_line
+                    )))
+                }
+            }),
+    )
+}
+
+/// Read the response head of the proxy byte by byte, so that nothing of the tunnel is lost
+fn read_head(stream: TcpStream) -> Connecting<(TcpStream, String)> {
+    Box::new(future::loop_fn(
+        (stream, Vec::new()),
+        |(stream, mut head)| {
+            read_exact(stream, [0u8; 1]).and_then(move |(stream, byte)| {
+                head.push(byte[0]);
+                if head.ends_with(b"\r\n\r\n") {
+                    Ok(Loop::Break((
+                        stream,
+                        String::from_utf8_lossy(&head).into_owned(),
+                    )))
+                } else if head.len() > 8192 {
+                    Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Proxy response head is too large",
+                    ))
+                } else {
+                    Ok(Loop::Continue((stream, head)))
+                }
+            })
+        },
+    ))
+}
+
+/// Open a tunnel with the SOCKS5 `CONNECT` command, the name is resolved by the proxy
+fn socks5_tunnel(
+    stream: TcpStream,
+    host: String,
+    port: u16,
+    auth: Option<(String, String)>,
+) -> Connecting<TcpStream> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let mut request = vec![5, 1, 0];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(1);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) if host.len() > 255 => {
+            return Box::new(future::err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "SOCKS5 host names are at most 255 bytes",
+            )));
+        }
+        Err(_) => {
+            request.push(3);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+
+    let methods = if auth.is_some() {
+        vec![5, 2, 0, 2]
+    } else {
+        vec![5, 1, 0]
+    };
+    Box::new(
+        write_all(stream, methods)
+            .and_then(|(stream, _)| read_exact(stream, [0u8; 2]))
+            .and_then(move |(stream, reply)| match (reply, auth) {
+                ([5, 0], _) => Box::new(future::ok(stream)) as Connecting<TcpStream>,
+                ([5, 2], Some((user, password))) => socks5_login(stream, &user, &password),
+                _ => Box::new(future::err(refused(
+                    "SOCKS5 proxy accepts none of the offered authentications".to_string(),
+                ))),
+            })
+            .and_then(move |stream| write_all(stream, request))
+            .and_then(|(stream, _)| read_exact(stream, [0u8; 5]))
+            .and_then(|(stream, reply)| {
+                if reply[1] != 0 {
+                    return Err(refused(format!(
+                        "SOCKS5 proxy refused the connection, reply {}",
+                        reply[1]
+                    )));
+                }
+                // The bound address, the first byte of which is already read
+                let rest = match reply[3] {
+                    1 => 4 - 1 + 2,
+                    3 => reply[4] as usize + 2,
+                    4 => 16 - 1 + 2,
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "SOCKS5 proxy replied an unknown address type",
+                        ))
+                    }
+                };
+                Ok((stream, vec![0u8; rest]))
+            })
+            .and_then(|(stream, rest)| read_exact(stream, rest))
+            .map(|(stream, _)| stream),
+    )
+}
+
+/// Username and password authentication of SOCKS5
+fn socks5_login(stream: TcpStream, user: &str, password: &str) -> Connecting<TcpStream> {
+    if user.len() > 255 || password.len() > 255 {
+        return Box::new(future::err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "SOCKS5 username and password are at most 255 bytes",
+        )));
+    }
+    let mut request = vec![1, user.len() as u8];
+    request.extend_from_slice(user.as_bytes());
+    request.push(password.len() as u8);
+    request.extend_from_slice(password.as_bytes());
+    Box::new(
+        write_all(stream, request)
+            .and_then(|(stream, _)| read_exact(stream, [0u8; 2]))
+            .and_then(|(stream, reply)| {
+                if reply[1] == 0 {
+                    Ok(stream)
+                } else {
+                    Err(refused("SOCKS5 proxy rejected the credentials".to_string()))
+                }
+            }),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::{Client, ClientExt};
+    use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue};
+    use futures::Stream;
+    use hyper::service::service_fn;
+    use hyper::{Body, Client as HyperClient, Request, Response, Server};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream as StdTcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use tokio::runtime::Runtime;
+
+    /// Jsonrpc node echoing the request uri and the proxy credentials as the result
+    fn node(runtime: &mut Runtime) -> String {
+        let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(|| {
+            service_fn(|req: Request<Body>| {
+                let auth = req
+                    .headers()
+                    .get("proxy-authorization")
+                    .map(|value| value.to_str().unwrap().to_string())
+                    .unwrap_or_default();
+                let result = format!("{},{}", req.uri(), auth);
+                req.into_body().concat2().map(move |body| {
+                    let param: JsonRpcParams =
+                        serde_json::from_slice(&body).unwrap_or_else(|_| JsonRpcParams::new());
+                    let id = match param.get("id") {
+                        Some(ParamsValue::Int(id)) => *id,
+                        _ => 0,
+                    };
+                    let response = JsonRpcResponse::from_result(
+                        id,
+                        ResponseValue::Singe(ParamsValue::String(result)),
+                    );
+                    Response::new(Body::from(serde_json::to_string(&response).unwrap()))
+                })
+            })
+        });
+        let addr = server.local_addr().to_string();
+        runtime.spawn(server.map_err(|_| ()));
+        addr
+    }
+
+    /// Tunnel stand-in, speak SOCKS5 or `CONNECT` by the first byte, record the targets
+    fn tunnel(targets: Arc<Mutex<Vec<String>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            for client in listener.incoming() {
+                let mut client = client.unwrap();
+                let mut head = vec![0u8; 1];
+                client.read_exact(&mut head).unwrap();
+                let target = if head[0] == 5 {
+                    let mut methods = [0u8; 1];
+                    client.read_exact(&mut methods).unwrap();
+                    client
+                        .read_exact(&mut vec![0u8; methods[0] as usize])
+                        .unwrap();
+                    client.write_all(&[5, 0]).unwrap();
+                    let mut request = [0u8; 10];
+                    client.read_exact(&mut request).unwrap();
+                    assert_eq!(request[..4], [5, 1, 0, 1]);
+                    client.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+                    format!(
+                        "{}.{}.{}.{}:{}",
+                        request[4],
+                        request[5],
+                        request[6],
+                        request[7],
+                        u16::from_be_bytes([request[8], request[9]])
+                    )
+                } else {
+                    while !head.ends_with(b"\r\n\r\n") {
+                        let mut byte = [0u8; 1];
+                        client.read_exact(&mut byte).unwrap();
+                        head.push(byte[0]);
+                    }
+                    client
+                        .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                        .unwrap();
+                    let head = String::from_utf8(head).unwrap();
+                    head.split_whitespace().nth(1).unwrap().to_string()
+                };
+                targets.lock().unwrap().push(target.clone());
+                let server = StdTcpStream::connect(target).unwrap();
+                let (mut client_read, mut server_write) =
+                    (client.try_clone().unwrap(), server.try_clone().unwrap());
+                let (mut server_read, mut client_write) = (server, client);
+                thread::spawn(move || io::copy(&mut client_read, &mut server_write));
+                thread::spawn(move || io::copy(&mut server_read, &mut client_write));
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_proxy() {
+        let mut runtime = Runtime::new().unwrap();
+        let node = node(&mut runtime);
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let tunnel = tunnel(Arc::clone(&targets));
+
+        // Absolute-form through a http proxy, the node stands in for the proxy
+        let proxy = format!("http://user:password@{}", node).parse().unwrap();
+        let client = Client::new()
+            .set_uri("http://chain.invalid:1337")
+            .set_proxy(Some(proxy));
+        let response = client.get_block_number().unwrap();
+        assert_eq!(
+            format!("{}", response.result().unwrap()),
+            "\"http://chain.invalid:1337/,Basic dXNlcjpwYXNzd29yZA==\""
+        );
+
+        let proxy = format!("socks5://{}", tunnel).parse().unwrap();
+        let client = Client::new()
+            .set_uri(&format!("http://{}", node))
+            .set_proxy(Some(proxy));
+        let response = client.get_block_number().unwrap();
+        assert_eq!(format!("{}", response.result().unwrap()), "\"/,\"");
+
+        // Https targets get a `CONNECT` tunnel, plain http inside is enough for the stand-in
+        let mut proxies = Proxies::default();
+        proxies.set(Some(format!("http://{}", tunnel).parse().unwrap()));
+        let client = HyperClient::builder().build::<_, Body>(ProxyConnector::new(proxies.clone()));
+        let status = runtime
+            .block_on(client.get(format!("https://{}", node).parse().unwrap()))
+            .unwrap()
+            .status();
+        assert!(status.is_success());
+        assert_eq!(*targets.lock().unwrap(), vec![node.clone(), node]);
+
+        let proxies = Proxies {
+            no_proxy: vec!["example.com".to_string()],
+            ..proxies
+        };
+        assert!(proxies.find("https", "api.example.com").is_none());
+        assert!(proxies.find("http", "example.com.cn").is_some());
+        assert_eq!(
+            "socks5://u:p%40ss@[::1]".parse::<Proxy>().unwrap(),
+            Proxy {
+                kind: ProxyKind::Socks5,
+                addr: "[::1]:1080".to_string(),
+                auth: Some(("u".to_string(), "p@ss".to_string())),
+            }
+        );
+    }
+
+    #[test]
+    fn test_socks5_long_host() {
+        let mut runtime = Runtime::new().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let host = "a".repeat(256);
+        let tunnel = TcpStream::connect(&addr)
+            .and_then(move |stream| socks5_tunnel(stream, host, 80, None).map(|_| ()));
+        match runtime.block_on(tunnel) {
+            Err(ref err) if err.kind() == io::ErrorKind::InvalidInput => {}
+            other => panic!("expect the long host to be invalid, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+use std::fmt;
+
+use hyper::Uri;
+use serde_json::{self, json, Value};
+
+use crate::error::ToolError;
+use crate::rpctypes::JsonRpcResponse;
+
+/// Nodes which gave the same answer
+#[derive(Clone, Debug, Serialize)]
+pub struct Agreement {
+    response: JsonRpcResponse,
+    urls: Vec<String>,
+}
+
+impl Agreement {
+    /// The answer, with the id of the first node
+    pub fn response(&self) -> &JsonRpcResponse {
+        &self.response
+    }
+
+    /// Nodes which gave the answer
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+}
+
+/// Every answer of a quorum read which didn't reach exactly one quorum
+#[derive(Clone, Debug, Serialize)]
+pub struct QuorumReport {
+    quorum: usize,
+    agreements: Vec<Agreement>,
+    failures: Vec<(String, String)>,
+}
+
+impl QuorumReport {
+    /// Number of agreeing answers needed
+    pub fn quorum(&self) -> usize {
+        self.quorum
+    }
+
+    /// Different answers, the most agreed first
+    pub fn agreements(&self) -> &[Agreement] {
+        &self.agreements
+    }
+
+    /// Nodes which didn't answer, with the reason
+    pub fn failures(&self) -> &[(String, String)] {
+        &self.failures
+    }
+}
+
+impl fmt::Display for QuorumReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", serde_json::to_string_pretty(self).unwrap())
+    }
+}
+
+/// Result and error of the response, the id doesn't matter
+fn answer(response: &JsonRpcResponse) -> Value {
+    json!({
+        "result": response.result(),
+        "error": response.error(),
+    })
+}
+
+/// Return the answer of at least `quorum` nodes, or the report when no answer
+/// or several different answers reach it
+pub(crate) fn decide(
+    responses: Vec<(Uri, Result<JsonRpcResponse, ToolError>)>,
+    quorum: usize,
+) -> Result<JsonRpcResponse, ToolError> {
+    let mut groups: Vec<(Value, Agreement)> = Vec::new();
+    let mut failures = Vec::new();
+    for (url, response) in responses {
+        match response {
+            Ok(response) => {
+                let value = answer(&response);
+                match groups.iter_mut().find(|(answer, _)| *answer == value) {
+                    Some((_, agreement)) => agreement.urls.push(url.to_string()),
+                    None => groups.push((
+                        value,
+                        Agreement {
+                            response,
+                            urls: vec![url.to_string()],
+                        },
+                    )),
+                }
+            }
+            Err(err) => failures.push((url.to_string(), err.to_string())),
+        }
+    }
+    groups.sort_by_key(|(_, agreement)| Reverse(agreement.urls.len()));
+    let reached = groups
+        .iter()
+        .filter(|(_, agreement)| agreement.urls.len() >= quorum)
+        .count();
+
+    match groups.first() {
+        Some((_, agreement)) if reached == 1 => Ok(agreement.response.clone()),
+        _ => Err(ToolError::Quorum(QuorumReport {
+            quorum,
+            agreements: groups.into_iter().map(|(_, agreement)| agreement).collect(),
+            failures,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::async_client::AsyncClient;
+    use crate::client::async_client::ResponseFuture;
+    use crate::client::transport::Transport;
+    use crate::rpctypes::{ErrorResponse, JsonRpcParams, ParamsValue, ResponseValue};
+    use futures::future;
+    use tokio::runtime::Runtime;
+
+    /// Answer with the port, except port 1003 which is down, `getCode` fails everywhere
+    struct Nodes;
+
+    impl Transport for Nodes {
+        fn send(
+            &self,
+            url: &Uri,
+            params: Vec<JsonRpcParams>,
+        ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+            let port = url.port_part().unwrap().as_u16();
+            if port == 1003 {
+                return Box::new(future::err(ToolError::HttpStatus(503)));
+            }
+            let balance = if port == 1002 { "0x2" } else { "0x1" };
+            Box::new(future::ok(
+                params
+                    .iter()
+                    .map(|param| match param.get("method") {
+                        Some(ParamsValue::String(method)) if method == "getCode" => {
+                            JsonRpcResponse::from_error(
+                                u64::from(port),
+                                ErrorResponse::new(-32000, "no code"),
+                            )
+                        }
+                        _ => JsonRpcResponse::from_result(
+                            u64::from(port),
+                            ResponseValue::Singe(ParamsValue::String(balance.to_string())),
+                        ),
+                    })
+                    .collect(),
+            ))
+        }
+    }
+
+    fn urls() -> impl Iterator<Item = Uri> {
+        (1000..1004).map(|port| format!("http://127.0.0.1:{}", port).parse().unwrap())
+    }
+
+    fn params(method: &str) -> JsonRpcParams {
+        JsonRpcParams::new().insert("method", ParamsValue::String(method.to_string()))
+    }
+
+    #[test]
+    fn test_quorum_reached() {
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(Nodes);
+        let response = runtime
+            .block_on(client.send_request_with_quorum(urls(), params("getBalance"), 2))
+            .unwrap();
+        assert_eq!(format!("{}", response.result().unwrap()), "\"0x1\"");
+    }
+
+    #[test]
+    fn test_quorum_disagreement() {
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(Nodes);
+        match runtime.block_on(client.send_request_with_quorum(urls(), params("getBalance"), 3)) {
+            Err(ToolError::Quorum(report)) => {
+                assert_eq!(report.quorum(), 3);
+                assert_eq!(report.agreements().len(), 2);
+                assert_eq!(report.agreements()[0].urls().len(), 2);
+                assert_eq!(report.agreements()[1].urls(), ["http://127.0.0.1:1002/"]);
+                assert_eq!(report.failures().len(), 1);
+            }
+            other => panic!("expect disagreement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_quorum_ambiguous() {
+        // Two different answers reach a quorum of one
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(Nodes);
+        match runtime.block_on(client.send_request_with_quorum(urls(), params("getBalance"), 1)) {
+            Err(ToolError::Quorum(report)) => assert_eq!(report.agreements().len(), 2),
+            other => panic!("expect two quorums, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_quorum_agreed_error() {
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(Nodes);
+        assert!(runtime
+            .block_on(client.send_request_with_quorum(urls(), params("getCode"), 2))
+            .unwrap()
+            .error()
+            .is_some());
+    }
+
+    #[test]
+    fn test_quorum_agreed_error_strict() {
+        let mut runtime = Runtime::new().unwrap();
+        let client = AsyncClient::new().set_transport(Nodes).set_strict(true);
+        match runtime.block_on(client.send_request_with_quorum(urls(), params("getCode"), 2)) {
+            Err(ToolError::JsonRpc(error)) => assert_eq!(error.code(), -32000),
+            other => panic!("expect the agreed error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::future::{self, Loop};
+use futures::Future;
+use rand::{thread_rng, Rng};
+use tokio::timer::{Delay, Timeout};
+
+use crate::client::async_client::ResponseFuture;
+use crate::error::ToolError;
+
+type Retryable = Arc<dyn Fn(&ToolError) -> bool + Send + Sync>;
+
+/// Timeouts and retries of the jsonrpc requests
+///
+/// Default is no timeout and no retry. Jsonrpc errors are answers from the node,
+/// they are never retried, only the failures to get an answer are.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    max_retries: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retryable: Retryable,
+}
+
+impl RetryPolicy {
+    /// Create a policy without timeout and retry
+    pub fn new() -> Self {
+        RetryPolicy {
+            timeout: None,
+            connect_timeout: None,
+            max_retries: 0,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable: Arc::new(is_transient),
+        }
+    }
+
+    /// Set the timeout of each attempt, including the connection
+    pub fn set_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get timeout
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set the timeout to establish a connection, only used by the default http transport
+    pub fn set_connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Get connect timeout
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Set the max number of retries after the first attempt
+    pub fn set_max_retries(mut self, retries: usize) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Get max retries
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Set the backoff before the first retry and its upper bound,
+    /// the backoff doubles after each retry
+    pub fn set_backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Randomize each backoff between half and all of it, default is true
+    pub fn set_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Decide which errors are retried, default is `is_transient`
+    pub fn set_retryable<F>(mut self, retryable: F) -> Self
+    where
+        F: Fn(&ToolError) -> bool + Send + Sync + 'static,
+    {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    /// Whether the error is worth another attempt
+    pub fn is_retryable(&self, err: &ToolError) -> bool {
+        (self.retryable)(err)
+    }
+
+    /// The backoff before the retry, count from 0
+    pub fn backoff(&self, retry: usize) -> Duration {
+        let backoff = self
+            .backoff
+            .checked_mul(1 << retry.min(31) as u32)
+            .map(|backoff| backoff.min(self.max_backoff))
+            .unwrap_or(self.max_backoff);
+        if self.jitter && backoff > Duration::from_millis(0) {
+            let half = backoff / 2;
+            half + half.mul_f64(thread_rng().gen_range(0.0, 1.0))
+        } else {
+            backoff
+        }
+    }
+
+    /// Run the request with timeout, create it again on retryable failures
+    pub(crate) fn run<T, F>(&self, request: F) -> ResponseFuture<T, ToolError>
+    where
+        T: Send + 'static,
+        F: FnMut() -> ResponseFuture<T, ToolError> + Send + 'static,
+    {
+        if self.timeout.is_none() && self.max_retries == 0 {
+            let mut request = request;
+            return request();
+        }
+        let policy = self.clone();
+        Box::new(future::loop_fn(
+            (request, 0),
+            move |(mut request, retry)| {
+                let policy = policy.clone();
+                policy.with_timeout(request()).then(
+                    move |result| -> ResponseFuture<Loop<T, (F, usize)>, ToolError> {
+                        match result {
+                            Err(ref err)
+                                if retry < policy.max_retries && policy.is_retryable(err) =>
+                            {
+                                Box::new(
+                                    Delay::new(Instant::now() + policy.backoff(retry))
+                                        .map_err(|err| {
+                                            ToolError::Transport(format!(
+                                                "Retry backoff timer error: {}",
+                                                err
+                                            ))
+                                        })
+                                        .map(move |_| Loop::Continue((request, retry + 1))),
+                                )
+                            }
+                            result => Box::new(future::result(result.map(Loop::Break))),
+                        }
+                    },
+                )
+            },
+        ))
+    }
+
+    fn with_timeout<T: Send + 'static>(
+        &self,
+        request: ResponseFuture<T, ToolError>,
+    ) -> ResponseFuture<T, ToolError> {
+        match self.timeout {
+            Some(timeout) => Box::new(Timeout::new(request, timeout).map_err(|err| {
+                if err.is_elapsed() {
+                    ToolError::Timeout
+                } else if err.is_inner() {
+                    err.into_inner().unwrap()
+                } else {
+                    ToolError::Transport(format!("Request timeout timer error: {}", err))
+                }
+            })),
+            None => request,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("max_retries", &self.max_retries)
+            .field("backoff", &self.backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .finish()
+    }
+}
+
+/// Connection failures, timeouts and 5xx responses
+pub fn is_transient(err: &ToolError) -> bool {
+    match err {
+        ToolError::Hyper(err) => err.is_connect(),
+        ToolError::Timeout => true,
+        ToolError::HttpStatus(status) => *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::runtime::Runtime;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .set_max_retries(3)
+            .set_backoff(Duration::from_millis(1), Duration::from_millis(4))
+    }
+
+    #[test]
+    fn test_retry_transient_errors() {
+        let mut runtime = Runtime::new().unwrap();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        let request = policy().run(move || -> ResponseFuture<u64, ToolError> {
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Box::new(future::err(ToolError::HttpStatus(503))),
+                _ => Box::new(future::ok(1)),
+            }
+        });
+        assert_eq!(runtime.block_on(request).unwrap(), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_no_retry_of_permanent_errors() {
+        let mut runtime = Runtime::new().unwrap();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        let request = policy().run(move || -> ResponseFuture<u64, ToolError> {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::new(future::err(ToolError::HttpStatus(404)))
+        });
+        assert!(runtime.block_on(request).is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_retry_timeout() {
+        let mut runtime = Runtime::new().unwrap();
+        let request = policy()
+            .set_max_retries(1)
+            .set_timeout(Some(Duration::from_millis(10)))
+            .run(|| -> ResponseFuture<u64, ToolError> { Box::new(future::empty()) });
+        match runtime.block_on(request) {
+            Err(ToolError::Timeout) => {}
+            other => panic!("expect timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = policy();
+        for retry in 0..10 {
+            assert!(policy.backoff(retry) <= Duration::from_millis(4));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use futures::{sync, Future};
+use lazy_static::lazy_static;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::client::async_client::ResponseFuture;
+use crate::error::ToolError;
+
+lazy_static! {
+    /// The runtime of the clients created without one, alive while one of them is
+    static ref SHARED: Mutex<Weak<Inner>> = Mutex::new(Weak::new());
+}
+
+/// The runtime which drives the requests of the blocking clients
+///
+/// Clones share the runtime, it is stopped by `shutdown` or when the last clone is dropped.
+/// The clients created by `Client::new` share one runtime.
+#[derive(Clone)]
+pub struct ClientRuntime {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    runtime: Mutex<Option<Runtime>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.get_mut().unwrap().take() {
+            // Don't wait, the last clone may be dropped by a task of the runtime
+            let _ = runtime.shutdown_now();
+        }
+    }
+}
+
+impl ClientRuntime {
+    /// Start a runtime of its own
+    pub fn new() -> Result<Self, ToolError> {
+        let runtime = Builder::new().name_prefix("cita-client-").build()?;
+        Ok(ClientRuntime {
+            inner: Arc::new(Inner {
+                runtime: Mutex::new(Some(runtime)),
+            }),
+        })
+    }
+
+    /// The runtime shared by the clients, a new one is started if none is running
+    /// ---
+    /// When the runtime can't start, panic
+    pub fn shared() -> Self {
+        let mut shared = SHARED.lock().unwrap();
+        match shared.upgrade().map(|inner| ClientRuntime { inner }) {
+            Some(runtime) if !runtime.is_shutdown() => runtime,
+            _ => {
+                let runtime = ClientRuntime::new().expect("Can't start the client runtime");
+                *shared = Arc::downgrade(&runtime.inner);
+                runtime
+            }
+        }
+    }
+
+    /// Whether the runtime was shut down
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.runtime.lock().unwrap().is_none()
+    }
+
+    /// Stop the runtime and wait for its threads, the requests in flight are cancelled
+    ///
+    /// The clients of the runtime fail afterwards. It must not be called by a task of the runtime.
+    pub fn shutdown(&self) {
+        let runtime = self.inner.runtime.lock().unwrap().take();
+        if let Some(runtime) = runtime {
+            let _ = runtime.shutdown_now().wait();
+        }
+    }
+
+    /// Spawn the future on the runtime
+    pub fn spawn<F>(&self, future: F) -> Result<(), ToolError>
+    where
+        F: Future<Item = (), Error = ()> + Send + 'static,
+    {
+        match *self.inner.runtime.lock().unwrap() {
+            Some(ref runtime) => {
+                runtime.executor().spawn(future);
+                Ok(())
+            }
+            None => Err(ToolError::Transport(
+                "The client runtime is shut down".to_string(),
+            )),
+        }
+    }
+
+    /// Drive the future on the runtime and wait for its result
+    pub fn block_on<T: Send + 'static>(
+        &self,
+        future: ResponseFuture<T, ToolError>,
+    ) -> Result<T, ToolError> {
+        let (tx, rx) = sync::oneshot::channel::<Result<T, ToolError>>();
+        self.spawn(future.then(move |res| tx.send(res)).map_err(|_| ()))?;
+        rx.wait().map_err(|_| {
+            ToolError::Transport("The request was cancelled by the client runtime".to_string())
+        })?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::{Client, ClientExt};
+    use crate::client::transport::MemoryTransport;
+    use crate::rpctypes::{JsonRpcParams, ParamsValue, ResponseValue};
+
+    fn client(runtime: &ClientRuntime) -> Client {
+        let transport = Arc::new(MemoryTransport::new().on_result(
+            "blockNumber",
+            ResponseValue::Singe(ParamsValue::String("0x10".to_string())),
+        ));
+        Client::new()
+            .set_runtime(runtime.clone())
+            .set_transport(transport)
+    }
+
+    #[test]
+    fn test_shared_runtime() {
+        let shared = ClientRuntime::shared();
+        let client = Client::new();
+        assert!(Arc::ptr_eq(&shared.inner, &client.runtime().inner));
+        assert!(Arc::ptr_eq(&shared.inner, &client.clone().runtime().inner));
+    }
+
+    #[test]
+    fn test_own_runtime() {
+        let runtime = ClientRuntime::new().unwrap();
+        let client = client(&runtime);
+        assert!(Arc::ptr_eq(&runtime.inner, &client.runtime().inner));
+        assert_eq!(client.get_current_height().unwrap(), 16);
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let runtime = ClientRuntime::new().unwrap();
+        let client = client(&runtime);
+        client.clone().shutdown();
+        assert!(runtime.is_shutdown());
+        assert!(!ClientRuntime::shared().is_shutdown());
+    }
+
+    #[test]
+    fn test_requests_after_shutdown() {
+        let runtime = ClientRuntime::new().unwrap();
+        let client = client(&runtime);
+        client.clone().shutdown();
+        match client.get_current_height() {
+            Err(ToolError::Transport(_)) => {}
+            other => panic!("expect the runtime to be shut down, got {:?}", other),
+        }
+        assert!(client.get_block_number().is_err());
+        let params =
+            JsonRpcParams::new().insert("method", ParamsValue::String("blockNumber".to_string()));
+        assert!(client.send_request_each(vec![params].into_iter()).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::client::async_client::{AsyncClient, AsyncClientExt, ResponseFuture};
 use crate::client::basic::ClientExt;
 use crate::client::{remove_0x, TransactionOptions};
 
@@ -5,10 +6,11 @@ use std::str::{self, FromStr};
 
 use crate::abi::contract_encode_input;
 use crate::error::ToolError;
-use crate::rpctypes::JsonRpcResponse;
+use crate::rpctypes::{JsonRpcResponse, ParamsValue, ResponseValue};
 use crate::LowerHex;
 use ethabi::{Address, Contract};
 use failure::Fail;
+use futures::{future, Future};
 use tool_derive::ContractExt;
 use types::U256;
 
@@ -68,6 +70,51 @@ where
     }
 }
 
+/// Call/SendTx to a contract method, the futures version of `ContractCall`
+pub trait AsyncContractCall<R, E>
+where
+    R: serde::Serialize + serde::Deserialize<'static> + ::std::fmt::Display,
+    E: Fail,
+{
+    /// Prepare contract call arguments
+    fn prepare_call_args(
+        &self,
+        name: &str,
+        values: &[&str],
+        to_addr: Option<Address>,
+    ) -> Result<(String, String), E>;
+
+    /// SendTx a contract method
+    fn contract_send_tx(
+        &self,
+        name: &str,
+        values: &[&str],
+        quota: Option<u64>,
+        to_addr: Option<Address>,
+    ) -> ResponseFuture<R, E>;
+
+    /// Call a contract method
+    fn contract_call(
+        &self,
+        name: &str,
+        values: &[&str],
+        to_addr: Option<Address>,
+        height: Option<&str>,
+    ) -> ResponseFuture<R, E>;
+
+    /// Call a contract method with a to_address
+    fn contract_call_to_address(
+        &self,
+        function_name: &str,
+        values: &[&str],
+        address: &str,
+        height: Option<&str>,
+    ) -> ResponseFuture<R, E> {
+        let address = Address::from_str(remove_0x(address)).unwrap();
+        self.contract_call(function_name, values, Some(address), height)
+    }
+}
+
 /// Group System Contract
 pub trait GroupExt<T, R, E>: ContractCall<R, E>
 where
@@ -832,6 +879,46 @@ where
     }
 }
 
+impl QuotaManageClient<AsyncClient> {
+    /// Create a QuotaManager client of the asynchronous api
+    pub fn create_async(client: AsyncClient) -> Self {
+        static ABI: &str = include_str!("../../contract_abi/QuotaManager.abi");
+        QuotaManageClient {
+            client,
+            address: Address::from_str("ffffffffffffffffffffffffffffffffff020003").unwrap(),
+            contract: Contract::load(ABI.as_bytes()).unwrap(),
+        }
+    }
+
+    /// Get account quota upper limit of the specific account, as a number
+    pub fn account_quota_limit(
+        &self,
+        address: &str,
+        height: Option<&str>,
+    ) -> ResponseFuture<U256, ToolError> {
+        let values = [remove_0x(address)];
+        Box::new(
+            <Self as AsyncContractCall<JsonRpcResponse, ToolError>>::contract_call(
+                self, "getAQL", &values, None, height,
+            )
+            .and_then(|response| {
+                if let Some(error) = response.error() {
+                    return Err(ToolError::JsonRpc(error));
+                }
+                match response.result() {
+                    Some(ResponseValue::Singe(ParamsValue::String(limit))) => {
+                        U256::from_str(remove_0x(&limit))
+                            .map_err(|err| ToolError::InvalidInput(err.to_string()))
+                    }
+                    _ => Err(ToolError::Transport(
+                        "No account quota limit in the response".to_string(),
+                    )),
+                }
+            }),
+        )
+    }
+}
+
 /// Admin manage client
 #[derive(ContractExt)]
 #[contract(addr = "0xffffffffffffffffffffffffffffffffff02000c")]
@@ -1,4 +1,162 @@
-use types::U256;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde_json;
+use types::{Address, U256};
+use uuid::Uuid;
+
+use crate::client::remove_0x;
+use crate::error::ToolError;
+use crate::protos::Transaction;
+use crate::LowerHex;
+
+lazy_static! {
+    /// Serializes the counter updates of this process
+    static ref COUNTER_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// The source of the transaction nonce
+#[derive(Clone, Copy, Debug)]
+pub enum NonceSource<'a> {
+    /// A random uuid v4, the default
+    Uuid,
+    /// The given nonce
+    Fixed(&'a str),
+    /// The next value of the account counter, persisted in the json file
+    ///
+    /// The file maps the accounts to their next value, starting from 0.
+    /// It must not be shared by several processes at the same time.
+    Counter(&'a Path, Address),
+}
+
+impl<'a> NonceSource<'a> {
+    /// Take the next nonce, a counter is incremented and saved
+    pub fn next_nonce(&self) -> Result<String, ToolError> {
+        match self {
+            NonceSource::Uuid => Ok(hex::encode(Uuid::new_v4().as_bytes())),
+            NonceSource::Fixed(nonce) => Ok(nonce.to_string()),
+            NonceSource::Counter(path, account) => next_counter(path, account),
+        }
+    }
+}
+
+/// Take the next value of the account counter in the file
+fn next_counter(path: &Path, account: &Address) -> Result<String, ToolError> {
+    let _guard = COUNTER_LOCK.lock().unwrap();
+    let mut counters: BTreeMap<String, u64> = match fs::read(path) {
+        Ok(content) => serde_json::from_slice(&content).map_err(ToolError::SerdeJson)?,
+        Err(ref err) if err.kind() == ::std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(err) => return Err(ToolError::Stdio(err)),
+    };
+    let counter = counters
+        .entry(format!("0x{}", account.lower_hex()))
+        .or_insert(0);
+    let nonce = counter.to_string();
+    *counter += 1;
+    // Replace the file at once, a crash never leaves half of it
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, serde_json::to_vec_pretty(&counters).unwrap())
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(ToolError::Stdio)?;
+    Ok(nonce)
+}
+
+/// Estimate the quota of a transaction without one
+///
+/// The estimation is multiplied by the safety multiplier, then checked against the cap
+/// and the account quota limit of the sender. A transaction whose multiplied quota
+/// exceeds one of the limits fails before it is sent, unless clamping is set.
+#[derive(Clone, Copy, Debug)]
+pub struct AutoQuota {
+    multiplier: f64,
+    cap: Option<u64>,
+    sender: Option<Address>,
+    clamp: bool,
+}
+
+impl AutoQuota {
+    /// Default is a multiplier of 1.2, no cap, the sender of the client private key, no clamping
+    pub fn new() -> Self {
+        AutoQuota {
+            multiplier: 1.2,
+            cap: None,
+            sender: None,
+            clamp: false,
+        }
+    }
+
+    /// Set the safety multiplier of the estimation
+    pub fn set_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Get multiplier
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Set the upper limit of the quota
+    pub fn set_cap(mut self, cap: Option<u64>) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Get cap
+    pub fn cap(&self) -> Option<u64> {
+        self.cap
+    }
+
+    /// Set the sender, needed when the client has no private key
+    pub fn set_sender(mut self, sender: Option<Address>) -> Self {
+        self.sender = sender;
+        self
+    }
+
+    /// Get sender
+    pub fn sender(&self) -> Option<Address> {
+        self.sender
+    }
+
+    /// Set clamping: a multiplied quota over a limit is lowered to the limit instead of
+    /// failing, only the raw estimation must fit in the limits
+    pub fn set_clamp(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// Get clamp
+    pub fn clamp(&self) -> bool {
+        self.clamp
+    }
+
+    /// The multiplied quota of the estimation, an error when it can't fit in the limits
+    pub(crate) fn quota(&self, estimation: u64, account_limit: u64) -> Result<u64, ToolError> {
+        let cap = self.cap.unwrap_or(u64::MAX);
+        let mut quota = ((estimation as f64 * self.multiplier).ceil() as u64).max(estimation);
+        for &(limit, name) in &[(cap, "quota cap"), (account_limit, "account quota limit")] {
+            let needed = if self.clamp { estimation } else { quota };
+            if needed > limit {
+                return Err(ToolError::QuotaExceeded {
+                    estimation: needed,
+                    limit,
+                    name,
+                });
+            }
+            quota = quota.min(limit);
+        }
+        Ok(quota)
+    }
+}
+
+impl Default for AutoQuota {
+    fn default() -> Self {
+        AutoQuota::new()
+    }
+}
 
 /// Transaction parameter option
 #[derive(Clone, Copy, Debug)]
@@ -9,6 +167,10 @@ pub struct TransactionOptions<'a> {
     quota: Option<u64>,
     value: Option<U256>,
     version: Option<u32>,
+    valid_until_block: Option<u64>,
+    valid_blocks: Option<u64>,
+    nonce: NonceSource<'a>,
+    auto_quota: Option<AutoQuota>,
 }
 
 impl<'a> TransactionOptions<'a> {
@@ -21,6 +183,10 @@ impl<'a> TransactionOptions<'a> {
             quota: None,
             value: None,
             version: None,
+            valid_until_block: None,
+            valid_blocks: None,
+            nonce: NonceSource::Uuid,
+            auto_quota: None,
         }
     }
 
@@ -71,6 +237,18 @@ impl<'a> TransactionOptions<'a> {
         self.quota
     }
 
+    /// Set auto quota. Estimate the quota when it is None,
+    /// default is None, the quota is 10_000_000
+    pub fn set_auto_quota(mut self, auto_quota: Option<AutoQuota>) -> Self {
+        self.auto_quota = auto_quota;
+        self
+    }
+
+    /// Get auto quota
+    pub fn auto_quota(&self) -> Option<AutoQuota> {
+        self.auto_quota
+    }
+
     /// Set value. Transaction transfer amount
     pub fn set_value(mut self, value: Option<U256>) -> Self {
         self.value = value;
@@ -93,6 +271,84 @@ impl<'a> TransactionOptions<'a> {
         self.version
     }
 
+    /// Set the last block the transaction is valid for,
+    /// default is None, the current height plus the valid blocks
+    pub fn set_valid_until_block(mut self, height: Option<u64>) -> Self {
+        self.valid_until_block = height;
+        self
+    }
+
+    /// Get valid until block
+    pub fn valid_until_block(&self) -> Option<u64> {
+        self.valid_until_block
+    }
+
+    /// Set the number of blocks after the current height the transaction is valid for,
+    /// default is 88
+    pub fn set_valid_blocks(mut self, blocks: Option<u64>) -> Self {
+        self.valid_blocks = blocks;
+        self
+    }
+
+    /// Get valid blocks
+    pub fn valid_blocks(&self) -> Option<u64> {
+        self.valid_blocks
+    }
+
+    /// Set the source of the nonce, default is a random uuid
+    pub fn set_nonce(mut self, nonce: NonceSource<'a>) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Get nonce source
+    pub fn nonce(&self) -> NonceSource<'a> {
+        self.nonce
+    }
+
+    /// Build the transaction without any query to the chain
+    ///
+    /// The version and valid until block must be set, the chain id is the one
+    /// of the version. The quota is 10_000_000 when it is None.
+    pub fn build_offline(&self, chain_id: U256) -> Result<Transaction, ToolError> {
+        let version = self.version.ok_or_else(|| {
+            ToolError::InvalidInput("The offline transaction needs the version".to_string())
+        })?;
+        let valid_until_block = self.valid_until_block.ok_or_else(|| {
+            ToolError::InvalidInput(
+                "The offline transaction needs the valid until block".to_string(),
+            )
+        })?;
+        if self.quota.is_none() && self.auto_quota.is_some() {
+            return Err(ToolError::InvalidInput(
+                "The auto quota needs to query the chain".to_string(),
+            ));
+        }
+        self.parts()?.build(
+            self.quota.unwrap_or(10_000_000),
+            valid_until_block,
+            version,
+            chain_id,
+        )
+    }
+
+    /// The content of the transaction, a counter nonce is taken once it is built
+    pub(crate) fn parts(&self) -> Result<TransactionParts, ToolError> {
+        let data = hex::decode(remove_0x(self.code)).map_err(ToolError::Decode)?;
+        let value = self.value.unwrap_or_else(U256::zero).completed_lower_hex();
+        let value = hex::decode(value).map_err(ToolError::Decode)?;
+        let nonce = match self.nonce {
+            NonceSource::Counter(path, account) => PartsNonce::Counter(path.to_path_buf(), account),
+            source => PartsNonce::Taken(source.next_nonce()?),
+        };
+        Ok(TransactionParts {
+            data,
+            value,
+            address: remove_0x(self.address).to_string(),
+            nonce,
+        })
+    }
+
     /// Restore initialization status
     pub fn clear(&mut self) {
         self.value = None;
@@ -100,7 +356,11 @@ impl<'a> TransactionOptions<'a> {
         self.current_height = None;
         self.address = "0x";
         self.code = "0x";
-        self.version = None
+        self.version = None;
+        self.valid_until_block = None;
+        self.valid_blocks = None;
+        self.nonce = NonceSource::Uuid;
+        self.auto_quota = None;
     }
 }
 
@@ -109,3 +369,221 @@ impl Default for TransactionOptions<'static> {
         TransactionOptions::new()
     }
 }
+
+/// The owned content of a transaction, built once the chain parameters are known
+pub(crate) struct TransactionParts {
+    data: Vec<u8>,
+    value: Vec<u8>,
+    address: String,
+    nonce: PartsNonce,
+}
+
+/// The nonce of the parts, a counter is not burned by a transaction which fails to build
+enum PartsNonce {
+    Taken(String),
+    Counter(PathBuf, Address),
+}
+
+impl TransactionParts {
+    /// Build the transaction of the version with its chain id
+    pub(crate) fn build(
+        self,
+        quota: u64,
+        valid_until_block: u64,
+        version: u32,
+        chain_id: U256,
+    ) -> Result<Transaction, ToolError> {
+        let mut tx = Transaction::new();
+        tx.set_data(self.data);
+        tx.set_valid_until_block(valid_until_block);
+        tx.set_quota(quota);
+        tx.set_value(self.value);
+        tx.set_version(version);
+
+        // Create a contract if the target address is empty
+        if version == 0 {
+            if chain_id > U256::from(u32::MAX) {
+                return Err(ToolError::InvalidInput(
+                    "The chain id of version 0 must fit in u32".to_string(),
+                ));
+            }
+            tx.set_to(self.address);
+            tx.set_chain_id(chain_id.low_u32());
+        } else if version < 3 {
+            tx.set_to_v1(hex::decode(&self.address).map_err(ToolError::Decode)?);
+            tx.set_chain_id_v1(
+                hex::decode(chain_id.completed_lower_hex()).map_err(ToolError::Decode)?,
+            );
+        } else {
+            return Err(ToolError::InvalidInput("Invalid version".to_string()));
+        }
+        // Take the nonce last, every other check has passed
+        tx.set_nonce(match self.nonce {
+            PartsNonce::Taken(nonce) => nonce,
+            PartsNonce::Counter(path, account) => next_counter(&path, &account)?,
+        });
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::Client;
+    use crate::client::transport::MemoryTransport;
+    use crate::rpctypes::ResponseValue;
+
+    #[test]
+    fn test_validity_and_nonce() {
+        let metadata: ResponseValue = serde_json::from_str(r#"{"chainId": 1}"#).unwrap();
+        let transport = MemoryTransport::new()
+            .on_result("getMetaData", metadata)
+            .on_result("blockNumber", serde_json::from_str(r#""0x10""#).unwrap());
+        let mut client = Client::new().set_transport(transport);
+        let options = TransactionOptions::new().set_version(Some(0));
+
+        let tx = client.generate_transaction(options).unwrap();
+        assert_eq!(tx.get_valid_until_block(), 16 + 88);
+        assert_eq!(tx.get_nonce().len(), 32);
+
+        let options = options
+            .set_valid_blocks(Some(10))
+            .set_nonce(NonceSource::Fixed("replay-1"));
+        let tx = client.generate_transaction(options).unwrap();
+        assert_eq!(tx.get_valid_until_block(), 26);
+        assert_eq!(tx.get_nonce(), "replay-1");
+        let tx = client
+            .generate_transaction(options.set_valid_until_block(Some(100)))
+            .unwrap();
+        assert_eq!(tx.get_valid_until_block(), 100);
+
+        let path = ::std::env::temp_dir().join(format!("nonce-{}.json", Uuid::new_v4()));
+        let (alice, bob) = (Address::from(1), Address::from(2));
+        let nonces = [alice, alice, bob, alice]
+            .iter()
+            .map(|account| NonceSource::Counter(&path, *account).next_nonce().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(nonces, vec!["0", "1", "0", "2"]);
+        let tx = client
+            .generate_transaction(options.set_nonce(NonceSource::Counter(&path, bob)))
+            .unwrap();
+        assert_eq!(tx.get_nonce(), "1");
+        // A transaction which fails before it is built does not take a counter value
+        let mut failing = Client::new().set_transport(MemoryTransport::new());
+        assert!(failing
+            .generate_transaction(options.set_nonce(NonceSource::Counter(&path, bob)))
+            .is_err());
+        let tx = client
+            .generate_transaction(options.set_nonce(NonceSource::Counter(&path, bob)))
+            .unwrap();
+        assert_eq!(tx.get_nonce(), "2");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_auto_quota() {
+        let transport = |account_limit: u64| {
+            MemoryTransport::new()
+                .on_result("blockNumber", serde_json::from_str(r#""0x10""#).unwrap())
+                .on_result(
+                    "estimateQuota",
+                    serde_json::from_str(r#""0x5208""#).unwrap(),
+                )
+                .on_result(
+                    "call",
+                    serde_json::from_str(&format!(r#""0x{:064x}""#, account_limit)).unwrap(),
+                )
+        };
+        let mut client = Client::new().set_transport(transport(10_000_000));
+        let auto_quota = AutoQuota::new().set_sender(Some(Address::from(1)));
+        let options = TransactionOptions::new()
+            .set_version(Some(1))
+            .set_address("0x0000000000000000000000000000000000000002");
+        client.set_chain_id(U256::from(1));
+
+        let tx = client
+            .generate_transaction(options.set_auto_quota(Some(auto_quota)))
+            .unwrap();
+        assert_eq!(tx.get_quota(), 25_200);
+        match client
+            .generate_transaction(options.set_auto_quota(Some(auto_quota.set_cap(Some(22_000)))))
+        {
+            Err(ToolError::QuotaExceeded {
+                estimation: 25_200,
+                limit: 22_000,
+                name: "quota cap",
+            }) => {}
+            other => panic!("expect the multiplied quota over the cap, got {:?}", other),
+        }
+        let tx = client
+            .generate_transaction(
+                options.set_auto_quota(Some(auto_quota.set_cap(Some(22_000)).set_clamp(true))),
+            )
+            .unwrap();
+        assert_eq!(tx.get_quota(), 22_000);
+        let tx = client
+            .generate_transaction(
+                options
+                    .set_quota(Some(30_000))
+                    .set_auto_quota(Some(auto_quota)),
+            )
+            .unwrap();
+        assert_eq!(tx.get_quota(), 30_000);
+        match client.generate_transaction(
+            options.set_auto_quota(Some(auto_quota.set_cap(Some(20_000)).set_clamp(true))),
+        ) {
+            Err(ToolError::QuotaExceeded {
+                estimation: 21_000,
+                limit: 20_000,
+                name: "quota cap",
+            }) => {}
+            other => panic!("expect the quota over the cap, got {:?}", other),
+        }
+
+        let mut client = Client::new().set_transport(transport(20_000));
+        client.set_chain_id(U256::from(1));
+        match client.generate_transaction(options.set_auto_quota(Some(auto_quota))) {
+            Err(ToolError::QuotaExceeded {
+                name: "account quota limit",
+                ..
+            }) => {}
+            other => panic!("expect the quota over the account limit, got {:?}", other),
+        }
+        assert!(client
+            .generate_transaction(options.set_auto_quota(Some(AutoQuota::new())))
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_offline() {
+        let options = TransactionOptions::new()
+            .set_address("0x0000000000000000000000000000000000000002")
+            .set_value(Some(U256::from(10)))
+            .set_nonce(NonceSource::Fixed("cold-1"));
+        match options.build_offline(U256::from(1)) {
+            Err(ToolError::InvalidInput(message)) => assert!(message.contains("version")),
+            other => panic!("expect a missing version, got {:?}", other),
+        }
+
+        let options = options
+            .set_version(Some(1))
+            .set_valid_until_block(Some(100));
+        let tx = options.build_offline(U256::from(7)).unwrap();
+        assert_eq!(tx.get_valid_until_block(), 100);
+        assert_eq!(tx.get_quota(), 10_000_000);
+        assert_eq!(tx.get_nonce(), "cold-1");
+        assert_eq!(tx.get_to_v1(), &Address::from(2)[..]);
+        assert_eq!(U256::from(tx.get_chain_id_v1()), U256::from(7));
+        assert_eq!(U256::from(tx.get_value()), U256::from(10));
+
+        let tx = options
+            .set_version(Some(0))
+            .build_offline(U256::from(3))
+            .unwrap();
+        assert_eq!(tx.get_chain_id(), 3);
+        assert!(options
+            .set_version(Some(0))
+            .build_offline(U256::from(u64::MAX))
+            .is_err());
+    }
+}
@@ -0,0 +1,535 @@
+use futures::Future;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+use types::{H256, U256};
+
+use crate::client::async_client::{
+    AsyncClient, AsyncClientExt, ResponseFuture, GET_LOGS, NEW_FILTER,
+};
+use crate::client::basic::Client;
+use crate::error::ToolError;
+use crate::models::{
+    bytes, quantity, Block, Filter, FilterChanges, Log, MetaData, PeersInfo, Receipt,
+    RpcTransaction, StateProof,
+};
+use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue};
+
+/// Deserialize the result of the response, a jsonrpc error is an error
+pub fn decode<T: DeserializeOwned>(response: JsonRpcResponse) -> Result<T, ToolError> {
+    if let Some(error) = response.error() {
+        return Err(ToolError::JsonRpc(error));
+    }
+    let value = serde_json::to_value(response.result()).map_err(ToolError::SerdeJson)?;
+    serde_json::from_value(value).map_err(ToolError::SerdeJson)
+}
+
+fn decode_quantity(response: JsonRpcResponse) -> Result<u64, ToolError> {
+    quantity::deserialize(decode::<Value>(response)?).map_err(ToolError::SerdeJson)
+}
+
+fn decode_bytes(response: JsonRpcResponse) -> Result<Vec<u8>, ToolError> {
+    bytes::deserialize(decode::<Value>(response)?).map_err(ToolError::SerdeJson)
+}
+
+fn typed<T, F>(
+    response: ResponseFuture<JsonRpcResponse, ToolError>,
+    decode: F,
+) -> ResponseFuture<T, ToolError>
+where
+    T: Send + 'static,
+    F: FnOnce(JsonRpcResponse) -> Result<T, ToolError> + Send + 'static,
+{
+    Box::new(response.and_then(decode))
+}
+
+/// The filter as the only param of the method
+fn filter_params(method: &str, filter: &Filter) -> JsonRpcParams {
+    let filter =
+        serde_json::from_value::<ParamsValue>(serde_json::to_value(filter).unwrap()).unwrap();
+    JsonRpcParams::new()
+        .insert("method", ParamsValue::String(String::from(method)))
+        .insert("params", ParamsValue::List(vec![filter]))
+}
+
+/// Asynchronous jsonrpc calls with typed results, hex quantities are decoded
+///
+/// A jsonrpc error resolves to `ToolError::JsonRpc`, a missing block, transaction
+/// or receipt resolves to `None`.
+pub trait AsyncTypedClientExt {
+    /// peerCount
+    fn peer_count(&self) -> ResponseFuture<u64, ToolError>;
+    /// peersInfo
+    fn peers_info(&self) -> ResponseFuture<PeersInfo, ToolError>;
+    /// blockNumber
+    fn block_number(&self) -> ResponseFuture<u64, ToolError>;
+    /// getBlockByHash
+    fn block_by_hash(
+        &self,
+        hash: &str,
+        transaction_info: bool,
+    ) -> ResponseFuture<Option<Block>, ToolError>;
+    /// getBlockByNumber
+    fn block_by_number(
+        &self,
+        height: &str,
+        transaction_info: bool,
+    ) -> ResponseFuture<Option<Block>, ToolError>;
+    /// getTransaction
+    fn transaction(&self, hash: &str) -> ResponseFuture<Option<RpcTransaction>, ToolError>;
+    /// getTransactionReceipt
+    fn transaction_receipt(&self, hash: &str) -> ResponseFuture<Option<Receipt>, ToolError>;
+    /// getTransactionCount
+    fn transaction_count(&self, address: &str, height: &str) -> ResponseFuture<u64, ToolError>;
+    /// getBalance
+    fn balance(&self, address: &str, height: &str) -> ResponseFuture<U256, ToolError>;
+    /// getCode
+    fn code(&self, address: &str, height: &str) -> ResponseFuture<Vec<u8>, ToolError>;
+    /// getAbi
+    fn abi(&self, address: &str, height: &str) -> ResponseFuture<Vec<u8>, ToolError>;
+    /// call: the output of the call
+    fn call_output(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<Vec<u8>, ToolError>;
+    /// estimateQuota
+    fn quota_estimate(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<U256, ToolError>;
+    /// getStorageAt
+    fn storage_at(&self, address: &str, key: &str, height: &str)
+        -> ResponseFuture<H256, ToolError>;
+    /// getLogs
+    fn logs(&self, filter: &Filter) -> ResponseFuture<Vec<Log>, ToolError>;
+    /// newFilter: the filter id
+    fn install_log_filter(&self, filter: &Filter) -> ResponseFuture<U256, ToolError>;
+    /// newBlockFilter: the filter id
+    fn install_block_filter(&self) -> ResponseFuture<U256, ToolError>;
+    /// uninstallFilter
+    fn remove_filter(&self, filter_id: &str) -> ResponseFuture<bool, ToolError>;
+    /// getFilterChanges
+    fn filter_changes(&self, filter_id: &str) -> ResponseFuture<FilterChanges, ToolError>;
+    /// getFilterLogs
+    fn filter_logs(&self, filter_id: &str) -> ResponseFuture<Vec<Log>, ToolError>;
+    /// getMetaData
+    fn metadata(&self, height: &str) -> ResponseFuture<MetaData, ToolError>;
+    /// getStateProof
+    fn state_proof(
+        &self,
+        address: &str,
+        key: &str,
+        height: &str,
+    ) -> ResponseFuture<StateProof, ToolError>;
+}
+
+impl AsyncTypedClientExt for AsyncClient {
+    fn peer_count(&self) -> ResponseFuture<u64, ToolError> {
+        typed(self.get_peer_count(), decode_quantity)
+    }
+
+    fn peers_info(&self) -> ResponseFuture<PeersInfo, ToolError> {
+        typed(self.get_peers_info(), decode)
+    }
+
+    fn block_number(&self) -> ResponseFuture<u64, ToolError> {
+        typed(self.get_block_number(), decode_quantity)
+    }
+
+    fn block_by_hash(
+        &self,
+        hash: &str,
+        transaction_info: bool,
+    ) -> ResponseFuture<Option<Block>, ToolError> {
+        typed(self.get_block_by_hash(hash, transaction_info), decode)
+    }
+
+    fn block_by_number(
+        &self,
+        height: &str,
+        transaction_info: bool,
+    ) -> ResponseFuture<Option<Block>, ToolError> {
+        typed(self.get_block_by_number(height, transaction_info), decode)
+    }
+
+    fn transaction(&self, hash: &str) -> ResponseFuture<Option<RpcTransaction>, ToolError> {
+        typed(self.get_transaction(hash), decode)
+    }
+
+    fn transaction_receipt(&self, hash: &str) -> ResponseFuture<Option<Receipt>, ToolError> {
+        typed(self.get_transaction_receipt(hash), decode)
+    }
+
+    fn transaction_count(&self, address: &str, height: &str) -> ResponseFuture<u64, ToolError> {
+        typed(self.get_transaction_count(address, height), decode_quantity)
+    }
+
+    fn balance(&self, address: &str, height: &str) -> ResponseFuture<U256, ToolError> {
+        typed(self.get_balance(address, height), decode)
+    }
+
+    fn code(&self, address: &str, height: &str) -> ResponseFuture<Vec<u8>, ToolError> {
+        typed(self.get_code(address, height), decode_bytes)
+    }
+
+    fn abi(&self, address: &str, height: &str) -> ResponseFuture<Vec<u8>, ToolError> {
+        typed(self.get_abi(address, height), decode_bytes)
+    }
+
+    fn call_output(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<Vec<u8>, ToolError> {
+        typed(self.call(from, to, data, height), decode_bytes)
+    }
+
+    fn quota_estimate(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> ResponseFuture<U256, ToolError> {
+        typed(self.estimate_quota(from, to, data, height), decode)
+    }
+
+    fn storage_at(
+        &self,
+        address: &str,
+        key: &str,
+        height: &str,
+    ) -> ResponseFuture<H256, ToolError> {
+        typed(self.get_storage_at(address, key, height), decode)
+    }
+
+    fn logs(&self, filter: &Filter) -> ResponseFuture<Vec<Log>, ToolError> {
+        typed(self.send_single(filter_params(GET_LOGS, filter)), decode)
+    }
+
+    fn install_log_filter(&self, filter: &Filter) -> ResponseFuture<U256, ToolError> {
+        typed(self.send_single(filter_params(NEW_FILTER, filter)), decode)
+    }
+
+    fn install_block_filter(&self) -> ResponseFuture<U256, ToolError> {
+        typed(self.new_block_filter(), decode)
+    }
+
+    fn remove_filter(&self, filter_id: &str) -> ResponseFuture<bool, ToolError> {
+        typed(self.uninstall_filter(filter_id), decode)
+    }
+
+    fn filter_changes(&self, filter_id: &str) -> ResponseFuture<FilterChanges, ToolError> {
+        typed(self.get_filter_changes(filter_id), decode)
+    }
+
+    fn filter_logs(&self, filter_id: &str) -> ResponseFuture<Vec<Log>, ToolError> {
+        typed(self.get_filter_logs(filter_id), decode)
+    }
+
+    fn metadata(&self, height: &str) -> ResponseFuture<MetaData, ToolError> {
+        typed(self.get_metadata(height), decode)
+    }
+
+    fn state_proof(
+        &self,
+        address: &str,
+        key: &str,
+        height: &str,
+    ) -> ResponseFuture<StateProof, ToolError> {
+        typed(self.get_state_proof(address, key, height), |response| {
+            StateProof::from_rlp(&decode_bytes(response)?)
+        })
+    }
+}
+
+/// Jsonrpc calls with typed results, the blocking version of
+/// [`AsyncTypedClientExt`](../typed/trait.AsyncTypedClientExt.html)
+pub trait TypedClientExt {
+    /// peerCount
+    fn peer_count(&self) -> Result<u64, ToolError>;
+    /// peersInfo
+    fn peers_info(&self) -> Result<PeersInfo, ToolError>;
+    /// blockNumber
+    fn block_number(&self) -> Result<u64, ToolError>;
+    /// getBlockByHash
+    fn block_by_hash(&self, hash: &str, transaction_info: bool)
+        -> Result<Option<Block>, ToolError>;
+    /// getBlockByNumber
+    fn block_by_number(
+        &self,
+        height: &str,
+        transaction_info: bool,
+    ) -> Result<Option<Block>, ToolError>;
+    /// getTransaction
+    fn transaction(&self, hash: &str) -> Result<Option<RpcTransaction>, ToolError>;
+    /// getTransactionReceipt
+    fn transaction_receipt(&self, hash: &str) -> Result<Option<Receipt>, ToolError>;
+    /// getTransactionCount
+    fn transaction_count(&self, address: &str, height: &str) -> Result<u64, ToolError>;
+    /// getBalance
+    fn balance(&self, address: &str, height: &str) -> Result<U256, ToolError>;
+    /// getCode
+    fn code(&self, address: &str, height: &str) -> Result<Vec<u8>, ToolError>;
+    /// getAbi
+    fn abi(&self, address: &str, height: &str) -> Result<Vec<u8>, ToolError>;
+    /// call: the output of the call
+    fn call_output(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> Result<Vec<u8>, ToolError>;
+    /// estimateQuota
+    fn quota_estimate(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> Result<U256, ToolError>;
+    /// getStorageAt
+    fn storage_at(&self, address: &str, key: &str, height: &str) -> Result<H256, ToolError>;
+    /// getLogs
+    fn logs(&self, filter: &Filter) -> Result<Vec<Log>, ToolError>;
+    /// newFilter: the filter id
+    fn install_log_filter(&self, filter: &Filter) -> Result<U256, ToolError>;
+    /// newBlockFilter: the filter id
+    fn install_block_filter(&self) -> Result<U256, ToolError>;
+    /// uninstallFilter
+    fn remove_filter(&self, filter_id: &str) -> Result<bool, ToolError>;
+    /// getFilterChanges
+    fn filter_changes(&self, filter_id: &str) -> Result<FilterChanges, ToolError>;
+    /// getFilterLogs
+    fn filter_logs(&self, filter_id: &str) -> Result<Vec<Log>, ToolError>;
+    /// getMetaData
+    fn metadata(&self, height: &str) -> Result<MetaData, ToolError>;
+    /// getStateProof
+    fn state_proof(&self, address: &str, key: &str, height: &str) -> Result<StateProof, ToolError>;
+}
+
+impl TypedClientExt for Client {
+    fn peer_count(&self) -> Result<u64, ToolError> {
+        self.run(self.async_client().peer_count())
+    }
+
+    fn peers_info(&self) -> Result<PeersInfo, ToolError> {
+        self.run(self.async_client().peers_info())
+    }
+
+    fn block_number(&self) -> Result<u64, ToolError> {
+        self.run(self.async_client().block_number())
+    }
+
+    fn block_by_hash(
+        &self,
+        hash: &str,
+        transaction_info: bool,
+    ) -> Result<Option<Block>, ToolError> {
+        self.run(self.async_client().block_by_hash(hash, transaction_info))
+    }
+
+    fn block_by_number(
+        &self,
+        height: &str,
+        transaction_info: bool,
+    ) -> Result<Option<Block>, ToolError> {
+        self.run(
+            self.async_client()
+                .block_by_number(height, transaction_info),
+        )
+    }
+
+    fn transaction(&self, hash: &str) -> Result<Option<RpcTransaction>, ToolError> {
+        self.run(self.async_client().transaction(hash))
+    }
+
+    fn transaction_receipt(&self, hash: &str) -> Result<Option<Receipt>, ToolError> {
+        self.run(self.async_client().transaction_receipt(hash))
+    }
+
+    fn transaction_count(&self, address: &str, height: &str) -> Result<u64, ToolError> {
+        self.run(self.async_client().transaction_count(address, height))
+    }
+
+    fn balance(&self, address: &str, height: &str) -> Result<U256, ToolError> {
+        self.run(self.async_client().balance(address, height))
+    }
+
+    fn code(&self, address: &str, height: &str) -> Result<Vec<u8>, ToolError> {
+        self.run(self.async_client().code(address, height))
+    }
+
+    fn abi(&self, address: &str, height: &str) -> Result<Vec<u8>, ToolError> {
+        self.run(self.async_client().abi(address, height))
+    }
+
+    fn call_output(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> Result<Vec<u8>, ToolError> {
+        self.run(self.async_client().call_output(from, to, data, height))
+    }
+
+    fn quota_estimate(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        data: Option<&str>,
+        height: &str,
+    ) -> Result<U256, ToolError> {
+        self.run(self.async_client().quota_estimate(from, to, data, height))
+    }
+
+    fn storage_at(&self, address: &str, key: &str, height: &str) -> Result<H256, ToolError> {
+        self.run(self.async_client().storage_at(address, key, height))
+    }
+
+    fn logs(&self, filter: &Filter) -> Result<Vec<Log>, ToolError> {
+        self.run(self.async_client().logs(filter))
+    }
+
+    fn install_log_filter(&self, filter: &Filter) -> Result<U256, ToolError> {
+        self.run(self.async_client().install_log_filter(filter))
+    }
+
+    fn install_block_filter(&self) -> Result<U256, ToolError> {
+        self.run(self.async_client().install_block_filter())
+    }
+
+    fn remove_filter(&self, filter_id: &str) -> Result<bool, ToolError> {
+        self.run(self.async_client().remove_filter(filter_id))
+    }
+
+    fn filter_changes(&self, filter_id: &str) -> Result<FilterChanges, ToolError> {
+        self.run(self.async_client().filter_changes(filter_id))
+    }
+
+    fn filter_logs(&self, filter_id: &str) -> Result<Vec<Log>, ToolError> {
+        self.run(self.async_client().filter_logs(filter_id))
+    }
+
+    fn metadata(&self, height: &str) -> Result<MetaData, ToolError> {
+        self.run(self.async_client().metadata(height))
+    }
+
+    fn state_proof(&self, address: &str, key: &str, height: &str) -> Result<StateProof, ToolError> {
+        self.run(self.async_client().state_proof(address, key, height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+    use crate::models::BlockTransaction;
+    use crate::rpctypes::{ErrorResponse, ResponseValue};
+    use std::sync::Arc;
+
+    fn result(json: &str) -> ResponseValue {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_typed_client() {
+        let transport = Arc::new(
+            MemoryTransport::new()
+                .on_result("blockNumber", result(r#""0x1b""#))
+                .on_result(
+                    "getBlockByNumber",
+                    result(
+                        r#"{
+                            "version": 1,
+                            "hash": "0x0ec56ba8a1b8c2bb3a7b1c4d2f4fa44b4a1b4a6e1a7e53b4f3bd6c3e4a3a8b4c",
+                            "header": {
+                                "timestamp": 1541739000000,
+                                "prevHash": "0x1c9a3a0b8d4e2f3c5b6a7e8d9c0b1a2f3e4d5c6b7a8e9f0a1b2c3d4e5f6a7b8c",
+                                "number": "0x1b",
+                                "stateRoot": "0x2d1e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e",
+                                "transactionsRoot": "0x3e2f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f",
+                                "receiptsRoot": "0x4f3a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a",
+                                "quotaUsed": "0x5208",
+                                "proof": {"Bft": {"height": 26, "round": 0}},
+                                "proposer": "0x31042d75d3a0ad4c7a88f2ff0f1ca5ad0b2c8dca"
+                            },
+                            "body": {
+                                "transactions": [
+                                    "0x5a4b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b"
+                                ]
+                            }
+                        }"#,
+                    ),
+                )
+                .on_result(
+                    "getTransactionReceipt",
+                    result(
+                        r#"{
+                            "transactionHash": "0x5a4b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b",
+                            "transactionIndex": "0x0",
+                            "blockHash": "0x0ec56ba8a1b8c2bb3a7b1c4d2f4fa44b4a1b4a6e1a7e53b4f3bd6c3e4a3a8b4c",
+                            "blockNumber": "0x1b",
+                            "cumulativeQuotaUsed": "0x5208",
+                            "quotaUsed": "0x5208",
+                            "contractAddress": null,
+                            "logs": [{
+                                "address": "0xffffffffffffffffffffffffffffffffff020000",
+                                "topics": ["0x8fb1356be6b2a4e49ee94447eb9dcb8783f51c41dcddfe7919f945017d163bf3"],
+                                "data": "0x0100",
+                                "blockNumber": "0x1b",
+                                "logIndex": "0x0"
+                            }],
+                            "root": null,
+                            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                            "errorMessage": null
+                        }"#,
+                    ),
+                )
+                .on_result("getTransaction", ResponseValue::Singe(ParamsValue::Null))
+                .on_result("getBalance", result(r#""0x2386f26fc10000""#))
+                .on_result("getCode", result(r#""0x6080""#))
+                .on("getLogs", |_| Err(ErrorResponse::new(-32602, "Invalid params"))),
+        );
+        let client = Client::new().set_transport(transport.clone());
+
+        assert_eq!(client.block_number().unwrap(), 27);
+
+        let block = client.block_by_number("0x1b", false).unwrap().unwrap();
+        assert_eq!(block.header.number, 27);
+        assert_eq!(block.header.quota_used, U256::from(21000));
+        match block.body.transactions[0] {
+            BlockTransaction::Hash(_) => {}
+            _ => panic!("expect a transaction hash"),
+        }
+
+        let receipt = client.transaction_receipt("0x0").unwrap().unwrap();
+        assert_eq!(receipt.block_hash, block.hash);
+        assert_eq!(receipt.logs[0].data, vec![1, 0]);
+        assert_eq!(receipt.logs[0].block_number, Some(27));
+        assert!(receipt.contract_address.is_none());
+
+        assert!(client.transaction("0x0").unwrap().is_none());
+        assert_eq!(
+            client.balance("0x0", "latest").unwrap(),
+            U256::from(10_000_000_000_000_000u64)
+        );
+        assert_eq!(client.code("0x0", "latest").unwrap(), vec![0x60, 0x80]);
+
+        match client.logs(&Filter::default()) {
+            Err(ToolError::JsonRpc(error)) => assert_eq!(error.code(), -32602),
+            other => panic!("expect a jsonrpc error, got {:?}", other),
+        }
+        let filter = transport.requests().pop().unwrap();
+        assert_eq!(format!("{}", filter.get("params").unwrap()), "[{}]");
+    }
+}
@@ -0,0 +1,294 @@
+use std::time::{Duration, Instant};
+
+use futures::future::{self, Loop};
+use futures::Future;
+use tokio::timer::Delay;
+
+use crate::client::async_client::{AsyncClient, ResponseFuture, VALID_BLOCKS};
+use crate::client::basic::Client;
+use crate::client::typed::AsyncTypedClientExt;
+use crate::error::ToolError;
+use crate::models::Receipt;
+use crate::rpctypes::{JsonRpcResponse, ParamsValue, ResponseValue};
+
+/// How to wait for the receipt of a sent transaction
+///
+/// Default is no confirmation, a poll every second, until the `valid_until_block`
+/// of a transaction generated at the current height.
+#[derive(Clone, Debug)]
+pub struct WaitOptions {
+    valid_until_block: Option<u64>,
+    confirmations: u64,
+    poll_interval: Duration,
+}
+
+impl WaitOptions {
+    /// Create the default options
+    pub fn new() -> Self {
+        WaitOptions {
+            valid_until_block: None,
+            confirmations: 0,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Set the `valid_until_block` of the transaction, the wait fails after it
+    ///
+    /// None is the current height of the first poll plus the blocks a generated
+    /// transaction stays valid for.
+    pub fn set_valid_until_block(mut self, height: Option<u64>) -> Self {
+        self.valid_until_block = height;
+        self
+    }
+
+    /// Get valid until block
+    pub fn valid_until_block(&self) -> Option<u64> {
+        self.valid_until_block
+    }
+
+    /// Set the number of blocks on top of the block of the receipt
+    pub fn set_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Get confirmations
+    pub fn confirmations(&self) -> u64 {
+        self.confirmations
+    }
+
+    /// Set the interval between two polls
+    pub fn set_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Get poll interval
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        WaitOptions::new()
+    }
+}
+
+/// The hash of the `sendRawTransaction` response
+pub fn transaction_hash(response: &JsonRpcResponse) -> Result<String, ToolError> {
+    if let Some(error) = response.error() {
+        return Err(ToolError::JsonRpc(error));
+    }
+    match response.result() {
+        Some(ResponseValue::Map(mut map)) => match map.remove("hash") {
+            Some(ParamsValue::String(hash)) => Ok(hash),
+            _ => Err(ToolError::Transport(
+                "No transaction hash in the response".to_string(),
+            )),
+        },
+        _ => Err(ToolError::Transport(
+            "No transaction hash in the response".to_string(),
+        )),
+    }
+}
+
+type Poll = Loop<Receipt, (Option<u64>, Option<Receipt>)>;
+
+/// One poll: the height first, a receipt missing after it is missing from its block
+fn poll(
+    client: &AsyncClient,
+    hash: &str,
+    until: Option<u64>,
+    receipt: Option<Receipt>,
+    options: &WaitOptions,
+) -> ResponseFuture<Poll, ToolError> {
+    let client = client.clone();
+    let hash = hash.to_string();
+    let options = options.clone();
+    Box::new(client.block_number().and_then(move |height| {
+        let until = until.unwrap_or(height + VALID_BLOCKS);
+        let receipt: ResponseFuture<Option<Receipt>, ToolError> = match receipt {
+            Some(receipt) => Box::new(future::ok(Some(receipt))),
+            None => client.transaction_receipt(&hash),
+        };
+        receipt.and_then(move |receipt| -> ResponseFuture<Poll, ToolError> {
+            match receipt {
+                Some(Receipt {
+                    error_message: Some(message),
+                    ..
+                }) => Box::new(future::err(ToolError::TransactionFailed(message))),
+                Some(receipt) if height >= receipt.block_number + options.confirmations() => {
+                    Box::new(future::ok(Loop::Break(receipt)))
+                }
+                None if height >= until => {
+                    Box::new(future::err(ToolError::TransactionExpired(until)))
+                }
+                receipt => Box::new(
+                    Delay::new(Instant::now() + options.poll_interval())
+                        .map_err(|err| ToolError::Transport(format!("Poll timer error: {}", err)))
+                        .map(move |_| Loop::Continue((Some(until), receipt))),
+                ),
+            }
+        })
+    }))
+}
+
+impl AsyncClient {
+    /// Poll until the receipt of the transaction is confirmed
+    ///
+    /// Resolve to `ToolError::TransactionFailed` when the receipt has an error message,
+    /// to `ToolError::TransactionExpired` when no block up to `valid_until_block`
+    /// includes the transaction.
+    pub fn wait_for_receipt(
+        &self,
+        hash: &str,
+        options: &WaitOptions,
+    ) -> ResponseFuture<Receipt, ToolError> {
+        let client = self.clone();
+        let hash = hash.to_string();
+        let options = options.clone();
+        Box::new(future::loop_fn(
+            (options.valid_until_block(), None),
+            move |(until, receipt)| poll(&client, &hash, until, receipt, &options),
+        ))
+    }
+
+    /// Wait for the receipt of the transaction sent with the response
+    pub fn wait_for_transaction(
+        &self,
+        response: &JsonRpcResponse,
+        options: &WaitOptions,
+    ) -> ResponseFuture<Receipt, ToolError> {
+        match transaction_hash(response) {
+            Ok(hash) => self.wait_for_receipt(&hash, options),
+            Err(err) => Box::new(future::err(err)),
+        }
+    }
+}
+
+impl Client {
+    /// Poll until the receipt of the transaction is confirmed
+    pub fn wait_for_receipt(
+        &self,
+        hash: &str,
+        options: &WaitOptions,
+    ) -> Result<Receipt, ToolError> {
+        self.run(self.async_client().wait_for_receipt(hash, options))
+    }
+
+    /// Wait for the receipt of the transaction sent with the response
+    pub fn wait_for_transaction(
+        &self,
+        response: &JsonRpcResponse,
+        options: &WaitOptions,
+    ) -> Result<Receipt, ToolError> {
+        self.run(self.async_client().wait_for_transaction(response, options))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A chain one block higher at each `blockNumber`, the transaction is in block 3
+    fn chain(error_message: Option<&str>) -> MemoryTransport {
+        let height = Arc::new(AtomicUsize::new(0));
+        let current = Arc::clone(&height);
+        let receipt: ResponseValue = serde_json::from_value(json!({
+            "transactionHash": format!("0x{}", "11".repeat(32)),
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "22".repeat(32)),
+            "blockNumber": "0x3",
+            "cumulativeQuotaUsed": "0x5208",
+            "quotaUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "root": null,
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "errorMessage": error_message,
+        }))
+        .unwrap();
+        MemoryTransport::new()
+            .on("blockNumber", move |_| {
+                let height = height.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(ResponseValue::Singe(ParamsValue::String(format!(
+                    "{:#x}",
+                    height
+                ))))
+            })
+            .on("getTransactionReceipt", move |_| {
+                if current.load(Ordering::SeqCst) >= 3 {
+                    Ok(receipt.clone())
+                } else {
+                    Ok(ResponseValue::Singe(ParamsValue::Null))
+                }
+            })
+    }
+
+    fn options() -> WaitOptions {
+        WaitOptions::new().set_poll_interval(Duration::from_millis(10))
+    }
+
+    /// The `sendRawTransaction` response of the transaction
+    fn sent() -> JsonRpcResponse {
+        serde_json::from_str(&format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":{{"hash":"0x{}","status":"OK"}}}}"#,
+            "11".repeat(32)
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_transaction_hash() {
+        assert_eq!(
+            transaction_hash(&sent()).unwrap(),
+            format!("0x{}", "11".repeat(32))
+        );
+        let response = JsonRpcResponse::from_result(1, ResponseValue::Singe(ParamsValue::Null));
+        match transaction_hash(&response) {
+            Err(ToolError::Transport(_)) => {}
+            other => panic!("expect no hash, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wait_for_confirmations() {
+        let transport = Arc::new(chain(None));
+        let client = Client::new().set_transport(transport.clone());
+        let receipt = client
+            .wait_for_transaction(&sent(), &options().set_confirmations(2))
+            .unwrap();
+        assert_eq!(receipt.block_number, 3);
+        // The receipt at height 3, confirmed at height 5
+        let polls = transport
+            .requests()
+            .iter()
+            .filter(|param| format!("{}", param.get("method").unwrap()) == "\"blockNumber\"")
+            .count();
+        assert_eq!(polls, 5);
+    }
+
+    #[test]
+    fn test_wait_for_failed_transaction() {
+        let client = Client::new().set_transport(chain(Some("Out of quota.")));
+        match client.wait_for_transaction(&sent(), &options()) {
+            Err(ToolError::TransactionFailed(message)) => assert_eq!(message, "Out of quota."),
+            other => panic!("expect a failed transaction, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wait_for_expired_transaction() {
+        let client = Client::new().set_transport(chain(None));
+        let options = options().set_valid_until_block(Some(2));
+        match client.wait_for_transaction(&sent(), &options) {
+            Err(ToolError::TransactionExpired(2)) => {}
+            other => panic!("expect an expired transaction, got {:?}", other),
+        }
+    }
+}
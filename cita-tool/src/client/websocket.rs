@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::sync::{mpsc, oneshot};
+use futures::{future, future::join_all, stream, Future, Poll, Sink, Stream};
+use hyper::Uri;
+use serde_json::{self, Value};
+use tokio;
+use tokio::timer::Interval;
+use websocket::r#async::{Client as WsClient, TcpStream};
+use websocket::{ClientBuilder, OwnedMessage};
+
+use crate::client::async_client::{
+    filter_object, ResponseFuture, GET_BLOCK_BY_HASH, GET_FILTER_CHANGES, NEW_BLOCK_FILTER,
+    NEW_FILTER, UNINSTALL_FILTER,
+};
+use crate::client::transport::Transport;
+use crate::error::ToolError;
+use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue};
+
+type Reply = oneshot::Sender<Result<JsonRpcResponse, ToolError>>;
+
+struct Command {
+    param: JsonRpcParams,
+    reply: Reply,
+}
+
+impl Command {
+    fn fail(self, err: &str) {
+        let _ = self.reply.send(Err(ToolError::Transport(err.to_string())));
+    }
+}
+
+enum Event {
+    Command(Command),
+    Message(OwnedMessage),
+    Closed,
+}
+
+/// Jsonrpc over one websocket connection, with polled subscriptions
+///
+/// The connection is opened by the first request, on the runtime which polls it,
+/// and opened again by the next request after it is closed.
+/// The url of the client is ignored, the requests go to the url of the transport.
+/// Only `ws://` is supported.
+///
+/// Subscriptions install a filter with `newBlockFilter` or `newFilter`, poll
+/// `getFilterChanges` over the connection, and `uninstallFilter` when the
+/// `Subscription` is dropped.
+pub struct WsTransport {
+    url: String,
+    poll_interval: Duration,
+    commands: Mutex<Option<mpsc::UnboundedSender<Command>>>,
+}
+
+impl WsTransport {
+    /// Create a websocket transport to the url, polling subscriptions every second
+    pub fn new(url: &str) -> Self {
+        WsTransport {
+            url: url.to_string(),
+            poll_interval: Duration::from_secs(1),
+            commands: Mutex::new(None),
+        }
+    }
+
+    /// Set the interval between two `getFilterChanges` of a subscription
+    pub fn set_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Get url
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get poll interval
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// Subscribe to the new blocks, without their transactions
+    pub fn subscribe_new_heads(&self) -> ResponseFuture<Subscription, ToolError> {
+        self.subscribe(request(NEW_BLOCK_FILTER, Vec::new()), true)
+    }
+
+    /// Subscribe to the new logs, filtered by topic and address like `new_filter`
+    pub fn subscribe_logs(
+        &self,
+        topic: Option<Vec<&str>>,
+        address: Option<Vec<&str>>,
+    ) -> ResponseFuture<Subscription, ToolError> {
+        let filter = filter_object(topic, address, None, None);
+        self.subscribe(request(NEW_FILTER, vec![filter]), false)
+    }
+
+    /// Install the filter, then poll its changes; the hashes of a block filter are
+    /// fetched with `getBlockByHash`
+    fn subscribe(
+        &self,
+        filter: JsonRpcParams,
+        blocks: bool,
+    ) -> ResponseFuture<Subscription, ToolError> {
+        let (commands, start) = self.channel();
+        let installed = call(&commands, filter);
+        let interval = self.poll_interval;
+
+        Box::new(start.and_then(|_| installed).and_then(move |id| match id {
+            ParamsValue::String(id) => Ok(Subscription::new(id, commands, interval, blocks)),
+            id => Err(ToolError::Transport(format!(
+                "Filter id expected, got {}",
+                serde_json::to_string(&id).unwrap()
+            ))),
+        }))
+    }
+
+    /// The sender to the connection, and a future which starts the connection if it's new
+    fn channel(
+        &self,
+    ) -> (
+        mpsc::UnboundedSender<Command>,
+        ResponseFuture<(), ToolError>,
+    ) {
+        let mut commands = self.commands.lock().unwrap();
+        if let Some(ref sender) = *commands {
+            if !sender.is_closed() {
+                return (sender.clone(), Box::new(future::ok(())));
+            }
+        }
+        let (sender, receiver) = mpsc::unbounded();
+        *commands = Some(sender.clone());
+        let connection = connect(&self.url, receiver);
+        (
+            sender,
+            Box::new(future::lazy(move || {
+                tokio::spawn(connection);
+                Ok(())
+            })),
+        )
+    }
+}
+
+impl Transport for WsTransport {
+    fn send(
+        &self,
+        _url: &Uri,
+        params: Vec<JsonRpcParams>,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        let (commands, start) = self.channel();
+        let replies = params
+            .into_iter()
+            .map(|param| send(&commands, param))
+            .collect::<Vec<_>>();
+        Box::new(start.and_then(|_| join_all(replies)))
+    }
+}
+
+/// Stream of the blocks or logs of a filter, uninstall the filter when dropped
+///
+/// A failed poll is an error item, the next poll is tried after the interval.
+pub struct Subscription {
+    id: String,
+    items: Box<dyn Stream<Item = ResponseValue, Error = ToolError> + Send>,
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl Subscription {
+    fn new(
+        id: String,
+        commands: mpsc::UnboundedSender<Command>,
+        interval: Duration,
+        blocks: bool,
+    ) -> Self {
+        let poll = commands.clone();
+        let changes = Interval::new(Instant::now(), interval)
+            .map_err(|err| ToolError::Transport(format!("Poll timer error: {}", err)))
+            .and_then({
+                let id = id.clone();
+                move |_| {
+                    call(
+                        &poll,
+                        request(GET_FILTER_CHANGES, vec![ParamsValue::String(id.clone())]),
+                    )
+                }
+            })
+            .map(|changes| match changes {
+                ParamsValue::List(changes) => stream::iter_ok(changes),
+                _ => stream::iter_ok(Vec::new()),
+            })
+            .flatten();
+
+        let items: Box<dyn Stream<Item = ResponseValue, Error = ToolError> + Send> = if blocks {
+            let fetch = commands.clone();
+            Box::new(
+                changes
+                    .and_then(move |hash| {
+                        call(
+                            &fetch,
+                            request(GET_BLOCK_BY_HASH, vec![hash, ParamsValue::Bool(false)]),
+                        )
+                    })
+                    .map(response_value),
+            )
+        } else {
+            Box::new(changes.map(response_value))
+        };
+
+        Subscription {
+            id,
+            items,
+            commands,
+        }
+    }
+
+    /// Get filter id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Stream for Subscription {
+    type Item = ResponseValue;
+    type Error = ToolError;
+
+    fn poll(&mut self) -> Poll<Option<ResponseValue>, ToolError> {
+        self.items.poll()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let param = request(UNINSTALL_FILTER, vec![ParamsValue::String(self.id.clone())]);
+        let _ = send(&self.commands, param);
+    }
+}
+
+fn request(method: &str, params: Vec<ParamsValue>) -> JsonRpcParams {
+    JsonRpcParams::new()
+        .insert("method", ParamsValue::String(String::from(method)))
+        .insert("params", ParamsValue::List(params))
+}
+
+fn response_value(value: ParamsValue) -> ResponseValue {
+    match value {
+        ParamsValue::Map(map) => ResponseValue::Map(map),
+        value => ResponseValue::Singe(value),
+    }
+}
+
+/// Send the param to the connection, resolve to its response
+fn send(
+    commands: &mpsc::UnboundedSender<Command>,
+    param: JsonRpcParams,
+) -> impl Future<Item = JsonRpcResponse, Error = ToolError> {
+    let (reply, response) = oneshot::channel();
+    let _ = commands.unbounded_send(Command { param, reply });
+    response.then(|response| match response {
+        Ok(response) => response,
+        Err(_) => Err(ToolError::Transport(
+            "Websocket connection closed".to_string(),
+        )),
+    })
+}
+
+/// The result of the param, a jsonrpc error is `ToolError::JsonRpc`
+fn call(
+    commands: &mpsc::UnboundedSender<Command>,
+    param: JsonRpcParams,
+) -> impl Future<Item = ParamsValue, Error = ToolError> {
+    send(commands, param).and_then(|response| {
+        if let Some(error) = response.error() {
+            return Err(ToolError::JsonRpc(error));
+        }
+        match response.result() {
+            Some(ResponseValue::Singe(value)) => Ok(value),
+            Some(ResponseValue::Map(map)) => Ok(ParamsValue::Map(map)),
+            None => Ok(ParamsValue::Null),
+        }
+    })
+}
+
+/// Open the connection and serve the commands until it's closed
+fn connect(
+    url: &str,
+    commands: mpsc::UnboundedReceiver<Command>,
+) -> impl Future<Item = (), Error = ()> + Send {
+    let connecting: ResponseFuture<WsClient<TcpStream>, String> = match ClientBuilder::new(url) {
+        Ok(builder) => Box::new(
+            builder
+                .async_connect_insecure()
+                .map(|(client, _)| client)
+                .map_err(|err| err.to_string()),
+        ),
+        Err(err) => Box::new(future::err(err.to_string())),
+    };
+
+    connecting.then(move |client| -> ResponseFuture<(), ()> {
+        match client {
+            Ok(client) => Box::new(serve(client, commands)),
+            Err(err) => {
+                let mut commands = commands;
+                commands.close();
+                Box::new(commands.for_each(move |command| {
+                    command.fail(&format!("Websocket error: {}", err));
+                    Ok(())
+                }))
+            }
+        }
+    })
+}
+
+fn serve(
+    client: WsClient<TcpStream>,
+    commands: mpsc::UnboundedReceiver<Command>,
+) -> impl Future<Item = (), Error = ()> + Send {
+    let (sink, messages) = client.split();
+    let (outgoing, receiver) = mpsc::unbounded();
+    tokio::spawn(receiver.forward(sink.sink_map_err(|_| ())).map(|_| ()));
+
+    let mut connection = Connection {
+        outgoing,
+        next_id: 0,
+        pending: HashMap::new(),
+    };
+    messages
+        .map(Event::Message)
+        .map_err(|_| ())
+        .chain(stream::once(Ok(Event::Closed)))
+        .select(commands.map(Event::Command))
+        .for_each(move |event| connection.handle(event))
+}
+
+struct Connection {
+    outgoing: mpsc::UnboundedSender<OwnedMessage>,
+    next_id: u64,
+    /// Connection id to the id of the caller and the waiting caller
+    pending: HashMap<u64, (Option<Value>, Reply)>,
+}
+
+impl Connection {
+    /// Err closes the connection
+    fn handle(&mut self, event: Event) -> Result<(), ()> {
+        match event {
+            Event::Command(command) => self.request(command),
+            Event::Message(OwnedMessage::Text(text)) => self.receive(&text),
+            Event::Message(OwnedMessage::Ping(data)) => self.write(OwnedMessage::Pong(data)),
+            Event::Message(OwnedMessage::Close(_)) | Event::Closed => Err(()),
+            Event::Message(_) => Ok(()),
+        }
+    }
+
+    /// Use an id of this connection, so the callers' ids can't collide
+    fn request(&mut self, command: Command) -> Result<(), ()> {
+        self.next_id += 1;
+        let Command { param, reply } = command;
+        let id = param.get("id").map(|id| serde_json::to_value(id).unwrap());
+        let param = param.insert("id", ParamsValue::Int(self.next_id));
+        self.pending.insert(self.next_id, (id, reply));
+        self.write(OwnedMessage::Text(serde_json::to_string(&param).unwrap()))
+    }
+
+    fn write(&self, message: OwnedMessage) -> Result<(), ()> {
+        self.outgoing.unbounded_send(message).map_err(|_| ())
+    }
+
+    fn receive(&mut self, text: &str) -> Result<(), ()> {
+        match serde_json::from_str::<Value>(text) {
+            Ok(Value::Array(values)) => values.into_iter().for_each(|value| self.dispatch(value)),
+            Ok(value) => self.dispatch(value),
+            Err(_) => {}
+        }
+        Ok(())
+    }
+
+    fn dispatch(&mut self, mut value: Value) {
+        let pending = value["id"].as_u64().and_then(|id| self.pending.remove(&id));
+        if let Some((id, reply)) = pending {
+            if let Some(id) = id {
+                value["id"] = id;
+            }
+            let response =
+                serde_json::from_value::<JsonRpcResponse>(value).map_err(ToolError::SerdeJson);
+            let _ = reply.send(response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::{Client, ClientExt};
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio::runtime::Runtime;
+    use websocket::r#async::{Handle, Server};
+
+    const BLOCK_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000011";
+    const ADDRESS: &str = "0xffffffffffffffffffffffffffffffffff020000";
+
+    /// Answer like a node with the block filter `0x1` and the log filter `0x2`
+    fn respond(request: &Value) -> Value {
+        let params = &request["params"];
+        let result = match request["method"].as_str().unwrap() {
+            "blockNumber" => json!("0x10"),
+            "newBlockFilter" => json!("0x1"),
+            "newFilter" => json!("0x2"),
+            "getFilterChanges" if params[0] == "0x1" => json!([BLOCK_HASH]),
+            "getFilterChanges" => json!([{"address": ADDRESS, "data": "0x"}]),
+            "getBlockByHash" => json!({"hash": params[0], "header": {"number": "0x11"}}),
+            "uninstallFilter" => json!(true),
+            _ => {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": {"code": -32601, "message": "Method not found"}
+                })
+            }
+        };
+        json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+    }
+
+    /// Serve `respond` over websocket, record the requests
+    fn stand_in(runtime: &mut Runtime) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let server = Server::bind("127.0.0.1:0", &Handle::default()).unwrap();
+        let url = format!("ws://{}", server.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        runtime.spawn(
+            server
+                .incoming()
+                .map_err(|_| ())
+                .for_each(move |(upgrade, _)| {
+                    let recorded = recorded.clone();
+                    tokio::spawn(
+                        upgrade
+                            .accept()
+                            .and_then(move |(client, _)| {
+                                let (sink, stream) = client.split();
+                                stream
+                                    .filter_map(move |message| match message {
+                                        OwnedMessage::Text(text) => {
+                                            let request =
+                                                serde_json::from_str::<Value>(&text).unwrap();
+                                            let response = respond(&request);
+                                            recorded.lock().unwrap().push(request);
+                                            Some(OwnedMessage::Text(response.to_string()))
+                                        }
+                                        _ => None,
+                                    })
+                                    .forward(sink)
+                            })
+                            .map(|_| ())
+                            .map_err(|_| ()),
+                    );
+                    Ok(())
+                }),
+        );
+        (url, requests)
+    }
+
+    #[test]
+    fn test_websocket_call() {
+        let mut runtime = Runtime::new().unwrap();
+        let (url, _) = stand_in(&mut runtime);
+        let client = Client::new().set_transport(Arc::new(WsTransport::new(&url)));
+        assert_eq!(client.get_current_height().unwrap(), 16);
+        let response = client.get_block_number().unwrap();
+        assert_eq!(response.id(), 2);
+    }
+
+    #[test]
+    fn test_subscribe_new_heads() {
+        let mut runtime = Runtime::new().unwrap();
+        let (url, requests) = stand_in(&mut runtime);
+        let transport = WsTransport::new(&url).set_poll_interval(Duration::from_millis(10));
+        let heads = runtime
+            .block_on(transport.subscribe_new_heads().and_then(|heads| {
+                assert_eq!(heads.id(), "0x1");
+                heads.take(2).collect()
+            }))
+            .unwrap();
+        assert_eq!(heads.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&heads[0]).unwrap(),
+            json!({"hash": BLOCK_HASH, "header": {"number": "0x11"}})
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0]["method"], "newBlockFilter");
+        assert_eq!(requests[1]["method"], "getFilterChanges");
+        assert_eq!(requests[1]["params"], json!(["0x1"]));
+        assert_eq!(requests[2]["method"], "getBlockByHash");
+        assert_eq!(requests[2]["params"], json!([BLOCK_HASH, false]));
+    }
+
+    #[test]
+    fn test_subscribe_logs() {
+        let mut runtime = Runtime::new().unwrap();
+        let (url, requests) = stand_in(&mut runtime);
+        let transport = WsTransport::new(&url).set_poll_interval(Duration::from_millis(10));
+        let logs = runtime
+            .block_on(
+                transport
+                    .subscribe_logs(None, Some(vec![ADDRESS]))
+                    .and_then(|logs| logs.take(1).collect()),
+            )
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&logs[0]).unwrap(),
+            json!({"address": ADDRESS, "data": "0x"})
+        );
+        let filter = requests.lock().unwrap()[0].clone();
+        assert_eq!(filter["method"], "newFilter");
+        assert_eq!(filter["params"][0]["address"], json!([ADDRESS]));
+        assert_eq!(filter["params"][0]["topics"], Value::Null);
+    }
+
+    #[test]
+    fn test_uninstall_filter_on_drop() {
+        let mut runtime = Runtime::new().unwrap();
+        let (url, requests) = stand_in(&mut runtime);
+        let transport = Arc::new(WsTransport::new(&url));
+        let subscription = runtime
+            .block_on(transport.subscribe_logs(None, None))
+            .unwrap();
+        drop(subscription);
+
+        // The requests are written in order, uninstallFilter is sent before blockNumber
+        let client = Client::new().set_transport(transport);
+        client.get_block_number().unwrap();
+        let uninstall = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|request| request["method"] == "uninstallFilter")
+            .cloned()
+            .expect("uninstallFilter is sent");
+        assert_eq!(uninstall["params"], json!(["0x2"]));
+    }
+}
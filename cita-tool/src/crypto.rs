@@ -151,6 +151,7 @@ impl fmt::Display for PrivateKey {
 }
 
 /// Pubkey of Secp256k1/Ed25519/Sm2
+#[derive(Clone, Copy)]
 pub enum PubKey {
     /// sha3
     Secp256k1(Secp256k1PubKey),
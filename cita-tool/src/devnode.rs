@@ -0,0 +1,1032 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::mem;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::{future, Future, Poll, Stream};
+use hyper::header::CONTENT_TYPE;
+use hyper::service::service_fn;
+use hyper::{Body, Request, Response, Server, Uri};
+use protobuf::Message as MessageTrait;
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use types::{Address, Bloom, BloomInput, H256, U256};
+
+use crate::client::async_client::ResponseFuture;
+use crate::client::basic::ABI_ADDRESS;
+use crate::client::remove_0x;
+use crate::client::transport::Transport;
+use crate::crypto::{pubkey_to_address, Encryption, Hashable, Signature};
+use crate::error::{ErrorContext, ToolError};
+use crate::models::{
+    Block, BlockBody, BlockHeader, BlockTransaction, Filter, Log, MetaData, PeersInfo, Receipt,
+    RpcTransaction,
+};
+use crate::protos::UnverifiedTransaction;
+use crate::rpctypes::{ErrorResponse, JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue};
+
+mod system;
+
+use self::system::Settings;
+
+/// Blocks after the current height a transaction may stay valid until
+const MAX_VALID_BLOCKS: u64 = 100;
+
+/// Blocks mined at most by one catch up, the intervals missed beyond are skipped
+const MAX_CATCH_UP_BLOCKS: u64 = 16;
+
+/// A mock CITA node with its chain in memory, for tests and demos
+///
+/// The node answers the jsonrpc methods of the clients: blocks, transactions, receipts,
+/// balances, logs and filters, and the getters and setters of the system contracts.
+/// A block is mined every block interval, when a request comes, at most 16 blocks for
+/// the intervals missed between two requests. Value transfers move
+/// the balances, contracts are stored but their code is not run.
+///
+/// The node is a transport of its own, or is served over http by `bind`.
+///
+/// ```rust
+/// extern crate cita_tool;
+///
+/// use std::time::Duration;
+///
+/// use cita_tool::client::basic::{Client, ClientExt};
+/// use cita_tool::devnode::DevNode;
+///
+/// let node = DevNode::new().set_block_interval(Duration::from_millis(10));
+/// let client = Client::new().set_transport(node.clone());
+/// node.mine();
+/// assert!(client.get_current_height().unwrap() >= 1);
+/// ```
+#[derive(Clone)]
+pub struct DevNode {
+    chain: Arc<Mutex<Chain>>,
+}
+
+impl Default for DevNode {
+    fn default() -> Self {
+        DevNode::new()
+    }
+}
+
+impl DevNode {
+    /// Create a node of chain id 1 and version 2, a block is mined every 3 seconds
+    pub fn new() -> Self {
+        let settings = Settings::default();
+        let mut chain = Chain {
+            next_block_at: Instant::now() + Duration::from_millis(settings.block_interval),
+            settings,
+            genesis_timestamp: timestamp(),
+            blocks: Vec::new(),
+            pool: Vec::new(),
+            accounts: HashMap::new(),
+            transactions: HashMap::new(),
+            filters: HashMap::new(),
+            next_filter_id: 1,
+        };
+        chain.mine();
+        DevNode {
+            chain: Arc::new(Mutex::new(chain)),
+        }
+    }
+
+    fn update<F: FnOnce(&mut Chain)>(self, update: F) -> Self {
+        update(&mut self.chain.lock().unwrap());
+        self
+    }
+
+    /// Set the chain id, of the transactions of all the versions
+    pub fn set_chain_id(self, chain_id: u32) -> Self {
+        self.update(|chain| {
+            chain.settings.chain_id = chain_id;
+            chain.settings.chain_id_v1 = U256::from(chain_id);
+        })
+    }
+
+    /// Set the chain version, the transactions must have it
+    pub fn set_version(self, version: u32) -> Self {
+        self.update(|chain| chain.settings.version = version)
+    }
+
+    /// Set the interval between the blocks, at least a millisecond
+    pub fn set_block_interval(self, interval: Duration) -> Self {
+        let millis = (interval.as_millis() as u64).max(1);
+        self.update(|chain| {
+            chain.settings.block_interval = millis;
+            chain.next_block_at = Instant::now() + Duration::from_millis(millis);
+        })
+    }
+
+    /// Set the admin of the chain, also its only validator
+    pub fn set_admin(self, admin: Address) -> Self {
+        self.update(|chain| chain.settings.admin = admin)
+    }
+
+    /// Set the balance of the account
+    pub fn set_balance(self, address: Address, balance: U256) -> Self {
+        self.update(|chain| chain.accounts.entry(address).or_default().balance = balance)
+    }
+
+    /// Current height of the chain
+    pub fn height(&self) -> u64 {
+        let mut chain = self.chain.lock().unwrap();
+        chain.catch_up();
+        chain.height()
+    }
+
+    /// Mine a block of the pending transactions now, return its height
+    pub fn mine(&self) -> u64 {
+        let mut chain = self.chain.lock().unwrap();
+        chain.catch_up();
+        chain.mine();
+        chain.height()
+    }
+
+    /// Answer the jsonrpc request
+    pub fn handle(&self, param: &JsonRpcParams) -> JsonRpcResponse {
+        let id = match param.get("id") {
+            Some(ParamsValue::Int(id)) => *id,
+            _ => 0,
+        };
+        let method = match param.get("method") {
+            Some(ParamsValue::String(method)) => method.as_str(),
+            _ => "",
+        };
+        let params = match param.get("params") {
+            Some(ParamsValue::List(list)) => list.iter().cloned().map(Value::from).collect(),
+            _ => Vec::new(),
+        };
+        let mut chain = self.chain.lock().unwrap();
+        chain.catch_up();
+        match chain.respond(method, &params) {
+            Ok(result) => JsonRpcResponse::from_result(id, ResponseValue::from(result)),
+            Err(error) => JsonRpcResponse::from_error(id, error),
+        }
+    }
+
+    /// Answer the body of a http request, one request or a batch
+    fn handle_body(&self, body: &[u8]) -> String {
+        let respond = |value: Value| match serde_json::from_value::<JsonRpcParams>(value) {
+            Ok(param) => self.handle(&param),
+            Err(_) => JsonRpcResponse::from_error(0, ErrorResponse::new(-32600, "Invalid request")),
+        };
+        let response = match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(batch)) => json!(batch.into_iter().map(respond).collect::<Vec<_>>()),
+            Ok(value) => json!(respond(value)),
+            Err(_) => json!(JsonRpcResponse::from_error(
+                0,
+                ErrorResponse::new(-32700, "Parse error")
+            )),
+        };
+        response.to_string()
+    }
+
+    /// Listen on the address, the server answers once it is run or spawned
+    pub fn bind(&self, addr: &SocketAddr) -> Result<DevServer, ToolError> {
+        let node = self.clone();
+        let server = Server::try_bind(addr)
+            .map_err(ToolError::Hyper)
+            .with_context(|| format!("Can't listen on {}", addr))?
+            .serve(move || {
+                let node = node.clone();
+                service_fn(move |req: Request<Body>| {
+                    let node = node.clone();
+                    req.into_body().concat2().map(move |body| {
+                        Response::builder()
+                            .header(CONTENT_TYPE, "application/json")
+                            .body(Body::from(node.handle_body(&body)))
+                            .unwrap()
+                    })
+                })
+            });
+        Ok(DevServer {
+            local_addr: server.local_addr(),
+            server: Box::new(server.map_err(ToolError::Hyper)),
+        })
+    }
+}
+
+impl Transport for DevNode {
+    fn send(
+        &self,
+        _url: &Uri,
+        params: Vec<JsonRpcParams>,
+    ) -> ResponseFuture<Vec<JsonRpcResponse>, ToolError> {
+        Box::new(future::ok(
+            params.iter().map(|param| self.handle(param)).collect(),
+        ))
+    }
+}
+
+/// The http server of a node, a future which runs until an error
+pub struct DevServer {
+    local_addr: SocketAddr,
+    server: ResponseFuture<(), ToolError>,
+}
+
+impl DevServer {
+    /// The address the server listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Serve on a runtime of its own, block until an error
+    pub fn run(self) -> Result<(), ToolError> {
+        Runtime::new()?.block_on(self)
+    }
+}
+
+impl Future for DevServer {
+    type Item = ();
+    type Error = ToolError;
+
+    fn poll(&mut self) -> Poll<(), ToolError> {
+        self.server.poll()
+    }
+}
+
+#[derive(Default)]
+struct Account {
+    balance: U256,
+    nonce: u64,
+    code: Vec<u8>,
+    abi: Vec<u8>,
+}
+
+/// A valid transaction waiting for the next block
+struct PendingTransaction {
+    hash: H256,
+    content: Vec<u8>,
+    sender: Address,
+    to: Option<Address>,
+    data: Vec<u8>,
+    value: U256,
+    quota: u64,
+}
+
+struct MinedTransaction {
+    hash: H256,
+    content: Vec<u8>,
+    sender: Address,
+    receipt: Receipt,
+}
+
+struct MinedBlock {
+    hash: H256,
+    header: BlockHeader,
+    transactions: Vec<MinedTransaction>,
+}
+
+/// An installed filter, with the first block of its next changes
+#[derive(Clone)]
+enum Installed {
+    Logs { filter: Filter, next: u64 },
+    Blocks { next: u64 },
+}
+
+struct Chain {
+    settings: Settings,
+    genesis_timestamp: u64,
+    next_block_at: Instant,
+    blocks: Vec<MinedBlock>,
+    pool: Vec<PendingTransaction>,
+    accounts: HashMap<Address, Account>,
+    /// Block and index of the mined transactions
+    transactions: HashMap<H256, (usize, usize)>,
+    filters: HashMap<u64, Installed>,
+    next_filter_id: u64,
+}
+
+impl Chain {
+    fn height(&self) -> u64 {
+        self.blocks.len() as u64 - 1
+    }
+
+    /// Mine the blocks due since the last request, at most `MAX_CATCH_UP_BLOCKS`
+    fn catch_up(&mut self) {
+        let now = Instant::now();
+        let interval = Duration::from_millis(self.settings.block_interval);
+        for _ in 0..MAX_CATCH_UP_BLOCKS {
+            if self.next_block_at > now {
+                return;
+            }
+            self.mine();
+            self.next_block_at += interval;
+        }
+        if self.next_block_at <= now {
+            self.next_block_at = now + interval;
+        }
+    }
+
+    fn mine(&mut self) {
+        let number = self.blocks.len() as u64;
+        let prev_hash = self
+            .blocks
+            .last()
+            .map(|block| block.hash)
+            .unwrap_or_default();
+        let mut quota_used = U256::zero();
+        let mut log_index = 0;
+        let mut transactions = Vec::new();
+        for (index, tx) in mem::take(&mut self.pool).into_iter().enumerate() {
+            let execution = self.execute(&tx);
+            quota_used += U256::from(execution.quota_used);
+            let mut logs_bloom = Bloom::default();
+            let logs = execution
+                .logs
+                .into_iter()
+                .enumerate()
+                .map(|(transaction_log_index, (address, topics, data))| {
+                    logs_bloom.accrue(BloomInput::Raw(&address));
+                    for topic in &topics {
+                        logs_bloom.accrue(BloomInput::Raw(topic));
+                    }
+                    log_index += 1;
+                    Log {
+                        address,
+                        topics,
+                        data,
+                        block_hash: None,
+                        block_number: Some(number),
+                        transaction_hash: Some(tx.hash),
+                        transaction_index: Some(index as u64),
+                        log_index: Some(log_index - 1),
+                        transaction_log_index: Some(transaction_log_index as u64),
+                    }
+                })
+                .collect();
+            transactions.push(MinedTransaction {
+                hash: tx.hash,
+                content: tx.content,
+                sender: tx.sender,
+                receipt: Receipt {
+                    transaction_hash: tx.hash,
+                    transaction_index: index as u64,
+                    block_hash: H256::zero(),
+                    block_number: number,
+                    cumulative_quota_used: quota_used,
+                    quota_used: U256::from(execution.quota_used),
+                    contract_address: execution.contract_address,
+                    logs,
+                    root: None,
+                    logs_bloom,
+                    error_message: execution.error_message,
+                },
+            });
+        }
+
+        // The hashes and roots digest the block, they are no merkle roots
+        let timestamp = timestamp();
+        let digest = |parts: &[&[u8]]| parts.concat().crypt_hash(Encryption::Secp256k1);
+        let hashes = transactions
+            .iter()
+            .flat_map(|tx| tx.hash.to_vec())
+            .collect::<Vec<_>>();
+        let header = BlockHeader {
+            timestamp,
+            prev_hash,
+            number,
+            state_root: digest(&[b"state", &number.to_be_bytes(), &hashes]),
+            transactions_root: digest(&[b"transactions", &hashes]),
+            receipts_root: digest(&[b"receipts", &hashes]),
+            quota_used,
+            proof: None,
+            proposer: self.settings.admin,
+        };
+        let hash = digest(&[
+            &number.to_be_bytes(),
+            &prev_hash,
+            &timestamp.to_be_bytes(),
+            &hashes,
+        ]);
+        for (index, tx) in transactions.iter_mut().enumerate() {
+            tx.receipt.block_hash = hash;
+            for log in &mut tx.receipt.logs {
+                log.block_hash = Some(hash);
+            }
+            self.transactions
+                .insert(tx.hash, (self.blocks.len(), index));
+        }
+        self.blocks.push(MinedBlock {
+            hash,
+            header,
+            transactions,
+        });
+    }
+
+    /// Run the transaction against the accounts and the system contracts
+    fn execute(&mut self, tx: &PendingTransaction) -> Execution {
+        let sender = self.accounts.entry(tx.sender).or_default();
+        sender.nonce += 1;
+        let quota_used = intrinsic_quota(&tx.data, tx.to.is_none());
+        let failed = |quota_used, message: &str| Execution {
+            quota_used,
+            contract_address: None,
+            logs: Vec::new(),
+            error_message: Some(message.to_string()),
+        };
+        if tx.quota < quota_used {
+            return failed(tx.quota, "Not enough base quota.");
+        }
+        if sender.balance < tx.value {
+            return failed(quota_used, "Not enough cash.");
+        }
+
+        let mut contract_address = None;
+        let mut logs = Vec::new();
+        let receiver = match tx.to {
+            None => {
+                let nonce = sender.nonce.to_be_bytes();
+                let hash = [&tx.sender[..], &tx.hash[..], &nonce[..]]
+                    .concat()
+                    .crypt_hash(Encryption::Secp256k1);
+                let address = Address::from(&hash.0[12..]);
+                self.accounts.entry(address).or_default().code = tx.data.clone();
+                contract_address = Some(address);
+                address
+            }
+            Some(to) => {
+                if to == Address::from_str(remove_0x(ABI_ADDRESS)).unwrap() && tx.data.len() > 20 {
+                    let address = Address::from(&tx.data[..20]);
+                    self.accounts.entry(address).or_default().abi = tx.data[20..].to_vec();
+                }
+                match system::execute(&mut self.settings, &tx.sender, &to, &tx.data) {
+                    Some(Ok(events)) => {
+                        logs.extend(events.into_iter().map(|(topics, data)| (to, topics, data)))
+                    }
+                    Some(Err(message)) => return failed(quota_used, &message),
+                    None => {}
+                }
+                to
+            }
+        };
+        if !tx.value.is_zero() {
+            self.accounts.entry(tx.sender).or_default().balance -= tx.value;
+            self.accounts.entry(receiver).or_default().balance += tx.value;
+        }
+        Execution {
+            quota_used,
+            contract_address,
+            logs,
+            error_message: None,
+        }
+    }
+
+    fn respond(&mut self, method: &str, params: &[Value]) -> Result<Value, ErrorResponse> {
+        match method {
+            "peerCount" => Ok(json!("0x0")),
+            "peersInfo" => Ok(json!(PeersInfo {
+                amount: 0,
+                peers: Some(HashMap::new()),
+                error_message: None,
+            })),
+            "blockNumber" => Ok(json!(format!("{:#x}", self.height()))),
+            "getMetaData" => {
+                self.height_param(params, 0)?;
+                Ok(json!(self.metadata()))
+            }
+            "getVersion" => Ok(json!({
+                "softwareVersion": format!("devnode-{}", env!("CARGO_PKG_VERSION"))
+            })),
+            "sendRawTransaction" => self.send_raw_transaction(string_param(params, 0)?),
+            "getBlockByNumber" => {
+                let height = self.height_param(params, 0)?;
+                let full = params.get(1).and_then(Value::as_bool).unwrap_or(false);
+                Ok(self
+                    .blocks
+                    .get(height as usize)
+                    .map(|block| self.block(block, full))
+                    .unwrap_or(Value::Null))
+            }
+            "getBlockByHash" => {
+                let hash = parse_hash(string_param(params, 0)?)?;
+                let full = params.get(1).and_then(Value::as_bool).unwrap_or(false);
+                Ok(self
+                    .blocks
+                    .iter()
+                    .find(|block| block.hash == hash)
+                    .map(|block| self.block(block, full))
+                    .unwrap_or(Value::Null))
+            }
+            "getTransaction" => {
+                let hash = parse_hash(string_param(params, 0)?)?;
+                Ok(self
+                    .transactions
+                    .get(&hash)
+                    .map(|&(block, index)| json!(self.transaction(block, index)))
+                    .unwrap_or(Value::Null))
+            }
+            "getTransactionReceipt" => {
+                let hash = parse_hash(string_param(params, 0)?)?;
+                Ok(self
+                    .transactions
+                    .get(&hash)
+                    .map(|&(block, index)| json!(self.blocks[block].transactions[index].receipt))
+                    .unwrap_or(Value::Null))
+            }
+            "getTransactionCount" | "getBalance" | "getCode" | "getAbi" => {
+                let address = parse_address(string_param(params, 0)?)?;
+                self.height_param(params, 1)?;
+                let account = self.accounts.get(&address);
+                Ok(match method {
+                    "getTransactionCount" => {
+                        json!(format!("{:#x}", account.map(|a| a.nonce).unwrap_or(0)))
+                    }
+                    "getBalance" => json!(account.map(|a| a.balance).unwrap_or_default()),
+                    "getCode" => hex_bytes(account.map(|a| &a.code[..]).unwrap_or_default()),
+                    _ => hex_bytes(account.map(|a| &a.abi[..]).unwrap_or_default()),
+                })
+            }
+            "call" => {
+                let (to, data) = call_request(params)?;
+                self.height_param(params, 1)?;
+                let output = to
+                    .and_then(|to| system::call(&self.settings, &to, &data))
+                    .unwrap_or_default();
+                Ok(hex_bytes(&output))
+            }
+            "estimateQuota" => {
+                let (to, data) = call_request(params)?;
+                Ok(json!(format!(
+                    "{:#x}",
+                    intrinsic_quota(&data, to.is_none())
+                )))
+            }
+            "getStorageAt" => Ok(json!(H256::zero())),
+            "getLogs" => {
+                let filter = filter_param(params)?;
+                let from = self.height_of(filter.from_block.as_deref())?;
+                let to = self.height_of(filter.to_block.as_deref())?;
+                Ok(json!(self.logs(&filter, from, to)))
+            }
+            "newFilter" => {
+                let filter = filter_param(params)?;
+                let next = self.height() + 1;
+                Ok(self.install(Installed::Logs { filter, next }))
+            }
+            "newBlockFilter" => {
+                let next = self.height() + 1;
+                Ok(self.install(Installed::Blocks { next }))
+            }
+            "uninstallFilter" => {
+                let id = filter_id(params)?;
+                Ok(json!(self.filters.remove(&id).is_some()))
+            }
+            "getFilterChanges" => {
+                let id = filter_id(params)?;
+                let height = self.height();
+                let changes = match self.filters.get(&id).cloned() {
+                    Some(Installed::Blocks { next }) => json!(self.blocks[next as usize..]
+                        .iter()
+                        .map(|block| block.hash)
+                        .collect::<Vec<_>>()),
+                    Some(Installed::Logs { filter, next }) => {
+                        // Only the blocks since the last poll, `latest` is no lower bound
+                        let from = match filter.from_block.as_deref().and_then(number) {
+                            Some(from) => from.max(next),
+                            None => next,
+                        };
+                        let to = self.height_of(filter.to_block.as_deref())?;
+                        json!(self.logs(&filter, from, to))
+                    }
+                    None => return Err(ErrorResponse::new(-32000, "Filter not found")),
+                };
+                if let Some(Installed::Blocks { next }) | Some(Installed::Logs { next, .. }) =
+                    self.filters.get_mut(&id)
+                {
+                    *next = height + 1;
+                }
+                Ok(changes)
+            }
+            "getFilterLogs" => {
+                let id = filter_id(params)?;
+                match self.filters.get(&id) {
+                    Some(Installed::Logs { filter, .. }) => {
+                        let from = self.height_of(filter.from_block.as_deref())?;
+                        let to = self.height_of(filter.to_block.as_deref())?;
+                        Ok(json!(self.logs(filter, from, to)))
+                    }
+                    Some(Installed::Blocks { .. }) => Ok(json!([])),
+                    None => Err(ErrorResponse::new(-32000, "Filter not found")),
+                }
+            }
+            _ => Err(ErrorResponse::new(-32601, "Method not found")),
+        }
+    }
+
+    /// Check the transaction and add it to the pool
+    fn send_raw_transaction(&mut self, content: &str) -> Result<Value, ErrorResponse> {
+        let unverified = UnverifiedTransaction::from_str(content).map_err(invalid_params)?;
+        let encryption = match Signature::from(unverified.get_signature()) {
+            Signature::Secp256k1(_) => Encryption::Secp256k1,
+            Signature::Ed25519(_) => Encryption::Ed25519,
+            Signature::Sm2(_) => Encryption::Sm2,
+            Signature::Null => return Err(rejected("InvalidSignature")),
+        };
+        let sender = unverified
+            .public_key(encryption)
+            .map(|pubkey| pubkey_to_address(&pubkey))
+            .map_err(|_| rejected("InvalidSignature"))?;
+        let content = unverified.write_to_bytes().map_err(invalid_params)?;
+        let hash = content.crypt_hash(encryption);
+        let tx = unverified.get_transaction();
+
+        if tx.version != self.settings.version {
+            return Err(rejected("InvalidVersion"));
+        }
+        let chain_id_matches = if tx.version == 0 {
+            tx.chain_id == self.settings.chain_id
+        } else {
+            uint_bytes(&tx.chain_id_v1)? == self.settings.chain_id_v1
+        };
+        if !chain_id_matches {
+            return Err(rejected("BadChainId"));
+        }
+        let height = self.height();
+        if tx.valid_until_block <= height || tx.valid_until_block > height + MAX_VALID_BLOCKS {
+            return Err(rejected("InvalidUntilBlock"));
+        }
+        if U256::from(tx.quota) > self.settings.account_quota_limit(&sender) {
+            return Err(rejected("QuotaNotEnough"));
+        }
+        if self.transactions.contains_key(&hash) || self.pool.iter().any(|tx| tx.hash == hash) {
+            return Err(rejected("Dup"));
+        }
+        let to = if tx.version == 0 {
+            Some(remove_0x(&tx.to))
+                .filter(|to| !to.is_empty())
+                .map(parse_address)
+                .transpose()?
+        } else if tx.to_v1.is_empty() {
+            None
+        } else if tx.to_v1.len() == 20 {
+            Some(Address::from(tx.to_v1.as_slice()))
+        } else {
+            return Err(invalid_params("invalid to address"));
+        };
+        let value = uint_bytes(&tx.value)?;
+
+        self.pool.push(PendingTransaction {
+            hash,
+            content,
+            sender,
+            to,
+            data: tx.data.clone(),
+            value,
+            quota: tx.quota,
+        });
+        Ok(json!({ "hash": hash, "status": "OK" }))
+    }
+
+    fn metadata(&self) -> MetaData {
+        let settings = &self.settings;
+        MetaData {
+            chain_id: settings.chain_id,
+            chain_id_v1: settings.chain_id_v1,
+            chain_name: settings.chain_name.clone(),
+            operator: settings.operator.clone(),
+            website: settings.website.clone(),
+            genesis_timestamp: self.genesis_timestamp,
+            validators: vec![settings.admin],
+            block_interval: settings.block_interval,
+            token_name: "CITA Test Token".to_string(),
+            token_symbol: "CTT".to_string(),
+            token_avatar: String::new(),
+            version: settings.version,
+            economical_model: settings.economical_model,
+        }
+    }
+
+    fn transaction(&self, block: usize, index: usize) -> RpcTransaction {
+        let tx = &self.blocks[block].transactions[index];
+        RpcTransaction {
+            hash: tx.hash,
+            content: tx.content.clone(),
+            from: Some(tx.sender),
+            block_number: Some(block as u64),
+            block_hash: Some(self.blocks[block].hash),
+            index: Some(index as u64),
+        }
+    }
+
+    fn block(&self, block: &MinedBlock, full: bool) -> Value {
+        let number = block.header.number as usize;
+        let transactions = block
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                if full {
+                    BlockTransaction::Full(self.transaction(number, index))
+                } else {
+                    BlockTransaction::Hash(tx.hash)
+                }
+            })
+            .collect();
+        json!(Block {
+            version: self.settings.version,
+            hash: block.hash,
+            header: block.header.clone(),
+            body: BlockBody { transactions },
+        })
+    }
+
+    /// The logs of the blocks `from..=to` which match the filter
+    fn logs(&self, filter: &Filter, from: u64, to: u64) -> Vec<Log> {
+        let to = to.min(self.height());
+        if from > to {
+            return Vec::new();
+        }
+        self.blocks[from as usize..=to as usize]
+            .iter()
+            .flat_map(|block| &block.transactions)
+            .flat_map(|tx| &tx.receipt.logs)
+            .filter(|log| {
+                let address = match filter.address {
+                    Some(ref addresses) if !addresses.is_empty() => {
+                        addresses.contains(&log.address)
+                    }
+                    _ => true,
+                };
+                let mut topics = filter.topics.iter().flatten().enumerate();
+                address && topics.all(|(index, topic)| log.topics.get(index) == Some(topic))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn install(&mut self, filter: Installed) -> Value {
+        let id = self.next_filter_id;
+        self.next_filter_id += 1;
+        self.filters.insert(id, filter);
+        json!(format!("{:#x}", id))
+    }
+
+    /// The height of a block parameter, the latest block by default
+    fn height_of(&self, height: Option<&str>) -> Result<u64, ErrorResponse> {
+        match height {
+            None | Some("latest") | Some("pending") => Ok(self.height()),
+            Some("earliest") => Ok(0),
+            Some(height) => number(height).ok_or_else(|| invalid_params(height)),
+        }
+    }
+
+    fn height_param(&self, params: &[Value], index: usize) -> Result<u64, ErrorResponse> {
+        match params.get(index) {
+            Some(Value::String(height)) => self.height_of(Some(height)),
+            None => self.height_of(None),
+            Some(height) => Err(invalid_params(height)),
+        }
+    }
+}
+
+struct Execution {
+    quota_used: u64,
+    contract_address: Option<Address>,
+    logs: Vec<(Address, Vec<H256>, Vec<u8>)>,
+    error_message: Option<String>,
+}
+
+/// Quota of a transaction before its code runs
+fn intrinsic_quota(data: &[u8], create: bool) -> u64 {
+    let base = if create { 53_000 } else { 21_000 };
+    data.iter()
+        .map(|&byte| if byte == 0 { 4 } else { 68 })
+        .sum::<u64>()
+        + base
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn invalid_params<T: Display>(detail: T) -> ErrorResponse {
+    ErrorResponse::new(-32602, format!("Invalid params: {}", detail))
+}
+
+/// A transaction refused by the pool
+fn rejected(reason: &str) -> ErrorResponse {
+    ErrorResponse::new(-32006, reason)
+}
+
+fn string_param(params: &[Value], index: usize) -> Result<&str, ErrorResponse> {
+    params
+        .get(index)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid_params(format!("no string param {}", index)))
+}
+
+fn filter_param(params: &[Value]) -> Result<Filter, ErrorResponse> {
+    params
+        .first()
+        .cloned()
+        .map(serde_json::from_value)
+        .unwrap_or_else(|| Ok(Filter::default()))
+        .map_err(invalid_params)
+}
+
+fn filter_id(params: &[Value]) -> Result<u64, ErrorResponse> {
+    let id = string_param(params, 0)?;
+    number(id).ok_or_else(|| invalid_params(id))
+}
+
+/// The receiver and data of `call` and `estimateQuota`, no receiver creates a contract
+fn call_request(params: &[Value]) -> Result<(Option<Address>, Vec<u8>), ErrorResponse> {
+    let request = params
+        .first()
+        .ok_or_else(|| invalid_params("no call request"))?;
+    let field = |name: &str| {
+        request
+            .get(name)
+            .and_then(Value::as_str)
+            .map(remove_0x)
+            .filter(|value| !value.is_empty())
+    };
+    let to = field("to").map(parse_address).transpose()?;
+    let data = match field("data") {
+        Some(data) => hex::decode(data).map_err(invalid_params)?,
+        None => Vec::new(),
+    };
+    Ok((to, data))
+}
+
+/// A hex quantity
+fn number(value: &str) -> Option<u64> {
+    u64::from_str_radix(remove_0x(value), 16).ok()
+}
+
+fn parse_address(address: &str) -> Result<Address, ErrorResponse> {
+    Address::from_str(remove_0x(address)).map_err(|_| invalid_params(address))
+}
+
+fn parse_hash(hash: &str) -> Result<H256, ErrorResponse> {
+    H256::from_str(remove_0x(hash)).map_err(|_| invalid_params(hash))
+}
+
+/// A big endian uint of at most 32 bytes
+fn uint_bytes(bytes: &[u8]) -> Result<U256, ErrorResponse> {
+    if bytes.len() > 32 {
+        return Err(invalid_params("uint longer than 32 bytes"));
+    }
+    Ok(U256::from(bytes))
+}
+
+fn hex_bytes(bytes: &[u8]) -> Value {
+    json!(format!("0x{}", hex::encode(bytes)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::{Client, Transfer};
+    use crate::client::system_contract::{QuotaManageClient, QuotaManagementExt};
+    use crate::client::typed::TypedClientExt;
+    use crate::client::wait::WaitOptions;
+    use crate::crypto::{KeyPair, PrivateKey};
+    use crate::error::ErrorKind;
+    use crate::models::FilterChanges;
+    use crate::protos::Transaction;
+    use std::thread;
+
+    #[test]
+    fn test_devnode() {
+        let key = PrivateKey::from_str(
+            "993ef0853d7bf1f4c2977457b50ea6b5f8bc2fd829e3ca3e19f6081ddabb07e9",
+            Encryption::Secp256k1,
+        )
+        .unwrap();
+        let sender = KeyPair::from_privkey(key).address();
+        let node = DevNode::new()
+            .set_chain_id(7)
+            .set_block_interval(Duration::from_millis(20))
+            .set_balance(sender, U256::from(1000));
+        let mut client = Client::new().set_transport(node.clone());
+        client.set_private_key(&key);
+        let options = WaitOptions::new().set_poll_interval(Duration::from_millis(10));
+        assert_eq!(
+            client.metadata("latest").unwrap().chain_id_v1,
+            U256::from(7)
+        );
+
+        // Value transfers
+        let receiver = "0xffffffffffffffffffffffffffffffffffff0001";
+        let response = client
+            .transfer(U256::from(100), receiver, Some(30_000))
+            .unwrap();
+        let receipt = client.wait_for_transaction(&response, &options).unwrap();
+        assert_eq!(receipt.quota_used, U256::from(21_000));
+        assert_eq!(client.balance(receiver, "latest").unwrap(), U256::from(100));
+        let sender = format!("{:?}", sender);
+        assert_eq!(client.balance(&sender, "latest").unwrap(), U256::from(900));
+        assert_eq!(client.transaction_count(&sender, "latest").unwrap(), 1);
+        let response = client
+            .transfer(U256::from(1000), receiver, Some(30_000))
+            .unwrap();
+        match client.wait_for_transaction(&response, &options) {
+            Err(err) => assert_eq!(err.kind(), ErrorKind::Transaction),
+            Ok(_) => panic!("expect not enough cash"),
+        }
+
+        // System contracts, their events and the filters
+        let quota_manager = "0xffffffffffffffffffffffffffffffffff020003";
+        let filter = Filter {
+            from_block: Some("earliest".to_string()),
+            address: Some(vec![Address::from_str(remove_0x(quota_manager)).unwrap()]),
+            ..Default::default()
+        };
+        let log_filter = format!("{:#x}", client.install_log_filter(&filter).unwrap());
+        let block_filter = format!("{:#x}", client.install_block_filter().unwrap());
+        let limit = |address: &str| {
+            let quota = QuotaManageClient::create_async(client.async_client().clone());
+            client
+                .runtime()
+                .block_on(quota.account_quota_limit(address, None))
+                .unwrap()
+        };
+        assert_eq!(limit(receiver), U256::from(268_435_456));
+        let response = QuotaManageClient::create(client.clone())
+            .set_aql(receiver, U256::from(1_000_000), None)
+            .unwrap();
+        let receipt = client.wait_for_transaction(&response, &options).unwrap();
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(limit(receiver), U256::from(1_000_000));
+        match client.filter_changes(&log_filter).unwrap() {
+            FilterChanges::Logs(logs) => {
+                assert_eq!(logs[0].transaction_hash, Some(receipt.transaction_hash))
+            }
+            changes => panic!("expect logs, got {:?}", changes),
+        }
+        assert_eq!(client.logs(&filter).unwrap().len(), 1);
+        match client.filter_changes(&block_filter).unwrap() {
+            FilterChanges::Hashes(hashes) => assert!(hashes.contains(&receipt.block_hash)),
+            changes => panic!("expect block hashes, got {:?}", changes),
+        }
+        assert!(client.remove_filter(&log_filter).unwrap());
+        assert!(client.filter_changes(&log_filter).is_err());
+
+        // Served over http
+        let server = node.bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        let url = format!("http://{}", server.local_addr());
+        client.runtime().spawn(server.map_err(|_| ())).unwrap();
+        let height = node.mine();
+        let client = Client::new().set_uri(&url);
+        assert!(client.get_current_height().unwrap() >= height);
+        assert_eq!(client.get_version().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_devnode_oversized_uint() {
+        let key = PrivateKey::from_str(
+            "993ef0853d7bf1f4c2977457b50ea6b5f8bc2fd829e3ca3e19f6081ddabb07e9",
+            Encryption::Secp256k1,
+        )
+        .unwrap();
+        let node = DevNode::new();
+        let send = |value: Vec<u8>, chain_id: Vec<u8>| {
+            let mut tx = Transaction::new();
+            tx.set_version(2);
+            tx.set_quota(30_000);
+            tx.set_valid_until_block(node.height() + 50);
+            tx.set_to_v1(vec![0xff; 20]);
+            tx.set_value(value);
+            tx.set_chain_id_v1(chain_id);
+            let content = tx.build_unverified(key).write_to_bytes().unwrap();
+            let param = JsonRpcParams::new()
+                .insert("id", ParamsValue::Int(1))
+                .insert(
+                    "method",
+                    ParamsValue::String("sendRawTransaction".to_string()),
+                )
+                .insert(
+                    "params",
+                    ParamsValue::List(vec![ParamsValue::String(hex::encode(content))]),
+                );
+            node.handle(&param)
+        };
+        let chain_id = U256::from(1);
+        let chain_id = <[u8; 32]>::from(chain_id).to_vec();
+
+        let response = send(vec![1; 33], chain_id.clone());
+        assert_eq!(response.error().unwrap().code(), -32602);
+        let response = send(Vec::new(), vec![1; 33]);
+        assert_eq!(response.error().unwrap().code(), -32602);
+        // The node still answers
+        assert!(send(Vec::new(), chain_id).is_ok());
+        assert_eq!(node.mine(), 1);
+    }
+
+    #[test]
+    fn test_catch_up_is_bounded() {
+        let node = DevNode::new().set_block_interval(Duration::from_millis(1));
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(node.height(), MAX_CATCH_UP_BLOCKS);
+        // The missed intervals are skipped, not mined by the next request
+        assert!(node.height() < 2 * MAX_CATCH_UP_BLOCKS);
+    }
+}
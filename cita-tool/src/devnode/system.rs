@@ -0,0 +1,383 @@
+use std::str::FromStr;
+
+use ethabi::param_type::ParamType;
+use ethabi::token::Token;
+use ethabi::{Contract, Function};
+use lazy_static::lazy_static;
+use types::{Address, H256, U256};
+
+use crate::crypto::{Encryption, Hashable};
+
+/// Name, address and ABI of the system contracts the node answers
+const SYSTEM_CONTRACTS: &[(&str, &str, &str)] = &[
+    (
+        "SysConfig",
+        "ffffffffffffffffffffffffffffffffff020000",
+        include_str!("../../contract_abi/SysConfig.abi"),
+    ),
+    (
+        "NodeManager",
+        "ffffffffffffffffffffffffffffffffff020001",
+        include_str!("../../contract_abi/NodeManager.abi"),
+    ),
+    (
+        "QuotaManager",
+        "ffffffffffffffffffffffffffffffffff020003",
+        include_str!("../../contract_abi/QuotaManager.abi"),
+    ),
+    (
+        "PermissionManagement",
+        "ffffffffffffffffffffffffffffffffff020004",
+        include_str!("../../contract_abi/PermissionManagement.abi"),
+    ),
+    (
+        "Authorization",
+        "ffffffffffffffffffffffffffffffffff020006",
+        include_str!("../../contract_abi/Authorization.abi"),
+    ),
+    (
+        "RoleManagement",
+        "ffffffffffffffffffffffffffffffffff020007",
+        include_str!("../../contract_abi/RoleManagement.abi"),
+    ),
+    (
+        "Group",
+        "ffffffffffffffffffffffffffffffffff020009",
+        include_str!("../../contract_abi/Group.abi"),
+    ),
+    (
+        "GroupManagement",
+        "ffffffffffffffffffffffffffffffffff02000a",
+        include_str!("../../contract_abi/GroupManagement.abi"),
+    ),
+    (
+        "Admin",
+        "ffffffffffffffffffffffffffffffffff02000c",
+        include_str!("../../contract_abi/Admin.abi"),
+    ),
+    (
+        "BatchTx",
+        "ffffffffffffffffffffffffffffffffff02000e",
+        include_str!("../../contract_abi/BatchTx.abi"),
+    ),
+    (
+        "EmergencyBrake",
+        "ffffffffffffffffffffffffffffffffff02000f",
+        include_str!("../../contract_abi/EmergencyBrake.abi"),
+    ),
+    (
+        "PriceManager",
+        "ffffffffffffffffffffffffffffffffff020010",
+        include_str!("../../contract_abi/PriceManager.abi"),
+    ),
+    (
+        "VersionManager",
+        "ffffffffffffffffffffffffffffffffff020011",
+        include_str!("../../contract_abi/VersionManager.abi"),
+    ),
+];
+
+struct SystemContract {
+    name: &'static str,
+    address: Address,
+    contract: Contract,
+}
+
+lazy_static! {
+    static ref CONTRACTS: Vec<SystemContract> = SYSTEM_CONTRACTS
+        .iter()
+        .map(|(name, address, abi)| SystemContract {
+            name,
+            address: Address::from_str(address).unwrap(),
+            contract: Contract::load(abi.as_bytes()).unwrap(),
+        })
+        .collect();
+}
+
+/// The chain settings kept by the system contracts
+#[derive(Clone)]
+pub(crate) struct Settings {
+    pub chain_id: u32,
+    pub chain_id_v1: U256,
+    pub version: u32,
+    pub chain_name: String,
+    pub operator: String,
+    pub website: String,
+    pub admin: Address,
+    pub block_interval: u64,
+    pub economical_model: u8,
+    pub block_quota_limit: U256,
+    pub default_account_quota_limit: U256,
+    pub account_quota_limits: Vec<(Address, U256)>,
+    pub quota_price: U256,
+    pub emergency_brake: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            chain_id: 1,
+            chain_id_v1: U256::from(1),
+            version: 2,
+            chain_name: "devnode".to_string(),
+            operator: "cita-cli".to_string(),
+            website: "https://github.com/cryptape/cita-cli".to_string(),
+            admin: Address::zero(),
+            block_interval: 3000,
+            economical_model: 0,
+            block_quota_limit: U256::from(1_073_741_824),
+            default_account_quota_limit: U256::from(268_435_456),
+            account_quota_limits: Vec::new(),
+            quota_price: U256::from(1),
+            emergency_brake: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Account quota limit of the address
+    pub fn account_quota_limit(&self, address: &Address) -> U256 {
+        self.account_quota_limits
+            .iter()
+            .find(|(account, _)| account == address)
+            .map(|(_, limit)| *limit)
+            .unwrap_or(self.default_account_quota_limit)
+    }
+}
+
+/// The system contract, the function and its inputs
+type Called = (&'static SystemContract, &'static Function, Vec<Token>);
+
+/// Topics and data of an event
+pub(crate) type EventLog = (Vec<H256>, Vec<u8>);
+
+/// The system contract and its function called by the data
+fn function(address: &Address, data: &[u8]) -> Option<Result<Called, String>> {
+    let system = CONTRACTS.iter().find(|system| system.address == *address)?;
+    let function = system.contract.functions().find(|function| {
+        let kinds = function
+            .inputs
+            .iter()
+            .map(|param| param.kind.to_string())
+            .collect::<Vec<_>>();
+        let signature = format!("{}({})", function.name, kinds.join(","));
+        data.len() >= 4
+            && signature.as_bytes().crypt_hash(Encryption::Secp256k1).0[..4] == data[..4]
+    });
+    Some(match function {
+        Some(function) => {
+            let kinds = function
+                .inputs
+                .iter()
+                .map(|param| param.kind.clone())
+                .collect::<Vec<_>>();
+            ethabi::decode(&kinds, &data[4..])
+                .map(|inputs| (system, function, inputs))
+                .map_err(|_| "Reverted.".to_string())
+        }
+        None => Err("Reverted.".to_string()),
+    })
+}
+
+/// Output of a getter of a system contract, `None` when the address is no system contract
+pub(crate) fn call(settings: &Settings, address: &Address, data: &[u8]) -> Option<Vec<u8>> {
+    let (system, function, inputs) = match function(address, data)? {
+        Ok(function) => function,
+        Err(_) => return Some(Vec::new()),
+    };
+    let outputs = match (system.name, function.name.as_str()) {
+        ("SysConfig", "getChainId") => vec![uint(U256::from(settings.chain_id))],
+        ("SysConfig", "getChainIdV1") => vec![uint(settings.chain_id_v1)],
+        ("SysConfig", "getChainName") => vec![Token::String(settings.chain_name.clone())],
+        ("SysConfig", "getOperator") => vec![Token::String(settings.operator.clone())],
+        ("SysConfig", "getWebsite") => vec![Token::String(settings.website.clone())],
+        ("SysConfig", "getBlockInterval") => vec![uint(U256::from(settings.block_interval))],
+        ("SysConfig", "getEconomicalModel") => vec![uint(U256::from(settings.economical_model))],
+        ("SysConfig", "getChainOwner") | ("Admin", "admin") => vec![address_token(&settings.admin)],
+        ("SysConfig", "getTokenInfo") => vec![
+            Token::String("CITA Test Token".to_string()),
+            Token::String("CTT".to_string()),
+            Token::String(String::new()),
+        ],
+        ("SysConfig", "getDelayBlockNumber") => vec![uint(U256::from(1))],
+        ("Admin", "isAdmin") => vec![Token::Bool(to_address(&inputs[0]) == settings.admin)],
+        ("NodeManager", "listNode") => vec![Token::Array(vec![address_token(&settings.admin)])],
+        ("NodeManager", "listStake") => vec![Token::Array(vec![uint(U256::from(1))])],
+        ("NodeManager", "getStatus") | ("NodeManager", "status") => {
+            vec![uint(U256::from(
+                (to_address(&inputs[0]) == settings.admin) as u8,
+            ))]
+        }
+        ("NodeManager", "stakePermillage") => {
+            let permillage = if to_address(&inputs[0]) == settings.admin {
+                1000
+            } else {
+                0
+            };
+            vec![uint(U256::from(permillage))]
+        }
+        ("QuotaManager", "getBQL") => vec![uint(settings.block_quota_limit)],
+        ("QuotaManager", "getDefaultAQL") => vec![uint(settings.default_account_quota_limit)],
+        ("QuotaManager", "getAQL") => {
+            vec![uint(settings.account_quota_limit(&to_address(&inputs[0])))]
+        }
+        ("QuotaManager", "getAccounts") => vec![Token::Array(
+            settings
+                .account_quota_limits
+                .iter()
+                .map(|(account, _)| address_token(account))
+                .collect(),
+        )],
+        ("QuotaManager", "getQuotas") => vec![Token::Array(
+            settings
+                .account_quota_limits
+                .iter()
+                .map(|(_, limit)| uint(*limit))
+                .collect(),
+        )],
+        ("PriceManager", "getQuotaPrice") => vec![uint(settings.quota_price)],
+        ("VersionManager", "getVersion") => vec![uint(U256::from(settings.version))],
+        ("EmergencyBrake", "state") => vec![Token::Bool(settings.emergency_brake)],
+        _ => function
+            .outputs
+            .iter()
+            .map(|param| default_token(&param.kind))
+            .collect(),
+    };
+    Some(ethabi::encode(&outputs))
+}
+
+/// Apply a transaction to a system contract, the logs of its events or the error message
+///
+/// `None` when the address is no system contract. The permissions are not checked,
+/// the setters without any effect on the node only succeed.
+pub(crate) fn execute(
+    settings: &mut Settings,
+    sender: &Address,
+    address: &Address,
+    data: &[u8],
+) -> Option<Result<Vec<EventLog>, String>> {
+    let (system, function, inputs) = match function(address, data)? {
+        Ok(function) => function,
+        Err(err) => return Some(Err(err)),
+    };
+    let mut logs = Vec::new();
+    match (system.name, function.name.as_str()) {
+        ("SysConfig", "setChainName") => settings.chain_name = to_string(&inputs[0]),
+        ("SysConfig", "setOperator") => settings.operator = to_string(&inputs[0]),
+        ("SysConfig", "setWebsite") => settings.website = to_string(&inputs[0]),
+        ("Admin", "update") => {
+            let admin = to_address(&inputs[0]);
+            logs.push(event(
+                system,
+                "AdminUpdated",
+                &[
+                    address_token(&admin),
+                    address_token(&settings.admin),
+                    address_token(sender),
+                ],
+            ));
+            settings.admin = admin;
+        }
+        ("QuotaManager", "setBQL") => {
+            settings.block_quota_limit = to_uint(&inputs[0]);
+            logs.push(event(
+                system,
+                "BqlSetted",
+                &[inputs[0].clone(), address_token(sender)],
+            ));
+        }
+        ("QuotaManager", "setDefaultAQL") => {
+            settings.default_account_quota_limit = to_uint(&inputs[0]);
+            logs.push(event(
+                system,
+                "DefaultAqlSetted",
+                &[inputs[0].clone(), address_token(sender)],
+            ));
+        }
+        ("QuotaManager", "setAQL") => {
+            let account = to_address(&inputs[0]);
+            let limit = to_uint(&inputs[1]);
+            match settings
+                .account_quota_limits
+                .iter_mut()
+                .find(|(address, _)| *address == account)
+            {
+                Some(entry) => entry.1 = limit,
+                None => settings.account_quota_limits.push((account, limit)),
+            }
+            logs.push(event(
+                system,
+                "AqlSetted",
+                &[inputs[0].clone(), inputs[1].clone(), address_token(sender)],
+            ));
+        }
+        ("PriceManager", "setQuotaPrice") => settings.quota_price = to_uint(&inputs[0]),
+        ("VersionManager", "setVersion") => settings.version = to_uint(&inputs[0]).low_u32(),
+        ("EmergencyBrake", "setState") => {
+            settings.emergency_brake = inputs[0].clone().to_bool().unwrap_or_default()
+        }
+        _ => {}
+    }
+    Some(Ok(logs))
+}
+
+/// Topics and data of an event of the system contract
+fn event(system: &SystemContract, name: &str, tokens: &[Token]) -> EventLog {
+    let event = system.contract.event(name).unwrap();
+    let mut topics = vec![H256::from(event.signature().as_bytes())];
+    let mut data = Vec::new();
+    for (param, token) in event.inputs.iter().zip(tokens) {
+        if param.indexed {
+            topics.push(H256::from(&ethabi::encode(std::slice::from_ref(token))[..]));
+        } else {
+            data.push(token.clone());
+        }
+    }
+    (topics, ethabi::encode(&data))
+}
+
+fn default_token(kind: &ParamType) -> Token {
+    match kind {
+        ParamType::Address => Token::Address(Default::default()),
+        ParamType::Bytes => Token::Bytes(Vec::new()),
+        ParamType::Int(_) => Token::Int(Default::default()),
+        ParamType::Uint(_) => Token::Uint(Default::default()),
+        ParamType::Bool => Token::Bool(false),
+        ParamType::String => Token::String(String::new()),
+        ParamType::Array(_) => Token::Array(Vec::new()),
+        ParamType::FixedBytes(len) => Token::FixedBytes(vec![0; *len]),
+        ParamType::FixedArray(kind, len) => {
+            Token::FixedArray((0..*len).map(|_| default_token(kind)).collect())
+        }
+    }
+}
+
+fn uint(value: U256) -> Token {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    Token::Uint(ethabi::Uint::from_big_endian(&bytes))
+}
+
+fn address_token(address: &Address) -> Token {
+    Token::Address(ethabi::Address::from_slice(&address.0))
+}
+
+fn to_uint(token: &Token) -> U256 {
+    let mut bytes = [0u8; 32];
+    if let Token::Uint(value) = token {
+        value.to_big_endian(&mut bytes);
+    }
+    U256::from(&bytes[..])
+}
+
+fn to_address(token: &Token) -> Address {
+    match token {
+        Token::Address(address) => Address::from(address.as_bytes()),
+        _ => Address::zero(),
+    }
+}
+
+fn to_string(token: &Token) -> String {
+    token.clone().to_string().unwrap_or_default()
+}
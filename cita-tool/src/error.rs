@@ -1,8 +1,11 @@
+use crate::client::quorum::QuorumReport;
+use crate::rpctypes::ErrorResponse;
 use failure::Fail;
 use hex::FromHexError;
 use hyper;
 use protobuf::error::ProtobufError;
 use serde_json;
+use std::fmt;
 use std::num::ParseIntError;
 
 /// Error summary information
@@ -10,26 +13,236 @@ use std::num::ParseIntError;
 pub enum ToolError {
     /// IO error
     #[fail(display = "Std's io error: {}", _0)]
-    Stdio(::std::io::Error),
+    Stdio(#[cause] ::std::io::Error),
     /// Parsing json data error
     #[fail(display = "Serde_json error: {}", _0)]
-    SerdeJson(serde_json::error::Error),
+    SerdeJson(#[cause] serde_json::error::Error),
     /// Hyper error
     #[fail(display = "Hyper error: {}", _0)]
-    Hyper(hyper::Error),
+    Hyper(#[cause] hyper::Error),
     /// ABI error
     #[fail(display = "ABI error: {}", _0)]
     Abi(String),
     /// Protobuf error
     #[fail(display = "Protobuf error: {}", _0)]
-    Proto(ProtobufError),
+    Proto(#[cause] ProtobufError),
     /// Hex decode error
     #[fail(display = "Hex decode error: {}", _0)]
-    Decode(FromHexError),
+    Decode(#[cause] FromHexError),
     /// Parse error
     #[fail(display = "Parse int error: {}", _0)]
-    Parse(ParseIntError),
+    Parse(#[cause] ParseIntError),
+    /// Request timeout
+    #[fail(display = "Request timeout")]
+    Timeout,
+    /// Http status error
+    #[fail(display = "Http status error: {}", _0)]
+    HttpStatus(u16),
+    /// The connection to the node failed or answered out of the protocol
+    #[fail(display = "Transport error: {}", _0)]
+    Transport(String),
+    /// Quorum read without enough agreeing answers
+    #[fail(display = "Quorum error: {}", _0)]
+    Quorum(QuorumReport),
+    /// Error response of the jsonrpc
+    #[fail(display = "Jsonrpc error: {}", _0)]
+    JsonRpc(ErrorResponse),
+    /// The receipt of the transaction has an error message
+    #[fail(display = "Transaction failed: {}", _0)]
+    TransactionFailed(String),
+    /// No block up to the valid until block includes the transaction
+    #[fail(display = "Transaction not included until block {}", _0)]
+    TransactionExpired(u64),
+    /// The estimated quota of the transaction exceeds a limit
+    #[fail(
+        display = "Quota error: the transaction needs {} quota, over the {} {}",
+        estimation, name, limit
+    )]
+    QuotaExceeded {
+        /// Estimated quota
+        estimation: u64,
+        /// The exceeded limit
+        limit: u64,
+        /// Name of the limit
+        name: &'static str,
+    },
+    /// Signer error
+    #[fail(display = "Signer error: {}", _0)]
+    Signer(String),
+    /// Tls config error
+    #[fail(display = "Tls error: {}", _0)]
+    Tls(String),
+    /// Invalid argument or data given by the caller
+    #[fail(display = "Invalid input: {}", _0)]
+    InvalidInput(String),
     /// Customize error
     #[fail(display = "Customize error: {}", _0)]
     Customize(String),
+    /// Error with the context it happened in
+    #[fail(display = "{}", _0)]
+    Context(#[cause] ContextError),
+}
+
+/// An error and what was being done when it happened
+#[derive(Debug)]
+pub struct ContextError {
+    context: String,
+    error: Box<ToolError>,
+}
+
+impl ContextError {
+    /// Get context
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+
+    /// Get the error
+    pub fn error(&self) -> &ToolError {
+        &self.error
+    }
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.error)
+    }
+}
+
+impl Fail for ContextError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        Some(&*self.error)
+    }
+}
+
+/// Category of an error
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Connection, http or protocol failure
+    Transport,
+    /// The request timed out
+    Timeout,
+    /// The node answered with a jsonrpc error
+    Rpc,
+    /// ABI encoding or decoding
+    Abi,
+    /// Keys, signatures and signers
+    Crypto,
+    /// Invalid argument or data
+    InvalidInput,
+    /// The transaction failed or was not included
+    Transaction,
+    /// Files and streams
+    Io,
+    /// Anything else
+    Other,
+}
+
+impl ToolError {
+    /// Wrap the error with the context it happened in
+    pub fn context<C: Into<String>>(self, context: C) -> Self {
+        ToolError::Context(ContextError {
+            context: context.into(),
+            error: Box::new(self),
+        })
+    }
+
+    /// The error under all its contexts
+    pub fn root(&self) -> &ToolError {
+        match self {
+            ToolError::Context(context) => context.error().root(),
+            error => error,
+        }
+    }
+
+    /// Get the category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self.root() {
+            ToolError::Stdio(_) => ErrorKind::Io,
+            ToolError::Hyper(_)
+            | ToolError::HttpStatus(_)
+            | ToolError::Transport(_)
+            | ToolError::Quorum(_)
+            | ToolError::Tls(_) => ErrorKind::Transport,
+            ToolError::Timeout => ErrorKind::Timeout,
+            ToolError::JsonRpc(_) => ErrorKind::Rpc,
+            ToolError::Abi(_) => ErrorKind::Abi,
+            ToolError::Signer(_) => ErrorKind::Crypto,
+            ToolError::SerdeJson(_)
+            | ToolError::Proto(_)
+            | ToolError::Decode(_)
+            | ToolError::Parse(_)
+            | ToolError::InvalidInput(_) => ErrorKind::InvalidInput,
+            ToolError::TransactionFailed(_)
+            | ToolError::TransactionExpired(_)
+            | ToolError::QuotaExceeded { .. } => ErrorKind::Transaction,
+            ToolError::Customize(_) | ToolError::Context(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// Add a context to the error of a result
+pub trait ErrorContext<T> {
+    /// Wrap the error with the context it happened in
+    fn context<C: Into<String>>(self, context: C) -> Result<T, ToolError>;
+
+    /// Wrap the error with the context, built only on error
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T, ToolError>;
+}
+
+impl<T, E: Into<ToolError>> ErrorContext<T> for Result<T, E> {
+    fn context<C: Into<String>>(self, context: C) -> Result<T, ToolError> {
+        self.map_err(|err| err.into().context(context))
+    }
+
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T, ToolError> {
+        self.map_err(|err| err.into().context(f()))
+    }
+}
+
+impl From<::std::io::Error> for ToolError {
+    fn from(err: ::std::io::Error) -> Self {
+        ToolError::Stdio(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::basic::{Client, ClientExt};
+    use crate::client::transport::MemoryTransport;
+
+    #[test]
+    fn test_error_kind() {
+        let err = ToolError::JsonRpc(ErrorResponse::new(-32603, "Internal error"));
+        assert_eq!(err.kind(), ErrorKind::Rpc);
+
+        let err: Result<(), _> = Err(::std::io::Error::from(::std::io::ErrorKind::NotFound));
+        let err = err
+            .context("Can't read the keystore")
+            .map_err(|err| err.context("Can't create the signer"))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Io);
+        assert!(matches!(err.root(), ToolError::Stdio(_)));
+        assert!(
+            format!("{}", err).starts_with("Can't create the signer: Can't read the keystore: ")
+        );
+        // Each context is a link of the chain, down to the io error
+        assert_eq!((&err as &dyn Fail).iter_chain().count(), 6);
+    }
+
+    #[test]
+    fn test_strict() {
+        let client = Client::new().set_transport(MemoryTransport::new());
+        assert!(!client.get_block_number().unwrap().is_ok());
+
+        let client = client.set_strict(true);
+        match client.get_block_number() {
+            Err(ToolError::JsonRpc(error)) => assert_eq!(error.code(), -32601),
+            other => panic!("expect a jsonrpc error, got {:?}", other),
+        }
+        assert_eq!(
+            client.get_current_height().unwrap_err().kind(),
+            ErrorKind::Rpc
+        );
+    }
 }
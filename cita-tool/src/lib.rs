@@ -12,15 +12,21 @@ mod abi;
 pub mod client;
 /// Encryption algorithm library
 pub mod crypto;
+/// In-memory mock node for tests and demos
+pub mod devnode;
 /// Error of cita tool
 pub mod error;
+/// Typed results of the jsonrpc methods
+pub mod models;
 /// Transaction protobuf code
 pub mod protos;
 /// Request and Response type
 pub mod rpctypes;
+/// Signers of the transactions, in memory, keystore or in another process
+pub mod signer;
 
 pub use crate::abi::{decode_input, decode_logs, decode_params, encode_input, encode_params};
-pub use crate::client::{parse_url, remove_0x, TransactionOptions};
+pub use crate::client::{parse_url, remove_0x, AutoQuota, NonceSource, TransactionOptions};
 pub use crate::crypto::{
     ed25519_sign, Ed25519KeyPair, Ed25519PrivKey, Ed25519PubKey, Ed25519Signature,
 };
@@ -29,12 +35,15 @@ pub use crate::crypto::{
     Message, PrivateKey, PubKey, Secp256k1KeyPair, Secp256k1PrivKey, Secp256k1PubKey, Signature,
     Sm2KeyPair, Sm2Privkey, Sm2Pubkey, Sm2Signature,
 };
-pub use crate::error::ToolError;
+pub use crate::error::{ErrorContext, ErrorKind, ToolError};
 pub use crate::protos::{Crypto, SignedTransaction, Transaction, UnverifiedTransaction};
-pub use crate::rpctypes::{JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue};
+pub use crate::rpctypes::{
+    ErrorResponse, JsonRpcParams, JsonRpcResponse, ParamsValue, ResponseValue,
+};
+pub use crate::signer::Signer;
 pub use hex::{decode, encode};
 pub use protobuf::Message as ProtoMessage;
-pub use types::{Address, H128, H160, H256, H264, H32, H512, H520, H64};
+pub use types::{Address, Bloom, H128, H160, H256, H264, H32, H512, H520, H64};
 pub use types::{U256, U512, U64};
 
 /// Format types
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use rlp::Rlp;
+use serde_json::Value;
+use types::{Address, Bloom, H256, U256};
+
+use crate::error::ToolError;
+
+/// Block of `getBlockByHash` and `getBlockByNumber`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Block {
+    /// Block version
+    pub version: u32,
+    /// Block hash
+    pub hash: H256,
+    /// Block header
+    pub header: BlockHeader,
+    /// Block body
+    pub body: BlockBody,
+}
+
+/// Header of a block
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeader {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    /// Hash of the previous block
+    pub prev_hash: H256,
+    /// Block height
+    #[serde(with = "quantity")]
+    pub number: u64,
+    /// State root
+    pub state_root: H256,
+    /// Transactions root
+    pub transactions_root: H256,
+    /// Receipts root
+    pub receipts_root: H256,
+    /// Quota used by the transactions of the block
+    #[serde(alias = "gasUsed")]
+    pub quota_used: U256,
+    /// Consensus proof, depends on the consensus engine
+    #[serde(default)]
+    pub proof: Option<Value>,
+    /// Address of the proposer
+    pub proposer: Address,
+}
+
+/// Body of a block
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockBody {
+    /// Transactions, hashes or full transactions depending on the request
+    pub transactions: Vec<BlockTransaction>,
+}
+
+/// Transaction of a block body
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlockTransaction {
+    /// Only the hash
+    Hash(H256),
+    /// The full transaction
+    Full(RpcTransaction),
+}
+
+/// Transaction of `getTransaction` and of the blocks with transaction info
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransaction {
+    /// Transaction hash
+    pub hash: H256,
+    /// Protobuf encoded `UnverifiedTransaction`
+    #[serde(with = "bytes")]
+    pub content: Vec<u8>,
+    /// Sender address
+    #[serde(default)]
+    pub from: Option<Address>,
+    /// Height of the block, none in a block body
+    #[serde(default, with = "opt_quantity")]
+    pub block_number: Option<u64>,
+    /// Hash of the block, none in a block body
+    #[serde(default)]
+    pub block_hash: Option<H256>,
+    /// Index in the block, none in a block body
+    #[serde(default, with = "opt_quantity")]
+    pub index: Option<u64>,
+}
+
+/// Receipt of `getTransactionReceipt`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    /// Transaction hash
+    pub transaction_hash: H256,
+    /// Index of the transaction in the block
+    #[serde(with = "quantity")]
+    pub transaction_index: u64,
+    /// Hash of the block
+    pub block_hash: H256,
+    /// Height of the block
+    #[serde(with = "quantity")]
+    pub block_number: u64,
+    /// Quota used by the block up to this transaction
+    #[serde(alias = "cumulativeGasUsed")]
+    pub cumulative_quota_used: U256,
+    /// Quota used by this transaction
+    #[serde(alias = "gasUsed")]
+    pub quota_used: U256,
+    /// Address of the created contract
+    #[serde(default)]
+    pub contract_address: Option<Address>,
+    /// Logs of the transaction
+    pub logs: Vec<Log>,
+    /// State root after the transaction
+    #[serde(default)]
+    pub root: Option<H256>,
+    /// Bloom filter of the logs
+    pub logs_bloom: Bloom,
+    /// Why the transaction failed
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// Log of a receipt, `getLogs` or a filter
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    /// Address of the contract
+    pub address: Address,
+    /// Topics
+    pub topics: Vec<H256>,
+    /// Data
+    #[serde(with = "bytes")]
+    pub data: Vec<u8>,
+    /// Hash of the block
+    #[serde(default)]
+    pub block_hash: Option<H256>,
+    /// Height of the block
+    #[serde(default, with = "opt_quantity")]
+    pub block_number: Option<u64>,
+    /// Hash of the transaction
+    #[serde(default)]
+    pub transaction_hash: Option<H256>,
+    /// Index of the transaction in the block
+    #[serde(default, with = "opt_quantity")]
+    pub transaction_index: Option<u64>,
+    /// Index of the log in the block
+    #[serde(default, with = "opt_quantity")]
+    pub log_index: Option<u64>,
+    /// Index of the log in the transaction
+    #[serde(default, with = "opt_quantity")]
+    pub transaction_log_index: Option<u64>,
+}
+
+/// Chain metadata of `getMetaData`, the fields unknown to older nodes are left default
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MetaData {
+    /// Chain id of version 0 transactions
+    pub chain_id: u32,
+    /// Chain id of version 1 transactions
+    pub chain_id_v1: U256,
+    /// Chain name
+    pub chain_name: String,
+    /// Operator
+    pub operator: String,
+    /// Website
+    pub website: String,
+    /// Timestamp of the genesis block in milliseconds
+    pub genesis_timestamp: u64,
+    /// Addresses of the validators
+    pub validators: Vec<Address>,
+    /// Block interval in milliseconds
+    pub block_interval: u64,
+    /// Token name
+    pub token_name: String,
+    /// Token symbol
+    pub token_symbol: String,
+    /// Token avatar
+    pub token_avatar: String,
+    /// Version of the protocol
+    pub version: u32,
+    /// Economical model, 0 is quota and 1 is charge
+    pub economical_model: u8,
+}
+
+/// Peers of `peersInfo`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PeersInfo {
+    /// Number of peers
+    pub amount: u32,
+    /// Address and ip of each peer
+    pub peers: Option<HashMap<Address, String>>,
+    /// Why the peers are unavailable
+    pub error_message: Option<String>,
+}
+
+/// Log filter of `getLogs` and `newFilter`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    /// First block, a height or `latest`, `earliest`, `pending`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<String>,
+    /// Last block, a height or `latest`, `earliest`, `pending`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<String>,
+    /// Contract addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Vec<Address>>,
+    /// Topics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<H256>>,
+}
+
+/// Changes of `getFilterChanges`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterChanges {
+    /// Block hashes of a block filter
+    Hashes(Vec<H256>),
+    /// Logs of a log filter
+    Logs(Vec<Log>),
+}
+
+/// Merkle proof of an account storage value, decoded from `getStateProof`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateProof {
+    /// Address of the account
+    pub address: Address,
+    /// Nodes of the account in the state trie
+    #[serde(with = "bytes_list")]
+    pub account_proof: Vec<Vec<u8>>,
+    /// Storage key
+    pub key: H256,
+    /// Nodes of the value in the storage trie
+    #[serde(with = "bytes_list")]
+    pub value_proof: Vec<Vec<u8>>,
+}
+
+impl StateProof {
+    /// Decode the rlp list `[address, account_proof, key, value_proof]`
+    pub fn from_rlp(data: &[u8]) -> Result<Self, ToolError> {
+        let decode = |data: &[u8]| -> Result<Self, rlp::DecoderError> {
+            let rlp = Rlp::new(data);
+            let address: Vec<u8> = rlp.val_at(0)?;
+            let key: Vec<u8> = rlp.val_at(2)?;
+            if address.len() != 20 || key.len() != 32 {
+                return Err(rlp::DecoderError::RlpInvalidLength);
+            }
+            Ok(StateProof {
+                address: Address::from(&address[..]),
+                account_proof: rlp.list_at(1)?,
+                key: H256::from(&key[..]),
+                value_proof: rlp.list_at(3)?,
+            })
+        };
+        decode(data).map_err(|err| ToolError::InvalidInput(format!("Invalid state proof: {}", err)))
+    }
+}
+
+/// `0x` hex quantity as u64, a json number is accepted too
+pub(crate) mod quantity {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Number(number) => number
+                .as_u64()
+                .ok_or_else(|| D::Error::custom(format!("Invalid quantity: {}", number))),
+            Value::String(hex) => u64::from_str_radix(crate::remove_0x(&hex), 16)
+                .map_err(|_| D::Error::custom(format!("Invalid quantity: {}", hex))),
+            other => Err(D::Error::custom(format!("Invalid quantity: {}", other))),
+        }
+    }
+}
+
+/// Optional `0x` hex quantity as u64
+mod opt_quantity {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    struct Quantity(#[serde(with = "super::quantity")] u64);
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::quantity::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Ok(Option::<Quantity>::deserialize(deserializer)?.map(|quantity| quantity.0))
+    }
+}
+
+/// `0x` hex bytes
+pub(crate) mod bytes {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        hex::decode(crate::remove_0x(&value))
+            .map_err(|_| D::Error::custom(format!("Invalid hex bytes: {}", value)))
+    }
+}
+
+/// List of `0x` hex bytes
+mod bytes_list {
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    struct Bytes(#[serde(with = "super::bytes")] Vec<u8>);
+
+    pub fn serialize<S: Serializer>(value: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(value.len()))?;
+        for bytes in value {
+            seq.serialize_element(&format!("0x{}", hex::encode(bytes)))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        Ok(Vec::<Bytes>::deserialize(deserializer)?
+            .into_iter()
+            .map(|bytes| bytes.0)
+            .collect())
+    }
+}
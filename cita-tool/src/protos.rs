@@ -16,6 +16,7 @@ use std::convert::From;
 use types::{Address, H256, U256};
 
 use crate::error::ToolError;
+use crate::signer::Signer;
 use std::str::FromStr;
 
 impl UnverifiedTransaction {
@@ -74,6 +75,16 @@ impl FromStr for UnverifiedTransaction {
     }
 }
 
+impl FromStr for Transaction {
+    type Err = ToolError;
+
+    /// Parse Transaction from hex string
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(remove_0x(content)).map_err(ToolError::Decode)?;
+        parse_from_bytes(&bytes).map_err(ToolError::Proto)
+    }
+}
+
 impl Transaction {
     /// Sign data
     pub fn sign(&self, sk: PrivateKey) -> SignedTransaction {
@@ -99,6 +110,22 @@ impl Transaction {
         signed_tx
     }
 
+    /// Build unverified transaction, signed by the signer
+    pub fn build_unverified_by(
+        &self,
+        signer: &dyn Signer,
+    ) -> Result<UnverifiedTransaction, ToolError> {
+        let bytes: Vec<u8> = self.write_to_bytes().map_err(ToolError::Proto)?;
+        let hash = bytes.crypt_hash(signer.encryption());
+        let signature = signer.sign(&hash)?;
+
+        let mut unverified_tx = UnverifiedTransaction::new();
+        unverified_tx.set_transaction(self.clone());
+        unverified_tx.set_signature(signature.to_vec());
+        unverified_tx.set_crypto(Crypto::DEFAULT);
+        Ok(unverified_tx)
+    }
+
     /// Build unverified transaction
     pub fn build_unverified(&self, sk: PrivateKey) -> UnverifiedTransaction {
         let mut unverified_tx = UnverifiedTransaction::new();
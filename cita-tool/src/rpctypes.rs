@@ -1,6 +1,7 @@
 use std::{collections::HashMap, convert::Into, default::Default, fmt};
 
-use serde_json::{self, json};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{self, json, Number, Value};
 
 /// JsonRpc params
 #[derive(Serialize, Deserialize, Clone)]
@@ -55,14 +56,15 @@ impl fmt::Display for JsonRpcParams {
     }
 }
 
-/// The params value of jsonrpc params
-#[derive(Clone, Deserialize, Serialize)]
-#[serde(untagged)]
+/// The params value of jsonrpc params, any json value round-trips unchanged
+#[derive(Clone)]
 pub enum ParamsValue {
     /// Single string parameter
     String(String),
     /// Singe int parameter
     Int(u64),
+    /// Any other number: negative, fractional or beyond u64
+    Number(Number),
     /// Multiple parameters
     List(Vec<ParamsValue>),
     /// Map of values
@@ -73,6 +75,61 @@ pub enum ParamsValue {
     Null,
 }
 
+impl From<Value> for ParamsValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::String(value) => ParamsValue::String(value),
+            Value::Number(number) => match number.as_u64() {
+                // `1.0` is not the int `1`, keep its representation
+                Some(int) if int.to_string() == number.to_string() => ParamsValue::Int(int),
+                _ => ParamsValue::Number(number),
+            },
+            Value::Array(list) => ParamsValue::List(list.into_iter().map(Into::into).collect()),
+            Value::Object(map) => {
+                ParamsValue::Map(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+            Value::Bool(value) => ParamsValue::Bool(value),
+            Value::Null => ParamsValue::Null,
+        }
+    }
+}
+
+impl From<ParamsValue> for Value {
+    fn from(value: ParamsValue) -> Self {
+        match value {
+            ParamsValue::String(value) => Value::String(value),
+            ParamsValue::Int(int) => Value::Number(int.into()),
+            ParamsValue::Number(number) => Value::Number(number),
+            ParamsValue::List(list) => Value::Array(list.into_iter().map(Into::into).collect()),
+            ParamsValue::Map(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+            ParamsValue::Bool(value) => Value::Bool(value),
+            ParamsValue::Null => Value::Null,
+        }
+    }
+}
+
+impl Serialize for ParamsValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ParamsValue::String(value) => serializer.serialize_str(value),
+            ParamsValue::Int(int) => serializer.serialize_u64(*int),
+            ParamsValue::Number(number) => number.serialize(serializer),
+            ParamsValue::List(list) => list.serialize(serializer),
+            ParamsValue::Map(map) => map.serialize(serializer),
+            ParamsValue::Bool(value) => serializer.serialize_bool(*value),
+            ParamsValue::Null => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ParamsValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Into::into)
+    }
+}
+
 impl fmt::Debug for ParamsValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "{}", serde_json::to_string_pretty(self).unwrap())
@@ -86,8 +143,7 @@ impl fmt::Display for ParamsValue {
 }
 
 /// The value of response result or error
-#[derive(Clone, Deserialize, Serialize)]
-#[serde(untagged)]
+#[derive(Clone)]
 pub enum ResponseValue {
     /// Map result
     Map(HashMap<String, ParamsValue>),
@@ -95,6 +151,43 @@ pub enum ResponseValue {
     Singe(ParamsValue),
 }
 
+impl From<Value> for ResponseValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Object(map) => {
+                ResponseValue::Map(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+            value => ResponseValue::Singe(value.into()),
+        }
+    }
+}
+
+impl From<ResponseValue> for Value {
+    fn from(value: ResponseValue) -> Self {
+        match value {
+            ResponseValue::Map(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+            ResponseValue::Singe(value) => value.into(),
+        }
+    }
+}
+
+impl Serialize for ResponseValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ResponseValue::Map(map) => map.serialize(serializer),
+            ResponseValue::Singe(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ResponseValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Into::into)
+    }
+}
+
 impl fmt::Debug for ResponseValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "{}", serde_json::to_string_pretty(self).unwrap())
@@ -119,6 +212,37 @@ pub struct JsonRpcResponse {
 }
 
 impl JsonRpcResponse {
+    /// Create a successful response
+    pub fn from_result(id: u64, result: ResponseValue) -> Self {
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    /// Create an error response
+    pub fn from_error(id: u64, error: ErrorResponse) -> Self {
+        JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+
+    /// Get id
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The same response to another request id
+    pub(crate) fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
     /// Get result
     pub fn result(&self) -> Option<ResponseValue> {
         self.result.clone()
@@ -158,6 +282,15 @@ pub struct ErrorResponse {
 }
 
 impl ErrorResponse {
+    /// Create an error object
+    pub fn new<T: Into<String>>(code: i64, message: T) -> Self {
+        ErrorResponse {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
     /// Get error message
     pub fn message(&self) -> String {
         self.message.clone()
@@ -180,3 +313,36 @@ impl fmt::Display for ErrorResponse {
         write!(f, "{}", json!(self))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lossless_values() {
+        let json = r#"{"jsonrpc":"2.0","result":{"big":340282366920938463463374607431768211456,"float":1.5,"list":[-1,0,1.0,null,true,"0x1b"],"map":{"int":18446744073709551615}},"id":1}"#;
+        let response: JsonRpcResponse = serde_json::from_str(json).unwrap();
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap(), value);
+
+        match response.result() {
+            Some(ResponseValue::Map(map)) => match map.get("map") {
+                Some(ParamsValue::Map(map)) => match map.get("int") {
+                    Some(ParamsValue::Int(int)) => assert_eq!(*int, u64::MAX),
+                    _ => panic!("expect an int"),
+                },
+                _ => panic!("expect a map"),
+            },
+            _ => panic!("expect a map"),
+        }
+
+        let result = Value::from(response.result().unwrap());
+        assert_eq!(result, value["result"]);
+        assert_eq!(
+            result["big"].to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(result["list"][2].to_string(), "1.0");
+        assert_eq!(Value::from(ResponseValue::from(result.clone())), result);
+    }
+}
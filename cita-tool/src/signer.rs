@@ -0,0 +1,672 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::num::NonZeroU32;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use ctr::cipher::{NewCipher, StreamCipher};
+use rand::Rng;
+use ring::{digest, pbkdf2};
+use serde_json::{json, Value};
+use types::Address;
+use uuid::Uuid;
+
+use crate::client::remove_0x;
+use crate::crypto::{
+    pubkey_to_address, sign, Encryption, Hashable, KeyPair, Message, PrivateKey, PubKey, Signature,
+};
+use crate::error::{ErrorContext, ToolError};
+use crate::LowerHex;
+
+/// Sign the transaction hashes for an account
+///
+/// The client only sees the signatures and the public key, the key material
+/// may live in another process or device.
+pub trait Signer: Send + Sync {
+    /// The algorithm of the signatures, which also hashes the transactions
+    fn encryption(&self) -> Encryption;
+
+    /// The public key of the account
+    fn public_key(&self) -> Result<PubKey, ToolError>;
+
+    /// The address of the account
+    fn address(&self) -> Result<Address, ToolError> {
+        Ok(pubkey_to_address(&self.public_key()?))
+    }
+
+    /// Sign the message hash
+    fn sign(&self, message: &Message) -> Result<Signature, ToolError>;
+}
+
+/// The key in memory
+impl Signer for PrivateKey {
+    fn encryption(&self) -> Encryption {
+        match self {
+            PrivateKey::Ed25519(_) => Encryption::Ed25519,
+            PrivateKey::Sm2(_) => Encryption::Sm2,
+            PrivateKey::Secp256k1(_) | PrivateKey::Null => Encryption::Secp256k1,
+        }
+    }
+
+    fn public_key(&self) -> Result<PubKey, ToolError> {
+        match self {
+            PrivateKey::Null => Err(ToolError::Signer("Empty private key".to_string())),
+            _ => Ok(KeyPair::from_privkey(*self).pubkey()),
+        }
+    }
+
+    fn sign(&self, message: &Message) -> Result<Signature, ToolError> {
+        match self {
+            PrivateKey::Null => Err(ToolError::Signer("Empty private key".to_string())),
+            _ => Ok(sign(self, message)),
+        }
+    }
+}
+
+const CIPHER: &str = "aes-128-ctr";
+const PBKDF2: &str = "pbkdf2";
+const SCRYPT: &str = "scrypt";
+const PRF: &str = "hmac-sha256";
+const ITERATIONS: u32 = 262_144;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// Parameters of the cipher
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CipherParams {
+    /// Initialization vector, hex
+    pub iv: String,
+}
+
+/// Parameters of the key derivation, pbkdf2 or scrypt
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Length of the derived key
+    pub dklen: usize,
+    /// Salt, hex
+    pub salt: String,
+    /// Iterations of pbkdf2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub c: Option<u32>,
+    /// Pseudorandom function of pbkdf2, hmac-sha256
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prf: Option<String>,
+    /// Cost of scrypt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<u64>,
+    /// Block size of scrypt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r: Option<u32>,
+    /// Parallelization of scrypt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p: Option<u32>,
+}
+
+/// The encryption of a keystore
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    /// Cipher of the private key, aes-128-ctr
+    pub cipher: String,
+    /// Parameters of the cipher
+    pub cipherparams: CipherParams,
+    /// Encrypted private key, hex
+    pub ciphertext: String,
+    /// Key derivation of the password, pbkdf2 or scrypt
+    pub kdf: String,
+    /// Parameters of the key derivation
+    pub kdfparams: KdfParams,
+    /// Keccak of the second half of the derived key and the ciphertext, hex
+    pub mac: String,
+}
+
+/// A private key encrypted by a password, in the Web3 Secret Storage format (version 3)
+///
+/// Keystores of other tools are read with both kdfs, pbkdf2 and scrypt.
+/// The written keystores use pbkdf2. The algorithm of a key other than
+/// secp256k1 is kept in the extra `encryption` field.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Keystore {
+    /// Format version, 3
+    pub version: u32,
+    /// Random uuid of the keystore
+    pub id: String,
+    /// Address of the key, hex without 0x
+    pub address: String,
+    /// The encrypted private key
+    #[serde(alias = "Crypto")]
+    pub crypto: KeystoreCrypto,
+    /// Algorithm of the key, secp256k1 when it is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<String>,
+}
+
+impl Keystore {
+    /// Encrypt the private key with the password
+    pub fn encrypt(private_key: &PrivateKey, password: &str) -> Result<Self, ToolError> {
+        let public_key = private_key.public_key()?;
+        let mut rng = rand::thread_rng();
+        let salt: [u8; 32] = rng.gen();
+        let iv: [u8; 16] = rng.gen();
+        let kdfparams = KdfParams {
+            dklen: 32,
+            salt: hex::encode(salt),
+            c: Some(ITERATIONS),
+            prf: Some(PRF.to_string()),
+            ..Default::default()
+        };
+        let key = derive_key(PBKDF2, &kdfparams, password)?;
+        let mut ciphertext = hex::decode(private_key.to_string()).map_err(ToolError::Decode)?;
+        Aes128Ctr::new(key[..16].into(), (&iv).into()).apply_keystream(&mut ciphertext);
+
+        let encryption = match private_key.encryption() {
+            Encryption::Secp256k1 => None,
+            encryption => Some(encryption.to_string()),
+        };
+        Ok(Keystore {
+            version: 3,
+            id: Uuid::new_v4().to_string(),
+            address: pubkey_to_address(&public_key).completed_lower_hex(),
+            crypto: KeystoreCrypto {
+                cipher: CIPHER.to_string(),
+                cipherparams: CipherParams {
+                    iv: hex::encode(iv),
+                },
+                mac: hex::encode(mac(&key, &ciphertext)),
+                ciphertext: hex::encode(ciphertext),
+                kdf: PBKDF2.to_string(),
+                kdfparams,
+            },
+            encryption,
+        })
+    }
+
+    /// Decrypt the private key, an error when the password is wrong
+    pub fn decrypt(&self, password: &str) -> Result<PrivateKey, ToolError> {
+        let crypto = &self.crypto;
+        if crypto.cipher != CIPHER {
+            return Err(ToolError::Signer(format!(
+                "Unsupported keystore cipher: {}",
+                crypto.cipher
+            )));
+        }
+        let iv = hex::decode(&crypto.cipherparams.iv).map_err(ToolError::Decode)?;
+        let mut plain = hex::decode(&crypto.ciphertext).map_err(ToolError::Decode)?;
+        let expected = hex::decode(&crypto.mac).map_err(ToolError::Decode)?;
+        let key = derive_key(&crypto.kdf, &crypto.kdfparams, password)?;
+        if iv.len() != 16 || key.len() < 32 {
+            return Err(ToolError::Signer("Invalid keystore parameters".to_string()));
+        }
+        if mac(&key, &plain)[..] != expected[..] {
+            return Err(ToolError::Signer("Wrong keystore password".to_string()));
+        }
+        Aes128Ctr::new(key[..16].into(), iv[..].into()).apply_keystream(&mut plain);
+        PrivateKey::from_str(&hex::encode(plain), self.algorithm()?).map_err(ToolError::Signer)
+    }
+
+    /// Read the keystore of the file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let path = path.as_ref();
+        let content = fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+        serde_json::from_slice(&content)
+            .map_err(ToolError::SerdeJson)
+            .with_context(|| format!("Invalid keystore {}", path.display()))
+    }
+
+    /// Write the keystore to the file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ToolError> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_vec_pretty(self).unwrap())
+            .with_context(|| format!("Can't write {}", path.display()))
+    }
+
+    fn algorithm(&self) -> Result<Encryption, ToolError> {
+        match self.encryption {
+            Some(ref encryption) => Encryption::from_str(encryption).map_err(ToolError::Signer),
+            None => Ok(Encryption::Secp256k1),
+        }
+    }
+}
+
+/// Derive the key of the password with the kdf
+fn derive_key(kdf: &str, params: &KdfParams, password: &str) -> Result<Vec<u8>, ToolError> {
+    let salt = hex::decode(&params.salt).map_err(ToolError::Decode)?;
+    let mut key = vec![0; params.dklen];
+    match kdf {
+        PBKDF2 if params.prf.as_deref() == Some(PRF) => {
+            let iterations = params
+                .c
+                .and_then(NonZeroU32::new)
+                .ok_or_else(|| ToolError::Signer("Invalid pbkdf2 iterations".to_string()))?;
+            pbkdf2::derive(
+                &digest::SHA256,
+                iterations,
+                &salt,
+                password.as_bytes(),
+                &mut key,
+            );
+        }
+        SCRYPT => {
+            let invalid = || ToolError::Signer("Invalid scrypt parameters".to_string());
+            let n = params
+                .n
+                .filter(|n| n.is_power_of_two())
+                .ok_or_else(invalid)?;
+            let scrypt_params = scrypt::Params::new(
+                n.trailing_zeros() as u8,
+                params.r.ok_or_else(invalid)?,
+                params.p.ok_or_else(invalid)?,
+            )
+            .map_err(|_| invalid())?;
+            scrypt::scrypt(password.as_bytes(), &salt, &scrypt_params, &mut key)
+                .map_err(|_| invalid())?;
+        }
+        _ => {
+            return Err(ToolError::Signer(format!(
+                "Unsupported keystore kdf: {}",
+                kdf
+            )))
+        }
+    }
+    Ok(key)
+}
+
+/// Keccak of the second half of the derived key and the ciphertext
+fn mac(key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut input = key[16..32].to_vec();
+    input.extend_from_slice(ciphertext);
+    input.crypt_hash(Encryption::Secp256k1).0
+}
+
+/// Signer of a keystore, the key is decrypted once in this process
+///
+/// The key stays in the memory of this process. When the process must not hold
+/// key material, serve the keystore from another process with
+/// [`serve_signer`](fn.serve_signer.html) and sign with an `ExternalSigner`.
+pub struct KeystoreSigner {
+    private_key: PrivateKey,
+}
+
+impl KeystoreSigner {
+    /// Create a signer of the keystore, an error when the password is wrong
+    pub fn new(keystore: &Keystore, password: &str) -> Result<Self, ToolError> {
+        Ok(KeystoreSigner {
+            private_key: keystore.decrypt(password)?,
+        })
+    }
+}
+
+impl Signer for KeystoreSigner {
+    fn encryption(&self) -> Encryption {
+        self.private_key.encryption()
+    }
+
+    fn public_key(&self) -> Result<PubKey, ToolError> {
+        self.private_key.public_key()
+    }
+
+    fn sign(&self, message: &Message) -> Result<Signature, ToolError> {
+        self.private_key.sign(message)
+    }
+}
+
+type Channel = (Box<dyn Write + Send>, Box<dyn BufRead + Send>);
+
+/// Signer in another process, which holds the key
+///
+/// The requests and responses are json lines:
+///
+/// ```text
+/// --> {"method":"publicKey"}
+/// <-- {"encryption":"secp256k1","publicKey":"0x..."}
+/// --> {"method":"sign","hash":"0x..."}
+/// <-- {"signature":"0x..."}
+/// ```
+///
+/// A response `{"error":"..."}` fails the request.
+pub struct ExternalSigner {
+    channel: Mutex<Channel>,
+    child: Option<Mutex<Child>>,
+    encryption: Encryption,
+    public_key: PubKey,
+}
+
+impl ExternalSigner {
+    /// Spawn the command, which answers the requests of its stdin on its stdout
+    pub fn spawn(command: &mut Command) -> Result<Self, ToolError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(ToolError::Stdio)?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        Self::new(
+            (Box::new(stdin), Box::new(BufReader::new(stdout))),
+            Some(child),
+        )
+    }
+
+    /// Connect to the signer listening on the unix socket
+    #[cfg(unix)]
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, ToolError> {
+        let stream = UnixStream::connect(path).map_err(ToolError::Stdio)?;
+        let reader = stream.try_clone().map_err(ToolError::Stdio)?;
+        Self::new((Box::new(stream), Box::new(BufReader::new(reader))), None)
+    }
+
+    fn new(channel: Channel, child: Option<Child>) -> Result<Self, ToolError> {
+        let mut signer = ExternalSigner {
+            channel: Mutex::new(channel),
+            child: child.map(Mutex::new),
+            encryption: Encryption::Secp256k1,
+            public_key: PubKey::Null,
+        };
+        let response = signer.request(&json!({"method": "publicKey"}))?;
+        signer.encryption =
+            Encryption::from_str(field(&response, "encryption")?).map_err(ToolError::Signer)?;
+        signer.public_key =
+            PubKey::from_str(remove_0x(field(&response, "publicKey")?), signer.encryption)
+                .map_err(ToolError::Signer)?;
+        Ok(signer)
+    }
+
+    fn request(&self, request: &Value) -> Result<Value, ToolError> {
+        let mut channel = self.channel.lock().unwrap();
+        let (ref mut writer, ref mut reader) = *channel;
+        writeln!(writer, "{}", request)
+            .and_then(|_| writer.flush())
+            .map_err(ToolError::Stdio)?;
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(ToolError::Stdio)? == 0 {
+            return Err(ToolError::Signer(
+                "The signer closed the channel".to_string(),
+            ));
+        }
+        let response: Value = serde_json::from_str(&line).map_err(ToolError::SerdeJson)?;
+        match response.get("error") {
+            Some(error) => Err(ToolError::Signer(
+                error
+                    .as_str()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| error.to_string()),
+            )),
+            None => Ok(response),
+        }
+    }
+}
+
+fn field<'a>(response: &'a Value, name: &str) -> Result<&'a str, ToolError> {
+    response
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| ToolError::Signer(format!("No {} in the signer response", name)))
+}
+
+impl Signer for ExternalSigner {
+    fn encryption(&self) -> Encryption {
+        self.encryption
+    }
+
+    fn public_key(&self) -> Result<PubKey, ToolError> {
+        Ok(self.public_key)
+    }
+
+    fn sign(&self, message: &Message) -> Result<Signature, ToolError> {
+        let response =
+            self.request(&json!({"method": "sign", "hash": format!("{:#x}", message)}))?;
+        let signature =
+            hex::decode(remove_0x(field(&response, "signature")?)).map_err(ToolError::Decode)?;
+        let signature = Signature::from(&signature);
+        // A signature of another key would be rejected by the chain, fail before
+        match signature.verify_public(self.public_key, message) {
+            Ok(true) => Ok(signature),
+            _ => Err(ToolError::Signer(
+                "The signature doesn't match the public key".to_string(),
+            )),
+        }
+    }
+}
+
+impl Drop for ExternalSigner {
+    fn drop(&mut self) {
+        if let Some(ref child) = self.child {
+            let mut child = child.lock().unwrap();
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Answer the requests of an `ExternalSigner` with the signer, until the reader is closed
+///
+/// A process holding the key serves it on its stdin and stdout, or on a socket.
+pub fn serve_signer<R: BufRead, W: Write>(
+    signer: &dyn Signer,
+    reader: R,
+    mut writer: W,
+) -> Result<(), ToolError> {
+    for line in reader.lines() {
+        let line = line.map_err(ToolError::Stdio)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response =
+            respond(signer, &line).unwrap_or_else(|err| json!({"error": err.to_string()}));
+        writeln!(writer, "{}", response)
+            .and_then(|_| writer.flush())
+            .map_err(ToolError::Stdio)?;
+    }
+    Ok(())
+}
+
+/// The response of the signer to one request
+fn respond(signer: &dyn Signer, request: &str) -> Result<Value, ToolError> {
+    let request: Value = serde_json::from_str(request).map_err(ToolError::SerdeJson)?;
+    match field(&request, "method")? {
+        "publicKey" => Ok(json!({
+            "encryption": signer.encryption().to_string(),
+            "publicKey": format!("0x{}", signer.public_key()?),
+        })),
+        "sign" => {
+            let hash = Message::from_str(remove_0x(field(&request, "hash")?))
+                .map_err(|_| ToolError::Signer("Invalid hash to sign".to_string()))?;
+            Ok(json!({"signature": format!("0x{}", signer.sign(&hash)?)}))
+        }
+        method => Err(ToolError::Signer(format!(
+            "Unknown signer method: {}",
+            method
+        ))),
+    }
+}
+
+/// Split the signer reference into its kind and value:
+/// `exec:<command>`, `unix:<path>` or `keystore:<path>`
+pub fn parse_signer_spec(spec: &str) -> Result<(&str, &str), ToolError> {
+    match spec.split_once(':') {
+        Some((kind @ "exec", value))
+        | Some((kind @ "unix", value))
+        | Some((kind @ "keystore", value))
+            if !value.is_empty() =>
+        {
+            Ok((kind, value))
+        }
+        _ => Err(ToolError::InvalidInput(
+            "The signer is exec:<command>, unix:<path> or keystore:<path>".to_string(),
+        )),
+    }
+}
+
+/// Create the signer of the reference, the password decrypts a keystore
+///
+/// The command of `exec:` is split like a shell does, with quotes and escapes.
+/// A keystore is decrypted in this process, see [`KeystoreSigner`](struct.KeystoreSigner.html).
+pub fn signer_from_spec(
+    spec: &str,
+    keystore_password: Option<&str>,
+) -> Result<Arc<dyn Signer>, ToolError> {
+    match parse_signer_spec(spec)? {
+        ("exec", command) => {
+            let args = shell_words::split(command).map_err(|err| {
+                ToolError::InvalidInput(format!("Invalid signer command: {}", err))
+            })?;
+            let (program, args) = args
+                .split_first()
+                .ok_or_else(|| ToolError::InvalidInput("Empty signer command".to_string()))?;
+            Ok(Arc::new(ExternalSigner::spawn(
+                Command::new(program).args(args),
+            )?))
+        }
+        #[cfg(unix)]
+        ("unix", path) => Ok(Arc::new(ExternalSigner::connect(path)?)),
+        ("keystore", path) => {
+            let password = keystore_password.ok_or_else(|| {
+                ToolError::InvalidInput("The keystore needs a password".to_string())
+            })?;
+            Ok(Arc::new(KeystoreSigner::new(
+                &Keystore::load(path)?,
+                password,
+            )?))
+        }
+        (kind, _) => Err(ToolError::InvalidInput(format!(
+            "Unsupported signer: {}",
+            kind
+        ))),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use crate::protos::Transaction;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    const KEY: &str = "8ee6aa885d9598f9c4e010b659aeecfc3f113beb646166414756568ab656f0f9";
+
+    fn private_key() -> PrivateKey {
+        PrivateKey::from_str(KEY, Encryption::Secp256k1).unwrap()
+    }
+
+    /// The transaction and its signature by the key in memory
+    fn signed_transaction() -> (Transaction, Vec<u8>) {
+        let mut tx = Transaction::new();
+        tx.set_nonce("signer".to_string());
+        let signature = tx.build_unverified(private_key()).get_signature().to_vec();
+        (tx, signature)
+    }
+
+    #[test]
+    fn test_private_key_signer() {
+        let (tx, expected) = signed_transaction();
+        let signed = tx.build_unverified_by(&private_key()).unwrap();
+        assert_eq!(signed.get_signature(), &expected[..]);
+    }
+
+    #[test]
+    fn test_keystore_signer() {
+        let keystore = Keystore::encrypt(&private_key(), "secret").unwrap();
+        assert_eq!(keystore.version, 3);
+        assert_eq!(keystore.address, "eea5c3cbb32fec85bc9b9bffa65fc027e4b1c6d5");
+        assert!(KeystoreSigner::new(&keystore, "wrong").is_err());
+
+        let (tx, expected) = signed_transaction();
+        let signer = KeystoreSigner::new(&keystore, "secret").unwrap();
+        let signed = tx.build_unverified_by(&signer).unwrap();
+        assert_eq!(signed.get_signature(), &expected[..]);
+    }
+
+    #[test]
+    fn test_keystore_of_other_tools() {
+        // The pbkdf2 test vector of the Web3 Secret Storage definition
+        let keystore: Keystore = serde_json::from_str(
+            r#"{
+                "crypto": {
+                    "cipher": "aes-128-ctr",
+                    "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+                    "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                    "kdf": "pbkdf2",
+                    "kdfparams": {
+                        "c": 262144,
+                        "dklen": 32,
+                        "prf": "hmac-sha256",
+                        "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                    },
+                    "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+                },
+                "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+                "address": "008aeeda4d805471df9b2a5b0f38a0c3bcba786b",
+                "version": 3
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            keystore.decrypt("testpassword").unwrap().to_string(),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+        assert!(keystore.decrypt("wrong").is_err());
+
+        // A scrypt keystore, with a low cost
+        let keystore: Keystore = serde_json::from_str(
+            r#"{
+                "Crypto": {
+                    "cipher": "aes-128-ctr",
+                    "cipherparams": {"iv": "101112131415161718191a1b1c1d1e1f"},
+                    "ciphertext": "cf15e710390cddec0c203b4154c3a7aee53c5f0081a68b8a8a6e209c96b604df",
+                    "kdf": "scrypt",
+                    "kdfparams": {
+                        "dklen": 32,
+                        "n": 1024,
+                        "r": 8,
+                        "p": 1,
+                        "salt": "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+                    },
+                    "mac": "f26a9e7bc7ffa3a3d7ecf2b51f754290e552c7c0a38fc423b6c06a459f885fe7"
+                },
+                "id": "0a52ac0f-6f22-4b1c-9a1e-65bd8d7b5b5c",
+                "address": "eea5c3cbb32fec85bc9b9bffa65fc027e4b1c6d5",
+                "version": 3
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(keystore.decrypt("secret").unwrap().to_string(), KEY);
+    }
+
+    #[test]
+    fn test_external_signer() {
+        // A signer holding the key on the other side of the socket
+        let path = ::std::env::temp_dir().join(format!("signer-{}.sock", Uuid::new_v4()));
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let writer = stream.try_clone().unwrap();
+            serve_signer(&private_key(), BufReader::new(stream), writer).unwrap();
+        });
+        let signer = ExternalSigner::connect(&path);
+        fs::remove_file(&path).unwrap();
+        let signer = signer.unwrap();
+        assert_eq!(
+            signer.address().unwrap(),
+            KeyPair::from_privkey(private_key()).address()
+        );
+        let (tx, expected) = signed_transaction();
+        let signed = tx.build_unverified_by(&signer).unwrap();
+        assert_eq!(signed.get_signature(), &expected[..]);
+    }
+
+    #[test]
+    fn test_exec_signer_spec() {
+        match signer_from_spec("exec:signer --key 'unterminated", None) {
+            Err(ToolError::InvalidInput(message)) => assert!(message.contains("signer command")),
+            _ => panic!("expect an invalid command"),
+        }
+        // The quoted path with a space is one program
+        match signer_from_spec("exec:'/nonexistent dir/signer' --key a", None) {
+            Err(ToolError::Stdio(err)) => assert_eq!(err.kind(), ::std::io::ErrorKind::NotFound),
+            _ => panic!("expect a missing program"),
+        }
+    }
+}
@@ -145,6 +145,62 @@ pub fn contract(input: TokenStream) -> TokenStream {
                     )
                 }
             }
+            impl<T> AsyncContractCall<JsonRpcResponse, ToolError> for #name<T>
+                where T: AsyncClientExt<JsonRpcResponse, ToolError>
+            {
+                fn prepare_call_args(
+                    &self,
+                    name: &str,
+                    values: &[&str],
+                    to_addr: Option<Address>,
+                ) -> Result<(String, String), ToolError> {
+                    let values = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    let code = contract_encode_input(&self.contract, name, values.as_slice(), false)?;
+                    let code = format!("0x{}", code);
+                    let to_address = to_addr.unwrap_or(self.address);
+                    let to_address = format!("{:?}", to_address);
+                    Ok((code, to_address))
+                }
+
+                fn contract_send_tx(
+                    &self,
+                    name: &str,
+                    values: &[&str],
+                    quota: Option<u64>,
+                    to_addr: Option<Address>,
+                ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+                    let (code, to_address) = match <Self as AsyncContractCall<JsonRpcResponse, ToolError>>::prepare_call_args(self, name, values, to_addr) {
+                        Ok(args) => args,
+                        Err(err) => return Box::new(future::err(err)),
+                    };
+                    let tx_options = TransactionOptions::new()
+                        .set_code(code.as_str())
+                        .set_address(to_address.as_str())
+                        .set_quota(quota);
+                    self.client.send_raw_transaction(
+                        tx_options,
+                    )
+                }
+
+                fn contract_call(
+                    &self,
+                    name: &str,
+                    values: &[&str],
+                    to_addr: Option<Address>,
+                    height: Option<&str>,
+                ) -> ResponseFuture<JsonRpcResponse, ToolError> {
+                    let (code, to_address) = match <Self as AsyncContractCall<JsonRpcResponse, ToolError>>::prepare_call_args(self, name, values, to_addr) {
+                        Ok(args) => args,
+                        Err(err) => return Box::new(future::err(err)),
+                    };
+                    self.client.call(
+                        None,
+                        to_address.as_str(),
+                        Some(code.as_str()),
+                        height.unwrap_or_else(|| "latest"),
+                    )
+                }
+            }
             impl<T> #trait_name<T, JsonRpcResponse, ToolError> for #name<T>
                  where T: ClientExt<JsonRpcResponse, ToolError>,
              {